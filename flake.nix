@@ -34,6 +34,7 @@
                         shellHook = ''
                                 git submodule update --init --recursive
                                 export COOKIE_KEY=1267b291500365c42043e04bc69cf24a31495bd8936fc8d6794283675e288fad755971922d45cf1ca0b438df4fc847f39cb0b2aceb3a45673eff231cddb88dc9
+                                export KV_SECRET_KEYS=1:7f3c2a9e5b8d41f6a0c4e2b7d9f1a3c5e8b0d2f4a6c8e1b3d5f7a9c2e4b6d8f0
                         '';
                     };
                 }
@@ -0,0 +1,182 @@
+// Warms the first bytes of upcoming feed videos into Cache Storage and serves
+// the player's range requests from them, see utils::video_prefetch
+
+const CACHE_NAME = "yral-video-prefetch-v2";
+const INDEX_URL = "/__video-prefetch-index__";
+// total size of a video whose prefix is cached
+const TOTAL_SIZE_HEADER = "X-Prefetch-Total-Size";
+const DEFAULT_PREFIX_BYTES = 1024 * 1024;
+const DEFAULT_BUDGET_BYTES = 64 * 1024 * 1024;
+// the stream base url is passed on registration
+const STREAM_BASE = new URL(self.location.href).searchParams.get("stream_base");
+const STREAM_ORIGIN = STREAM_BASE ? new URL(STREAM_BASE).origin : null;
+
+// index and cache mutations are serialized through this chain
+let queue = Promise.resolve();
+
+function enqueue(task) {
+  const next = queue.then(task, task);
+  queue = next.catch(() => {});
+  return next;
+}
+
+async function loadIndex(cache) {
+  const res = await cache.match(INDEX_URL);
+  if (!res) {
+    return { entries: {} };
+  }
+  try {
+    return await res.json();
+  } catch (err) {
+    return { entries: {} };
+  }
+}
+
+async function saveIndex(cache, index) {
+  await cache.put(
+    INDEX_URL,
+    new Response(JSON.stringify(index), { headers: { "Content-Type": "application/json" } })
+  );
+}
+
+// Cache API can't store partial responses, so the prefix is stored as a
+// full response along with the size of the whole video
+async function fetchPrefixIntoCache(cache, url, prefixBytes) {
+  const res = await fetch(url, {
+    mode: "cors",
+    credentials: "omit",
+    headers: { Range: `bytes=0-${prefixBytes - 1}` },
+  });
+  if (res.status !== 200 && res.status !== 206) {
+    throw new Error(`prefetch of ${url} failed with ${res.status}`);
+  }
+  const body = await res.arrayBuffer();
+  const total =
+    res.status === 206
+      ? Number((res.headers.get("Content-Range") || "").split("/")[1])
+      : body.byteLength;
+  if (!Number.isFinite(total) || total <= 0) {
+    throw new Error(`prefetch of ${url} returned no size`);
+  }
+  await cache.put(
+    url,
+    new Response(body, {
+      headers: {
+        "Content-Type": res.headers.get("Content-Type") || "video/mp4",
+        [TOTAL_SIZE_HEADER]: String(total),
+      },
+    })
+  );
+  return body.byteLength;
+}
+
+// single `bytes=start-[end]` range, the only form media elements send
+function parseRange(header) {
+  const match = /^bytes=(\d+)-(\d*)$/.exec(header.trim());
+  if (!match) {
+    return null;
+  }
+  return { start: Number(match[1]), end: match[2] === "" ? null : Number(match[2]) };
+}
+
+// answers a range request from the cached prefix, the player requests the
+// rest of the video from the network once it reaches the end of the prefix
+async function serveRange(req, range) {
+  const cache = await caches.open(CACHE_NAME);
+  const cached = await cache.match(req.url);
+  if (!cached) {
+    return fetch(req);
+  }
+  const total = Number(cached.headers.get(TOTAL_SIZE_HEADER));
+  const prefix = await cached.arrayBuffer();
+  if (range.start >= prefix.byteLength) {
+    return fetch(req);
+  }
+  const end = Math.min(range.end === null ? total - 1 : range.end, prefix.byteLength - 1);
+  return new Response(prefix.slice(range.start, end + 1), {
+    status: 206,
+    headers: {
+      "Accept-Ranges": "bytes",
+      "Content-Type": cached.headers.get("Content-Type"),
+      "Content-Length": String(end - range.start + 1),
+      "Content-Range": `bytes ${range.start}-${end}/${total}`,
+    },
+  });
+}
+
+async function evict(cache, index, budgetBytes) {
+  const entries = Object.entries(index.entries).sort((a, b) => a[1].lastUsed - b[1].lastUsed);
+  let total = entries.reduce((sum, [, e]) => sum + e.bytes, 0);
+  for (const [uid, entry] of entries) {
+    if (total <= budgetBytes) {
+      break;
+    }
+    await Promise.all(entry.urls.map((url) => cache.delete(url)));
+    delete index.entries[uid];
+    total -= entry.bytes;
+  }
+}
+
+async function prefetch({ uid, videoUrl, prefixBytes, budgetBytes }) {
+  const cache = await caches.open(CACHE_NAME);
+  const index = await loadIndex(cache);
+  const existing = index.entries[uid];
+  if (existing) {
+    existing.lastUsed = Date.now();
+    await saveIndex(cache, index);
+    return existing.bytes;
+  }
+
+  const bytes = await fetchPrefixIntoCache(cache, videoUrl, prefixBytes || DEFAULT_PREFIX_BYTES);
+
+  index.entries[uid] = { urls: [videoUrl], bytes, lastUsed: Date.now() };
+  await evict(cache, index, budgetBytes || DEFAULT_BUDGET_BYTES);
+  await saveIndex(cache, index);
+  return bytes;
+}
+
+self.addEventListener("install", (event) => {
+  event.waitUntil(self.skipWaiting());
+});
+
+self.addEventListener("activate", (event) => {
+  event.waitUntil(
+    caches
+      .keys()
+      .then((keys) =>
+        Promise.all(
+          keys
+            .filter((key) => key.startsWith("yral-video-prefetch-") && key !== CACHE_NAME)
+            .map((key) => caches.delete(key))
+        )
+      )
+      .then(() => clients.claim())
+  );
+});
+
+self.addEventListener("message", (event) => {
+  const msg = event.data;
+  const port = event.ports[0];
+  if (!msg || msg.type !== "prefetch" || !port) {
+    return;
+  }
+  event.waitUntil(
+    enqueue(() => prefetch(msg)).then(
+      (bytes) => port.postMessage({ ok: true, bytes }),
+      (err) => port.postMessage({ ok: false, error: String(err) })
+    )
+  );
+});
+
+self.addEventListener("fetch", (event) => {
+  const req = event.request;
+  if (req.method !== "GET" || !STREAM_ORIGIN || new URL(req.url).origin !== STREAM_ORIGIN) {
+    return;
+  }
+  // only a prefix is cached, whole video requests always go to the network
+  const range = req.headers.has("range") ? parseRange(req.headers.get("range")) : null;
+  if (!range) {
+    return;
+  }
+  event.respondWith(serveRange(req, range).catch(() => fetch(req)));
+});
@@ -1,5 +1,7 @@
 use crate::error_template::{AppError, ErrorTemplate};
+use component::auth_providers::account_merge::{AccountMergeNotice, AccountMergeSummary};
 use component::content_upload::AuthorizedUserToSeedContent;
+use component::share_meta::DEFAULT_PREVIEW_IMAGE;
 use component::{base_route::BaseRoute, nav::NavBar};
 use leptos::prelude::*;
 use leptos_meta::*;
@@ -35,8 +37,10 @@ use state::app_type::AppType;
 use state::{audio_state::AudioState, content_seed_client::ContentSeedClient};
 use utils::event_streaming::events::HistoryCtx;
 use utils::event_streaming::EventHistory;
+use utils::i18n::provide_i18n;
 use utils::mixpanel::state::MixpanelState;
 use utils::types::PostParams;
+use utils::video_prefetch::VideoPrefetchCtx;
 use yral_canisters_common::Canisters;
 
 #[component]
@@ -47,12 +51,27 @@ fn NotFound() -> impl IntoView {
 }
 
 #[component(transparent)]
-fn GoogleAuthRedirectHandlerRoute() -> impl MatchNestedRoutes + Clone {
+fn AuthRedirectHandlerRoute() -> impl MatchNestedRoutes + Clone {
+    let path = path!("/auth/:provider/redirect");
+    #[cfg(any(feature = "oauth-ssr", feature = "oauth-hydrate"))]
+    {
+        use page::yral_auth_redirect::AuthRedirectHandler;
+        view! { <Route path view=AuthRedirectHandler /> }.into_inner()
+    }
+    #[cfg(not(any(feature = "oauth-ssr", feature = "oauth-hydrate")))]
+    {
+        view! { <Route path view=NotFound /> }.into_inner()
+    }
+}
+
+/// Redirect URL registered with Yral Auth before per-provider routes existed
+#[component(transparent)]
+fn LegacyAuthRedirectHandlerRoute() -> impl MatchNestedRoutes + Clone {
     let path = path!("/auth/google_redirect");
     #[cfg(any(feature = "oauth-ssr", feature = "oauth-hydrate"))]
     {
-        use page::yral_auth_redirect::YralAuthRedirectHandler;
-        view! { <Route path view=YralAuthRedirectHandler /> }.into_inner()
+        use page::yral_auth_redirect::AuthRedirectHandler;
+        view! { <Route path view=AuthRedirectHandler /> }.into_inner()
     }
     #[cfg(not(any(feature = "oauth-ssr", feature = "oauth-hydrate")))]
     {
@@ -60,10 +79,24 @@ fn GoogleAuthRedirectHandlerRoute() -> impl MatchNestedRoutes + Clone {
     }
 }
 
+/// [`BaseRoute`] serving only the routes enabled for the tenant
+#[component]
+fn TenantBaseRoute() -> impl IntoView {
+    let app_state: AppState = expect_context();
+    let location = use_location();
+    let enabled = Memo::new(move |_| location.pathname.with(|path| app_state.route_enabled(path)));
+
+    view! {
+        <Show when=enabled fallback=|| view! { <NotFound /> }>
+            <BaseRoute />
+        </Show>
+    }
+}
+
 pub fn shell(options: LeptosOptions) -> impl IntoView {
     view! {
         <!DOCTYPE html>
-        <html lang="en">
+        <html>
             <head>
                 <meta charset="utf-8" />
                 <meta name="viewport" content="width=device-width, initial-scale=1" />
@@ -78,10 +111,11 @@ pub fn shell(options: LeptosOptions) -> impl IntoView {
 
                 <AutoReload options=options.clone() />
                 <HashedStylesheet id="leptos" options=options.clone() />
-                <Meta property="og:title" content="YRAL - World's first social on Bitcoin" />
-                <Meta property="og:image" content="/img/common/preview.webp" />
                 <HydrationScripts options />
                 <MetaTags />
+                // fallbacks for pages without a link preview, crawlers use the first tag they see
+                <meta property="og:title" content="YRAL - World's first social on Bitcoin" />
+                <meta property="og:image" content=DEFAULT_PREVIEW_IMAGE />
             </head>
             <body>
                 <App />
@@ -97,15 +131,18 @@ pub fn App() -> impl IntoView {
     let app_type = AppType::select();
     let app_state = AppState::from_type(&app_type);
     provide_context(app_state.clone());
+    let i18n = provide_i18n();
 
     // Existing context providers
     provide_context(Canisters::default());
     provide_context(ContentSeedClient::default());
-    provide_context(PostViewCtx::new());
+    provide_context(PostViewCtx::new().with_history_depth(app_state.feed_history_depth));
     provide_context(ProfilePostsContext::default());
     provide_context(AuthorizedUserToSeedContent::default());
+    provide_context(AccountMergeNotice::default());
     provide_context(AudioState::default());
     provide_context(PostDetailsCacheCtx::default());
+    provide_context(VideoPrefetchCtx::default());
 
     // History Tracking
     let history_ctx = HistoryCtx::default();
@@ -129,8 +166,21 @@ pub fn App() -> impl IntoView {
     {
         provide_context(EventHistory::default());
     }
+    #[cfg(feature = "ga4")]
+    let ga4_script = app_state.analytics.ga4_measurement_id.map(|id| {
+        let config = format!(
+            "window.dataLayer=window.dataLayer||[];function gtag(){{dataLayer.push(arguments);}}gtag('js',new Date());gtag('config','{id}');"
+        );
+        view! {
+            <Script async_="true" src=format!("https://www.googletagmanager.com/gtag/js?id={id}") />
+            <Script>{config}</Script>
+        }
+    });
+    #[cfg(not(feature = "ga4"))]
+    let ga4_script = ();
 
     view! {
+        <Html attr:lang=move || i18n.locale().code() />
         <Title text=app_state.name />
 
         // Favicon
@@ -151,6 +201,8 @@ pub fn App() -> impl IntoView {
 
         // Meta
         <Meta name="apple-mobile-web-app-title" content=app_state.name />
+        <Meta name="theme-color" content=app_state.theme_color />
+        {ga4_script}
 
         // App manifest
         <Link rel="manifest" href=format!("/{}/manifest.json", app_state.asset_path()) />
@@ -159,8 +211,9 @@ pub fn App() -> impl IntoView {
             <main class="bg-black" id="body">
                 <Routes fallback=|| view! { <NotFound /> }.into_view()>
                     // auth redirect routes exist outside main context
-                    <GoogleAuthRedirectHandlerRoute />
-                    <ParentRoute path=path!("") view=BaseRoute>
+                    <AuthRedirectHandlerRoute />
+                    <LegacyAuthRedirectHandlerRoute />
+                    <ParentRoute path=path!("") view=TenantBaseRoute>
                     <Route path=path!("/") view=YralRootPage />
                         // TODO: enable when SATS are added back
                         // <Route
@@ -222,6 +275,7 @@ pub fn App() -> impl IntoView {
             <nav>
                 <NavBar />
             </nav>
+            <AccountMergeSummary />
         </Router>
     }
 }
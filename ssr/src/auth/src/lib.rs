@@ -1,6 +1,7 @@
 // TEMP
 #![allow(dead_code)]
 
+pub mod passkey;
 #[cfg(feature = "ssr")]
 pub mod server_impl;
 
@@ -16,7 +17,11 @@ use serde::{Deserialize, Serialize};
 use web_time::Duration;
 use yral_canisters_common::utils::time::current_epoch;
 
-use consts::auth::DELEGATION_MAX_AGE;
+use consts::auth::{DELEGATION_MAX_AGE, UPLOAD_DELEGATION_MAX_AGE, VIDEO_GEN_DELEGATION_MAX_AGE};
+use passkey::{
+    CreationChallengeResponse, PublicKeyCredential, RegisterPublicKeyCredential,
+    RequestChallengeResponse,
+};
 use yral_types::delegated_identity::DelegatedIdentityWire;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -25,18 +30,60 @@ pub struct AnonymousIdentity {
     pub refresh_token: String,
 }
 
-fn delegate_identity_with_max_age(
+/// Lifetime and canister restrictions of a delegation
+///
+/// delegations handed to other services should be as narrow as their use,
+/// so a leaked delegation can't be used for anything else
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DelegationPolicy {
+    pub max_age: Duration,
+    /// Canisters the delegation can call, any canister if `None`
+    pub targets: Option<Vec<Principal>>,
+}
+
+impl DelegationPolicy {
+    /// The user's own session, can call any canister
+    pub fn session() -> Self {
+        Self {
+            max_age: DELEGATION_MAX_AGE,
+            targets: None,
+        }
+    }
+
+    /// Publishing posts through the upload worker, which registers the post
+    /// with the post service on behalf of the user's canister (the user info
+    /// service for users migrated off individual canisters)
+    pub fn upload(user_canister: Principal, post_service: Principal) -> Self {
+        Self {
+            max_age: UPLOAD_DELEGATION_MAX_AGE,
+            targets: Some(vec![user_canister, post_service]),
+        }
+    }
+
+    /// Generating a video through the off-chain agent, which counts the request
+    /// against the user's `rate_limits` and, for DOLR, charges it on `ledger`
+    ///
+    /// SATS are charged off-chain, so no ledger is needed for them
+    pub fn video_generation(rate_limits: Principal, ledger: Option<Principal>) -> Self {
+        Self {
+            max_age: VIDEO_GEN_DELEGATION_MAX_AGE,
+            targets: Some([Some(rate_limits), ledger].into_iter().flatten().collect()),
+        }
+    }
+}
+
+pub fn delegate_identity_with_policy(
     from: &impl Identity,
-    max_age: Duration,
+    policy: &DelegationPolicy,
 ) -> DelegatedIdentityWire {
     let to_secret = k256::SecretKey::random(&mut OsRng);
     let to_identity = Secp256k1Identity::from_private_key(to_secret.clone());
-    let expiry = current_epoch() + max_age;
+    let expiry = current_epoch() + policy.max_age;
     let expiry_ns = expiry.as_nanos() as u64;
     let delegation = Delegation {
         pubkey: to_identity.public_key().unwrap(),
         expiration: expiry_ns,
-        targets: None,
+        targets: policy.targets.clone(),
     };
     let sig = from.sign_delegation(&delegation).unwrap();
     let signed_delegation = SignedDelegation {
@@ -54,19 +101,31 @@ fn delegate_identity_with_max_age(
     }
 }
 
+/// Delegation for the user's own session, see [`DelegationPolicy::session`]
 pub fn delegate_identity(from: &impl Identity) -> DelegatedIdentityWire {
-    delegate_identity_with_max_age(from, DELEGATION_MAX_AGE)
+    delegate_identity_with_policy(from, &DelegationPolicy::session())
 }
 
-pub fn delegate_short_lived_identity(from: &impl Identity) -> DelegatedIdentityWire {
-    let max_age = Duration::from_secs(24 * 60 * 60); // 1 day
-    delegate_identity_with_max_age(from, max_age)
+/// An active login of the user, see [`list_sessions`]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SessionInfo {
+    pub id: String,
+    pub device: String,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub last_seen_ms: u64,
+    /// Whether this is the session making the request
+    pub current: bool,
 }
 
 #[derive(Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
 pub struct RefreshTokenLegacy {
     principal: Principal,
     expiry_epoch_ms: u128,
+    /// Issued to a user who logged in (e.g with a partner identity provider),
+    /// rather than an anonymous visitor
+    #[serde(default)]
+    registered: bool,
 }
 
 /// Generate an anonymous identity if refresh token is not set
@@ -93,3 +152,81 @@ pub async fn extract_identity() -> Result<Option<DelegatedIdentityWire>, ServerF
 pub async fn logout_identity() -> Result<DelegatedIdentityWire, ServerFnError> {
     server_impl::logout_identity_impl().await
 }
+
+/// Active sessions of the logged in user, the current one first
+#[server(endpoint = "list_sessions", input = Json, output = Json)]
+pub async fn list_sessions() -> Result<Vec<SessionInfo>, ServerFnError> {
+    server_impl::list_sessions_impl().await
+}
+
+/// Logs out one of the user's sessions, its refresh token is rejected from now on
+#[server(endpoint = "revoke_session", input = Json, output = Json)]
+pub async fn revoke_session(session_id: String) -> Result<(), ServerFnError> {
+    server_impl::revoke_session_impl(session_id).await
+}
+
+/// Logs out every session of the user except the current one,
+/// returns how many were revoked
+#[server(endpoint = "revoke_other_sessions", input = Json, output = Json)]
+pub async fn revoke_other_sessions() -> Result<usize, ServerFnError> {
+    server_impl::revoke_other_sessions_impl().await
+}
+
+/// Forgets the caller's identity, sessions and passkeys, once their account is deleted
+#[server(endpoint = "delete_user_data", input = Json, output = Json)]
+pub async fn delete_user_data() -> Result<(), ServerFnError> {
+    server_impl::delete_user_data_impl().await
+}
+
+/// Starts registering a passkey, see [`register_passkey`]
+#[server(endpoint = "passkey_registration_options", input = Json, output = Json)]
+pub async fn passkey_registration_options() -> Result<CreationChallengeResponse, ServerFnError> {
+    server_impl::passkey_registration_options_impl().await
+}
+
+/// Binds a newly created passkey to the caller's account,
+/// or to a new one logged in to if the caller is anonymous
+#[server(endpoint = "register_passkey", input = Json, output = Json)]
+pub async fn register_passkey(
+    registration: RegisterPublicKeyCredential,
+) -> Result<DelegatedIdentityWire, ServerFnError> {
+    server_impl::register_passkey_impl(registration).await
+}
+
+/// Starts logging in with a passkey, see [`login_with_passkey`]
+#[server(endpoint = "passkey_login_options", input = Json, output = Json)]
+pub async fn passkey_login_options() -> Result<RequestChallengeResponse, ServerFnError> {
+    server_impl::passkey_login_options_impl().await
+}
+
+/// Logs in to the account a passkey is bound to
+#[server(endpoint = "login_with_passkey", input = Json, output = Json)]
+pub async fn login_with_passkey(
+    credential: PublicKeyCredential,
+) -> Result<DelegatedIdentityWire, ServerFnError> {
+    server_impl::login_with_passkey_impl(credential).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn policy_restricts_delegation() {
+        let base = Secp256k1Identity::from_private_key(k256::SecretKey::random(&mut OsRng));
+        let user_canister = Principal::from_slice(&[1; 10]);
+        let post_service = Principal::from_slice(&[2; 10]);
+        let policy = DelegationPolicy::upload(user_canister, post_service);
+
+        let wire = delegate_identity_with_policy(&base, &policy);
+        let delegation = &wire.delegation_chain.last().unwrap().delegation;
+        assert_eq!(delegation.targets, Some(vec![user_canister, post_service]));
+        let max_expiry = (current_epoch() + policy.max_age).as_nanos() as u64;
+        assert!(delegation.expiration <= max_expiry);
+
+        let session = delegate_identity(&base);
+        let delegation = &session.delegation_chain.last().unwrap().delegation;
+        assert_eq!(delegation.targets, None);
+        assert!(delegation.expiration > max_expiry);
+    }
+}
@@ -0,0 +1,8 @@
+//! WebAuthn messages exchanged with the browser when creating or using a passkey
+//!
+//! these are the JSON forms used by webauthn-rs, binary fields are base64url
+//! encoded (without padding), as in the `toJSON()` form of WebAuthn credentials
+pub use webauthn_rs_proto::{
+    CreationChallengeResponse, PublicKeyCredential, RegisterPublicKeyCredential,
+    RequestChallengeResponse,
+};
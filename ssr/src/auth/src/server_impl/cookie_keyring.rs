@@ -0,0 +1,216 @@
+use std::{env, sync::Arc};
+
+use axum::{
+    http::{header, HeaderMap, HeaderValue},
+    response::IntoResponse,
+};
+use axum_extra::extract::{cookie::Key, PrivateCookieJar, SignedCookieJar};
+use thiserror::Error;
+
+const PRIMARY_KEY_ENV: &str = "COOKIE_KEY";
+const FALLBACK_KEYS_ENV: &str = "COOKIE_KEY_FALLBACKS";
+
+#[derive(Error, Debug)]
+pub enum CookieKeyError {
+    #[error("`{0}` is required")]
+    Missing(&'static str),
+    #[error("`{var}` key #{index} is not valid hex")]
+    InvalidHex { var: &'static str, index: usize },
+    #[error("`{var}` key #{index} must be 64 bytes (128 hex characters)")]
+    TooShort { var: &'static str, index: usize },
+}
+
+fn parse_key(var: &'static str, index: usize, hex_key: &str) -> Result<Key, CookieKeyError> {
+    let raw = hex::decode(hex_key.trim()).map_err(|_| CookieKeyError::InvalidHex { var, index })?;
+    Key::try_from(raw.as_slice()).map_err(|_| CookieKeyError::TooShort { var, index })
+}
+
+/// `headers` with the cookies set by `resealed` appended to the request cookies
+fn with_request_cookies(headers: &HeaderMap, resealed: impl IntoResponse) -> HeaderMap {
+    let resp = resealed.into_response();
+    let mut headers = headers.clone();
+    for set_cookie in resp.headers().get_all(header::SET_COOKIE) {
+        let pair = set_cookie
+            .to_str()
+            .ok()
+            .and_then(|set_cookie| set_cookie.split(';').next())
+            .and_then(|pair| HeaderValue::from_str(pair).ok());
+        if let Some(pair) = pair {
+            headers.append(header::COOKIE, pair);
+        }
+    }
+    headers
+}
+
+/// Keys for signed and private cookies
+///
+/// New cookies are always signed (or encrypted) with the primary key,
+/// fallback keys are only accepted when reading, so the primary key can be
+/// rotated without logging everyone out
+#[derive(Clone)]
+pub struct CookieKeyring {
+    primary: Key,
+    fallbacks: Arc<Vec<Key>>,
+}
+
+impl CookieKeyring {
+    pub fn new(primary: Key, fallbacks: Vec<Key>) -> Self {
+        Self {
+            primary,
+            fallbacks: Arc::new(fallbacks),
+        }
+    }
+
+    /// `COOKIE_KEY` is the primary key, `COOKIE_KEY_FALLBACKS` an optional comma separated list
+    /// keys are hex encoded, 64 bytes each
+    pub fn from_env() -> Result<Self, CookieKeyError> {
+        let primary = env::var(PRIMARY_KEY_ENV)
+            .map_err(|_| CookieKeyError::Missing(PRIMARY_KEY_ENV))
+            .and_then(|key| parse_key(PRIMARY_KEY_ENV, 0, &key))?;
+        let fallbacks = env::var(FALLBACK_KEYS_ENV)
+            .unwrap_or_default()
+            .split(',')
+            .filter(|key| !key.trim().is_empty())
+            .enumerate()
+            .map(|(index, key)| parse_key(FALLBACK_KEYS_ENV, index, key))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self::new(primary, fallbacks))
+    }
+
+    pub fn primary(&self) -> &Key {
+        &self.primary
+    }
+
+    /// Signed cookies of a request, and the names of those only valid under a fallback key
+    ///
+    /// those are readable from the jar, but writing it back doesn't upgrade them:
+    /// a request only carries name and value, so each owner re-issues its cookie
+    /// with its usual attributes
+    pub fn signed_jar(&self, headers: &HeaderMap) -> (SignedCookieJar, Vec<String>) {
+        let jar = SignedCookieJar::from_headers(headers, self.primary.clone());
+        let mut resealed = SignedCookieJar::new(self.primary.clone());
+        let mut upgrade = vec![];
+        for key in self.fallbacks.iter() {
+            for cookie in SignedCookieJar::from_headers(headers, key.clone()).iter() {
+                let name = cookie.name().to_string();
+                if jar.get(&name).is_none() && !upgrade.contains(&name) {
+                    resealed = resealed.add(cookie);
+                    upgrade.push(name);
+                }
+            }
+        }
+        if upgrade.is_empty() {
+            return (jar, upgrade);
+        }
+
+        let headers = with_request_cookies(headers, resealed);
+        (
+            SignedCookieJar::from_headers(&headers, self.primary.clone()),
+            upgrade,
+        )
+    }
+
+    /// Private cookies of a request, see [`Self::signed_jar`]
+    pub fn private_jar(&self, headers: &HeaderMap) -> (PrivateCookieJar, Vec<String>) {
+        let jar = PrivateCookieJar::from_headers(headers, self.primary.clone());
+        let mut resealed = PrivateCookieJar::new(self.primary.clone());
+        let mut upgrade = vec![];
+        for key in self.fallbacks.iter() {
+            for cookie in PrivateCookieJar::from_headers(headers, key.clone()).iter() {
+                let name = cookie.name().to_string();
+                if jar.get(&name).is_none() && !upgrade.contains(&name) {
+                    resealed = resealed.add(cookie);
+                    upgrade.push(name);
+                }
+            }
+        }
+        if upgrade.is_empty() {
+            return (jar, upgrade);
+        }
+
+        let headers = with_request_cookies(headers, resealed);
+        (
+            PrivateCookieJar::from_headers(&headers, self.primary.clone()),
+            upgrade,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{http::header, response::IntoResponse};
+    use axum_extra::extract::cookie::Cookie;
+
+    use super::*;
+
+    /// Request headers carrying the cookies set by `jar`
+    fn request_headers(jar: impl IntoResponse) -> HeaderMap {
+        let resp = jar.into_response();
+        let cookies: Vec<_> = resp
+            .headers()
+            .get_all(header::SET_COOKIE)
+            .iter()
+            .map(|set_cookie| {
+                let set_cookie = set_cookie.to_str().unwrap();
+                set_cookie.split(';').next().unwrap().to_string()
+            })
+            .collect();
+
+        let mut headers = HeaderMap::new();
+        headers.insert(header::COOKIE, cookies.join("; ").parse().unwrap());
+        headers
+    }
+
+    #[test]
+    fn fallback_signed_cookies_are_upgraded() {
+        let (old, new, unknown) = (
+            Key::from(&[1u8; 64]),
+            Key::from(&[2u8; 64]),
+            Key::from(&[3u8; 64]),
+        );
+        let headers = request_headers(
+            SignedCookieJar::new(old.clone())
+                .add(Cookie::new("old", "1"))
+                .add(Cookie::new("other", "2")),
+        );
+
+        let (jar, mut upgrade) = CookieKeyring::new(new.clone(), vec![old]).signed_jar(&headers);
+        upgrade.sort();
+        assert_eq!(upgrade, ["old", "other"]);
+        assert_eq!(jar.get("old").unwrap().value(), "1");
+        // writing the jar back must not re-issue cookies stripped of their attributes
+        let resp = jar.into_response();
+        assert!(resp.headers().get(header::SET_COOKIE).is_none());
+
+        let reissued = request_headers(
+            SignedCookieJar::new(new.clone())
+                .add(Cookie::new("old", "1"))
+                .add(Cookie::new("other", "2")),
+        );
+        let (jar, upgrade) = CookieKeyring::new(new, vec![]).signed_jar(&reissued);
+        assert!(upgrade.is_empty());
+        assert_eq!(jar.get("old").unwrap().value(), "1");
+        assert_eq!(jar.get("other").unwrap().value(), "2");
+
+        let (jar, upgrade) = CookieKeyring::new(unknown, vec![]).signed_jar(&headers);
+        assert!(upgrade.is_empty());
+        assert!(jar.get("old").is_none());
+    }
+
+    #[test]
+    fn fallback_private_cookies_are_upgraded() {
+        let (old, new) = (Key::from(&[1u8; 64]), Key::from(&[2u8; 64]));
+        let headers =
+            request_headers(PrivateCookieJar::new(old.clone()).add(Cookie::new("a", "1")));
+
+        let (jar, upgrade) = CookieKeyring::new(new, vec![old]).private_jar(&headers);
+        assert_eq!(upgrade, ["a"]);
+        assert_eq!(jar.get("a").unwrap().value(), "1");
+        assert!(jar
+            .into_response()
+            .headers()
+            .get(header::SET_COOKIE)
+            .is_none());
+    }
+}
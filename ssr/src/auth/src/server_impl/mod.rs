@@ -1,28 +1,49 @@
+pub mod cookie_keyring;
+pub mod passkey;
+#[cfg(feature = "oauth-ssr")]
+pub mod provider;
+#[cfg(feature = "oauth-ssr")]
+pub mod refresh_cache;
+pub mod sessions;
 pub mod store;
 #[cfg(feature = "oauth-ssr")]
 pub mod yral;
 
-use std::env;
+use std::time::Duration;
 
-use axum::response::IntoResponse;
+use axum::{http::HeaderMap, response::IntoResponse};
 use axum_extra::extract::{
-    cookie::{Cookie, Key, SameSite},
-    SignedCookieJar,
+    cookie::{Cookie, SameSite},
+    PrivateCookieJar, SignedCookieJar,
 };
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
 use candid::Principal;
 use http::header;
 use ic_agent::{identity::Secp256k1Identity, Identity};
 use k256::elliptic_curve::JwkEcKey;
 use leptos::prelude::*;
-use leptos_axum::{extract_with_state, ResponseOptions};
+use leptos_axum::{extract, ResponseOptions};
 use rand_chacha::rand_core::OsRng;
+use utils::host::get_host;
 use yral_canisters_common::utils::time::current_epoch;
 
 use consts::auth::{REFRESH_MAX_AGE, REFRESH_TOKEN_COOKIE};
 
-use crate::{delegate_identity, AnonymousIdentity};
+use crate::{
+    delegate_identity,
+    passkey::{
+        CreationChallengeResponse, PublicKeyCredential, RegisterPublicKeyCredential,
+        RequestChallengeResponse,
+    },
+    AnonymousIdentity, SessionInfo,
+};
 
-use self::store::{KVStore, KVStoreImpl};
+use self::cookie_keyring::CookieKeyring;
+use self::passkey::{PasskeyAccount, PasskeyConfig, PasskeyConfigs, PasskeyError};
+use self::store::{
+    encrypted_kv::{EncryptedKV, SecretKeyring},
+    KVStore, KVStoreImpl,
+};
 use yral_types::delegated_identity::DelegatedIdentityWire;
 
 use super::RefreshTokenLegacy;
@@ -39,16 +60,39 @@ fn set_cookies(resp: &ResponseOptions, jar: impl IntoResponse) {
     }
 }
 
-fn cookie_key() -> Key {
+fn cookie_keyring() -> CookieKeyring {
     use_context().unwrap_or_else(|| {
         // HACK: https://github.com/leptos-rs/leptos/issues/2112
-        let cookie_key_str = env::var("COOKIE_KEY").expect("`COOKIE_KEY` is required!");
-        let raw_key =
-            hex::decode(cookie_key_str).expect("Invalid `COOKIE_KEY` (must be length 128 hex)");
-        Key::from(&raw_key)
+        CookieKeyring::from_env().expect("cookie keys are validated at startup")
     })
 }
 
+/// Signed cookies of the current request, and the names of those signed with a fallback key
+async fn signed_jar() -> Result<(SignedCookieJar, Vec<String>), ServerFnError> {
+    let headers: HeaderMap = extract().await?;
+    Ok(cookie_keyring().signed_jar(&headers))
+}
+
+/// Signed cookies of the current request, verified against every accepted cookie key
+pub async fn extract_signed_jar() -> Result<SignedCookieJar, ServerFnError> {
+    Ok(signed_jar().await?.0)
+}
+
+/// Private cookies of the current request, decrypted with every accepted cookie key
+///
+/// they only live for the length of a login or passkey prompt, so they aren't re-issued
+pub async fn extract_private_jar() -> Result<PrivateCookieJar, ServerFnError> {
+    let headers: HeaderMap = extract().await?;
+    Ok(cookie_keyring().private_jar(&headers).0)
+}
+
+/// KV store for identity secrets, encrypted at rest
+fn identity_kv() -> EncryptedKV {
+    let kv: KVStoreImpl = expect_context();
+    let keyring: SecretKeyring = expect_context();
+    EncryptedKV::new(kv, keyring)
+}
+
 pub fn extract_principal_from_cookie_legacy(
     jar: &SignedCookieJar,
 ) -> Result<Option<Principal>, ServerFnError> {
@@ -62,8 +106,28 @@ pub fn extract_principal_from_cookie_legacy(
     Ok(Some(token.principal))
 }
 
+/// Principal the refresh cookie was issued to, without exchanging it
+///
+/// the cookie is signed by us, so its claims can be read without verifying the token
+pub fn principal_from_refresh_cookie(jar: &SignedCookieJar) -> Option<Principal> {
+    if let Ok(Some(principal)) = extract_principal_from_cookie_legacy(jar) {
+        return Some(principal);
+    }
+
+    #[derive(serde::Deserialize)]
+    struct Subject {
+        sub: Principal,
+    }
+    let token = jar.get(REFRESH_TOKEN_COOKIE)?;
+    let claims = token.value().split('.').nth(1)?;
+    let claims = URL_SAFE_NO_PAD.decode(claims).ok()?;
+    serde_json::from_slice::<Subject>(&claims)
+        .ok()
+        .map(|subject| subject.sub)
+}
+
 async fn fetch_identity_from_kv(
-    kv: &KVStoreImpl,
+    kv: &impl KVStore,
     principal: Principal,
 ) -> Result<Option<k256::SecretKey>, ServerFnError> {
     let Some(identity_jwk) = kv.read(principal.to_text()).await? else {
@@ -75,7 +139,7 @@ async fn fetch_identity_from_kv(
 
 pub async fn try_extract_identity_legacy(
     jar: &SignedCookieJar,
-    kv: &KVStoreImpl,
+    kv: &impl KVStore,
 ) -> Result<Option<k256::SecretKey>, ServerFnError> {
     let Some(principal) = extract_principal_from_cookie_legacy(jar)? else {
         return Ok(None);
@@ -84,17 +148,105 @@ pub async fn try_extract_identity_legacy(
 }
 
 async fn generate_and_save_identity_legacy(
-    kv: &KVStoreImpl,
+    kv: &impl KVStore,
+) -> Result<Secp256k1Identity, ServerFnError> {
+    save_identity_legacy(kv, k256::SecretKey::random(&mut OsRng)).await
+}
+
+async fn save_identity_legacy(
+    kv: &impl KVStore,
+    base_identity_key: k256::SecretKey,
 ) -> Result<Secp256k1Identity, ServerFnError> {
-    let base_identity_key = k256::SecretKey::random(&mut OsRng);
     let base_identity = Secp256k1Identity::from_private_key(base_identity_key.clone());
     let principal = base_identity.sender().unwrap();
 
     let base_jwk = base_identity_key.to_jwk_string();
-    kv.write(principal.to_text(), base_jwk.to_string()).await?;
+    // the identity is unreachable once the refresh token expires
+    kv.write_with_ttl(principal.to_text(), base_jwk.to_string(), REFRESH_MAX_AGE)
+        .await?;
     Ok(base_identity)
 }
 
+/// Legacy identities still in use are renewed once less than this much of their lifetime remains
+const LEGACY_RENEW_THRESHOLD: Duration = Duration::from_secs(REFRESH_MAX_AGE.as_secs() / 2);
+
+/// A legacy refresh token with a fresh expiry, if the caller's is due for renewal.
+/// The identity it refers to is saved again so both expire together
+async fn renew_identity_legacy(
+    jar: &SignedCookieJar,
+    kv: &impl KVStore,
+) -> Result<Option<String>, ServerFnError> {
+    let Some(cookie) = jar.get(REFRESH_TOKEN_COOKIE) else {
+        return Ok(None);
+    };
+    let Ok(token) = serde_json::from_str::<RefreshTokenLegacy>(cookie.value()) else {
+        return Ok(None);
+    };
+    let remaining = token
+        .expiry_epoch_ms
+        .saturating_sub(current_epoch().as_millis());
+    if remaining == 0 || remaining > LEGACY_RENEW_THRESHOLD.as_millis() {
+        return Ok(None);
+    }
+    let Some(base_identity_key) = fetch_identity_from_kv(kv, token.principal).await? else {
+        return Ok(None);
+    };
+
+    let identity = save_identity_legacy(kv, base_identity_key).await?;
+    Ok(Some(refresh_token_legacy(
+        identity.sender().unwrap(),
+        token.registered,
+    )))
+}
+
+fn refresh_token_legacy(principal: Principal, registered: bool) -> String {
+    serde_json::to_string(&RefreshTokenLegacy {
+        principal,
+        expiry_epoch_ms: (current_epoch() + REFRESH_MAX_AGE).as_millis(),
+        registered,
+    })
+    .unwrap()
+}
+
+/// Refresh token of an anonymous identity held by the server
+fn legacy_refresh_token(principal: Principal) -> String {
+    refresh_token_legacy(principal, false)
+}
+
+/// Refresh token of a logged in user whose identity is held by the server
+fn registered_refresh_token(principal: Principal) -> String {
+    refresh_token_legacy(principal, true)
+}
+
+/// Principal of a logged in user whose identity is held by the server
+///
+/// these users don't hold a Yral Auth refresh token, but count as logged in all the same
+pub fn registered_principal_from_cookie(jar: &SignedCookieJar) -> Option<Principal> {
+    let cookie = jar.get(REFRESH_TOKEN_COOKIE)?;
+    let token: RefreshTokenLegacy = serde_json::from_str(cookie.value()).ok()?;
+    (token.registered && current_epoch().as_millis() <= token.expiry_epoch_ms)
+        .then_some(token.principal)
+}
+
+/// Base identity key of a user of an external identity provider,
+/// generated on their first login and kept without an expiry
+#[cfg(feature = "oauth-ssr")]
+async fn external_subject_identity(
+    kv: &impl KVStore,
+    issuer: &str,
+    subject: &str,
+) -> Result<k256::SecretKey, ServerFnError> {
+    let key = format!("external-subject:{issuer}:{subject}");
+    if let Some(jwk) = kv.read(key.clone()).await? {
+        return Ok(k256::SecretKey::from_jwk_str(&jwk)?);
+    }
+
+    let base_identity_key = k256::SecretKey::random(&mut OsRng);
+    kv.write(key, base_identity_key.to_jwk_string().to_string())
+        .await?;
+    Ok(base_identity_key)
+}
+
 fn identity_from_jwk(id: &JwkEcKey) -> Result<Secp256k1Identity, ServerFnError> {
     let base_identity_key = k256::SecretKey::from_jwk(id)?;
     let base_identity: Secp256k1Identity =
@@ -102,6 +254,16 @@ fn identity_from_jwk(id: &JwkEcKey) -> Result<Secp256k1Identity, ServerFnError>
     Ok(base_identity)
 }
 
+/// Matches the attributes of the refresh cookie, browsers ignore removals that don't
+fn refresh_cookie_removal() -> Cookie<'static> {
+    Cookie::build(REFRESH_TOKEN_COOKIE)
+        .secure(true)
+        .path("/")
+        .same_site(SameSite::None)
+        .partitioned(true)
+        .build()
+}
+
 pub fn update_user_identity(
     response_opts: &ResponseOptions,
     mut jar: SignedCookieJar,
@@ -130,7 +292,7 @@ async fn extract_identity_legacy(
         return Ok(None);
     }
 
-    let kv: KVStoreImpl = expect_context();
+    let kv = identity_kv();
     let Some(id) = try_extract_identity_legacy(jar, &kv).await? else {
         return Ok(None);
     };
@@ -141,14 +303,15 @@ async fn extract_identity_legacy(
     Ok(Some(id))
 }
 
-pub async fn extract_identity_impl() -> Result<Option<DelegatedIdentityWire>, ServerFnError> {
-    let key = cookie_key();
-    let jar: SignedCookieJar = extract_with_state(&key).await?;
-
+async fn extract_identity_from_token(
+    jar: &SignedCookieJar,
+    refresh_token: &Cookie<'static>,
+) -> Result<Option<DelegatedIdentityWire>, ServerFnError> {
     #[cfg(not(feature = "oauth-ssr"))]
     {
-        let kv: KVStoreImpl = expect_context();
-        let base_identity = if let Some(identity) = try_extract_identity_legacy(&jar, &kv).await? {
+        _ = refresh_token;
+        let kv = identity_kv();
+        let base_identity = if let Some(identity) = try_extract_identity_legacy(jar, &kv).await? {
             Secp256k1Identity::from_private_key(identity)
         } else {
             return Ok(None);
@@ -159,49 +322,297 @@ pub async fn extract_identity_impl() -> Result<Option<DelegatedIdentityWire>, Se
 
     #[cfg(feature = "oauth-ssr")]
     {
-        use openidconnect::{reqwest::async_http_client, RefreshToken};
         use yral::YralOAuthClient;
 
-        let Some(refresh_token) = jar.get(REFRESH_TOKEN_COOKIE) else {
-            return Ok(None);
-        };
-
-        if let Some(id) = extract_identity_legacy(&jar, &refresh_token).await? {
+        if let Some(id) = extract_identity_legacy(jar, refresh_token).await? {
             return Ok(Some(id));
         }
 
         let oauth2: YralOAuthClient = expect_context();
-        let token_res = oauth2
-            .exchange_refresh_token(&RefreshToken::new(refresh_token.value().to_string()))
-            .request_async(async_http_client)
-            .await?;
-
-        let id_token = token_res
-            .extra_fields()
-            .id_token()
-            .expect("Yral Auth V2 must return an ID token");
-        let id_claims = id_token.claims(&yral::token_verifier(), yral::no_op_nonce_verifier)?;
-        let identity = id_claims.additional_claims().ext_delegated_identity.clone();
+        let kv: KVStoreImpl = expect_context();
+        let exchange = yral::exchange_refresh_token(oauth2, kv, refresh_token.value().to_string());
+        let identity =
+            refresh_cache::cached_exchange(&identity_kv(), refresh_token.value(), exchange).await?;
 
         Ok(Some(identity))
     }
 }
 
+pub async fn extract_identity_impl() -> Result<Option<DelegatedIdentityWire>, ServerFnError> {
+    let (jar, upgrade) = signed_jar().await?;
+
+    let Some(refresh_token) = jar.get(REFRESH_TOKEN_COOKIE) else {
+        return Ok(None);
+    };
+
+    let kv = identity_kv();
+    let session_id = sessions::session_id(refresh_token.value());
+    if sessions::is_revoked(&kv, &session_id).await? {
+        let resp: ResponseOptions = expect_context();
+        // a revoked session is logged out, the caller starts over as anonymous
+        set_cookies(&resp, jar.remove(refresh_cookie_removal()));
+        return Ok(None);
+    }
+
+    let identity = extract_identity_from_token(&jar, &refresh_token).await?;
+    if let Some(identity) = &identity {
+        let principal = Principal::self_authenticating(&identity.from_key);
+        let mut session_id = session_id;
+        let renewed = renew_identity_legacy(&jar, &kv).await?;
+        if let Some(renewed) = &renewed {
+            let renewed_id = sessions::session_id(renewed);
+            sessions::rename(&kv, principal, &session_id, &renewed_id).await?;
+            session_id = renewed_id;
+        }
+        if renewed.is_some() || upgrade.iter().any(|name| name == REFRESH_TOKEN_COOKIE) {
+            // a renewed token is signed with the primary key as well
+            let resp: ResponseOptions = expect_context();
+            let refresh_token = renewed.unwrap_or_else(|| refresh_token.value().to_string());
+            update_user_identity(&resp, jar, refresh_token)?;
+        }
+
+        if let Err(e) = sessions::touch(&kv, principal, &session_id).await {
+            tracing::warn!("failed to record session: {e}");
+        }
+    }
+
+    Ok(identity)
+}
+
+/// Principal and session id of the caller
+async fn current_session() -> Result<(Principal, String), ServerFnError> {
+    let jar = extract_signed_jar().await?;
+    let refresh_token = jar
+        .get(REFRESH_TOKEN_COOKIE)
+        .ok_or_else(|| ServerFnError::new("not logged in"))?;
+    let identity = extract_identity_impl()
+        .await?
+        .ok_or_else(|| ServerFnError::new("not logged in"))?;
+
+    Ok((
+        Principal::self_authenticating(&identity.from_key),
+        sessions::session_id(refresh_token.value()),
+    ))
+}
+
+pub async fn list_sessions_impl() -> Result<Vec<SessionInfo>, ServerFnError> {
+    let (principal, session_id) = current_session().await?;
+    sessions::list(&identity_kv(), principal, &session_id).await
+}
+
+pub async fn revoke_session_impl(session_id: String) -> Result<(), ServerFnError> {
+    let (principal, _) = current_session().await?;
+    if !sessions::revoke(&identity_kv(), principal, &session_id).await? {
+        return Err(ServerFnError::new("session not found"));
+    }
+    Ok(())
+}
+
+pub async fn revoke_other_sessions_impl() -> Result<usize, ServerFnError> {
+    let (principal, session_id) = current_session().await?;
+    sessions::revoke_others(&identity_kv(), principal, &session_id).await
+}
+
+pub async fn delete_user_data_impl() -> Result<(), ServerFnError> {
+    let (principal, _) = current_session().await?;
+    let kv = identity_kv();
+    passkey::delete_account(&kv, principal).await?;
+    sessions::revoke_all(&kv, principal).await?;
+    kv.delete(principal.to_text()).await?;
+    Ok(())
+}
+
+/// Matches the attributes of the refresh cookie, see [`refresh_cookie_removal`]
+fn passkey_ceremony_cookie(ceremony_id: String) -> Cookie<'static> {
+    Cookie::build((passkey::CEREMONY_COOKIE, ceremony_id))
+        .http_only(true)
+        .secure(true)
+        .path("/")
+        .same_site(SameSite::None)
+        .partitioned(true)
+        .max_age(passkey::CEREMONY_TIMEOUT.try_into().unwrap())
+        .build()
+}
+
+/// Relying party of the tenant the request was made to
+fn passkey_config() -> Result<PasskeyConfig, ServerFnError> {
+    let configs: PasskeyConfigs = expect_context();
+    Ok(configs.for_host(&get_host())?.clone())
+}
+
+/// Hands the id of a pending passkey prompt to the browser, in a private cookie
+async fn set_passkey_ceremony(ceremony_id: String) -> Result<(), ServerFnError> {
+    let jar = extract_private_jar().await?;
+    let resp: ResponseOptions = expect_context();
+    set_cookies(&resp, jar.add(passkey_ceremony_cookie(ceremony_id)));
+    Ok(())
+}
+
+/// Id of the pending passkey prompt, which can only be answered once
+async fn take_passkey_ceremony() -> Result<String, ServerFnError> {
+    let jar = extract_private_jar().await?;
+    let ceremony_id = jar
+        .get(passkey::CEREMONY_COOKIE)
+        .ok_or(PasskeyError::CeremonyExpired)?;
+
+    let resp: ResponseOptions = expect_context();
+    set_cookies(&resp, jar.remove(passkey_ceremony_cookie(String::new())));
+    Ok(ceremony_id.value().to_string())
+}
+
+/// Account a new passkey of the caller is bound to, their own unless they are
+/// anonymous and the server doesn't hold their key, then the passkey starts a new account
+async fn passkey_owner(
+    jar: &SignedCookieJar,
+    kv: &impl KVStore,
+) -> Result<(Principal, PasskeyAccount), ServerFnError> {
+    if let Some(principal) = extract_principal_from_cookie_legacy(jar)? {
+        if let Some(account) = passkey::account(kv, principal).await? {
+            return Ok((principal, account));
+        }
+        if let Some(key) = fetch_identity_from_kv(kv, principal).await? {
+            return Ok((principal, PasskeyAccount::server(&key)));
+        }
+    }
+
+    #[cfg(feature = "oauth-ssr")]
+    if let Some(refresh_token) = jar.get(REFRESH_TOKEN_COOKIE) {
+        let oauth2: yral::YralOAuthClient = expect_context();
+        // anonymous Yral Auth identities can't be logged in to again
+        let claims = yral::verify_refresh_token(&oauth2, refresh_token.value())
+            .ok()
+            .filter(|claims| !claims.ext_is_anonymous);
+        if let Some(claims) = claims {
+            let account = PasskeyAccount::YralAuth {
+                refresh_token: refresh_token.value().to_string(),
+            };
+            return Ok((claims.sub, account));
+        }
+    }
+
+    let key = k256::SecretKey::random(&mut OsRng);
+    let principal = Secp256k1Identity::from_private_key(key.clone())
+        .sender()
+        .unwrap();
+    Ok((principal, PasskeyAccount::server(&key)))
+}
+
+/// Logs in to a passkey account, with a registered refresh token if the server holds
+/// its identity, or a renewed Yral Auth refresh token otherwise
+async fn login_passkey_account(
+    kv: &impl KVStore,
+    principal: Principal,
+    account: PasskeyAccount,
+) -> Result<DelegatedIdentityWire, ServerFnError> {
+    let jar = extract_signed_jar().await?;
+    let resp: ResponseOptions = expect_context();
+
+    match account {
+        PasskeyAccount::Server { base_identity_jwk } => {
+            let base_identity_key = k256::SecretKey::from_jwk_str(&base_identity_jwk)?;
+            let identity = save_identity_legacy(kv, base_identity_key).await?;
+            update_user_identity(&resp, jar, registered_refresh_token(principal))?;
+            Ok(delegate_identity(&identity))
+        }
+        #[cfg(feature = "oauth-ssr")]
+        PasskeyAccount::YralAuth { refresh_token } => {
+            let oauth2: yral::YralOAuthClient = expect_context();
+            let (identity, renewed) =
+                yral::renew_refresh_token(oauth2, expect_context(), refresh_token)
+                    .await
+                    .map_err(|e| {
+                        tracing::warn!("failed to renew the Yral Auth login of a passkey: {e}");
+                        ServerFnError::new("passkey login expired, please log in with Yral again")
+                    })?;
+            let account = PasskeyAccount::YralAuth {
+                refresh_token: renewed.clone(),
+            };
+            passkey::save_account(kv, principal, &account).await?;
+            update_user_identity(&resp, jar, renewed)?;
+            Ok(identity)
+        }
+        #[cfg(not(feature = "oauth-ssr"))]
+        PasskeyAccount::YralAuth { .. } => Err(ServerFnError::new("Yral Auth is not enabled")),
+    }
+}
+
+pub async fn passkey_registration_options_impl() -> Result<CreationChallengeResponse, ServerFnError>
+{
+    let config = passkey_config()?;
+    let kv = identity_kv();
+    let jar = extract_signed_jar().await?;
+    let (principal, account) = passkey_owner(&jar, &kv).await?;
+
+    let (ceremony_id, options) =
+        passkey::start_registration(&kv, &config, principal, account).await?;
+    set_passkey_ceremony(ceremony_id).await?;
+    Ok(options)
+}
+
+pub async fn register_passkey_impl(
+    registration: RegisterPublicKeyCredential,
+) -> Result<DelegatedIdentityWire, ServerFnError> {
+    let ceremony_id = take_passkey_ceremony().await?;
+    let config = passkey_config()?;
+    let kv = identity_kv();
+    let (principal, account) =
+        passkey::finish_registration(&kv, &config, &ceremony_id, &registration).await?;
+
+    // a caller already logged in to the account keeps their session
+    let jar = extract_signed_jar().await?;
+    let logged_in = matches!(account, PasskeyAccount::YralAuth { .. })
+        || registered_principal_from_cookie(&jar) == Some(principal);
+    if logged_in {
+        let identity = extract_identity_impl()
+            .await?
+            .filter(|identity| Principal::self_authenticating(&identity.from_key) == principal);
+        if let Some(identity) = identity {
+            return Ok(identity);
+        }
+    }
+
+    login_passkey_account(&kv, principal, account).await
+}
+
+pub async fn passkey_login_options_impl() -> Result<RequestChallengeResponse, ServerFnError> {
+    let config = passkey_config()?;
+    let (ceremony_id, options) = passkey::start_login(&identity_kv(), &config).await?;
+    set_passkey_ceremony(ceremony_id).await?;
+    Ok(options)
+}
+
+pub async fn login_with_passkey_impl(
+    credential: PublicKeyCredential,
+) -> Result<DelegatedIdentityWire, ServerFnError> {
+    let ceremony_id = take_passkey_ceremony().await?;
+    let config = passkey_config()?;
+    let kv = identity_kv();
+    let (principal, account) =
+        passkey::finish_login(&kv, &config, &ceremony_id, &credential).await?;
+
+    login_passkey_account(&kv, principal, account).await
+}
+
 pub async fn logout_identity_impl() -> Result<DelegatedIdentityWire, ServerFnError> {
-    let key = cookie_key();
-    let jar: SignedCookieJar = extract_with_state(&key).await?;
+    let jar = extract_signed_jar().await?;
+
+    // the old refresh token must not outlive the logout
+    if let (Some(refresh_token), Some(principal)) = (
+        jar.get(REFRESH_TOKEN_COOKIE),
+        principal_from_refresh_cookie(&jar),
+    ) {
+        let session_id = sessions::session_id(refresh_token.value());
+        sessions::revoke(&identity_kv(), principal, &session_id).await?;
+    }
+
     let resp: ResponseOptions = expect_context();
 
     #[cfg(not(feature = "oauth-ssr"))]
     {
-        let kv: KVStoreImpl = expect_context();
+        let kv = identity_kv();
         let identity = generate_and_save_identity_legacy(&kv).await?;
 
-        let refresh_token = serde_json::to_string(&RefreshTokenLegacy {
-            principal: identity.sender().unwrap(),
-            expiry_epoch_ms: (current_epoch() + REFRESH_MAX_AGE).as_millis(),
-        })
-        .unwrap();
+        let refresh_token = legacy_refresh_token(identity.sender().unwrap());
 
         update_user_identity(&resp, jar, refresh_token)?;
 
@@ -213,6 +624,11 @@ pub async fn logout_identity_impl() -> Result<DelegatedIdentityWire, ServerFnErr
     #[cfg(feature = "oauth-ssr")]
     {
         use openidconnect::{reqwest::async_http_client, OAuth2TokenResponse};
+
+        if let Some(old_refresh_token) = jar.get(REFRESH_TOKEN_COOKIE) {
+            refresh_cache::invalidate(&identity_kv(), old_refresh_token.value()).await?;
+        }
+
         let oauth_client: yral::YralOAuthClient = expect_context();
         let token = oauth_client
             .exchange_client_credentials()
@@ -227,7 +643,10 @@ pub async fn logout_identity_impl() -> Result<DelegatedIdentityWire, ServerFnErr
             .refresh_token()
             .expect("Yral Auth V2 must return a refresh token");
 
-        let id_claims = id_token.claims(&yral::token_verifier(), yral::no_op_nonce_verifier)?;
+        let id_claims = id_token.claims(
+            &yral::token_verifier(&oauth_client),
+            yral::no_op_nonce_verifier,
+        )?;
         let identity = id_claims.additional_claims().ext_delegated_identity.clone();
         update_user_identity(&resp, jar, refresh_token.secret().clone())?;
 
@@ -237,23 +656,18 @@ pub async fn logout_identity_impl() -> Result<DelegatedIdentityWire, ServerFnErr
 
 pub async fn generate_anonymous_identity_if_required_impl(
 ) -> Result<Option<AnonymousIdentity>, ServerFnError> {
-    let key = cookie_key();
-    let jar: SignedCookieJar = extract_with_state(&key).await?;
+    let jar = extract_signed_jar().await?;
     #[cfg(not(feature = "oauth-ssr"))]
     {
         if extract_principal_from_cookie_legacy(&jar)?.is_some() {
             return Ok(None);
         }
 
-        let kv: KVStoreImpl = expect_context();
+        let kv = identity_kv();
         let identity = generate_and_save_identity_legacy(&kv).await?;
         Ok(Some(AnonymousIdentity {
             identity: delegate_identity(&identity).into(),
-            refresh_token: serde_json::to_string(&RefreshTokenLegacy {
-                principal: identity.sender().unwrap(),
-                expiry_epoch_ms: (current_epoch() + REFRESH_MAX_AGE).as_millis(),
-            })
-            .unwrap(),
+            refresh_token: legacy_refresh_token(identity.sender().unwrap()),
         }))
     }
 
@@ -287,7 +701,10 @@ pub async fn generate_anonymous_identity_if_required_impl(
             .refresh_token()
             .expect("Yral Auth V2 must return a refresh token");
 
-        let id_claims = id_token.claims(&yral::token_verifier(), yral::no_op_nonce_verifier)?;
+        let id_claims = id_token.claims(
+            &yral::token_verifier(&oauth_client),
+            yral::no_op_nonce_verifier,
+        )?;
         let identity = id_claims.additional_claims().ext_delegated_identity.clone();
 
         Ok(Some(AnonymousIdentity {
@@ -298,8 +715,13 @@ pub async fn generate_anonymous_identity_if_required_impl(
 }
 
 pub async fn set_anonymous_identity_cookie_impl(refresh_jwt: String) -> Result<(), ServerFnError> {
-    let key = cookie_key();
-    let jar: SignedCookieJar = extract_with_state(&key).await?;
+    // the value is signed as given, it must not be able to claim a login
+    if let Ok(token) = serde_json::from_str::<RefreshTokenLegacy>(&refresh_jwt) {
+        if token.registered || cfg!(feature = "oauth-ssr") {
+            return Err(ServerFnError::new("not an anonymous refresh token"));
+        }
+    }
+    let jar = extract_signed_jar().await?;
 
     let resp: ResponseOptions = expect_context();
 
@@ -307,3 +729,143 @@ pub async fn set_anonymous_identity_cookie_impl(refresh_jwt: String) -> Result<(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use axum_extra::extract::cookie::Key;
+
+    use self::store::in_memory_kv::InMemoryKV;
+
+    use super::*;
+
+    fn jar_with_token(token: &RefreshTokenLegacy) -> SignedCookieJar {
+        SignedCookieJar::new(Key::from(&[7u8; 64])).add(Cookie::new(
+            REFRESH_TOKEN_COOKIE,
+            serde_json::to_string(token).unwrap(),
+        ))
+    }
+
+    #[tokio::test]
+    async fn legacy_identity_is_restored_from_kv() {
+        let kv = KVStoreImpl::InMemory(InMemoryKV::new());
+        let identity = generate_and_save_identity_legacy(&kv).await.unwrap();
+        let principal = identity.sender().unwrap();
+
+        let jar = jar_with_token(&RefreshTokenLegacy {
+            principal,
+            expiry_epoch_ms: (current_epoch() + REFRESH_MAX_AGE).as_millis(),
+            registered: false,
+        });
+        let key = try_extract_identity_legacy(&jar, &kv)
+            .await
+            .unwrap()
+            .expect("identity should be stored");
+        let restored = Secp256k1Identity::from_private_key(key);
+        assert_eq!(restored.sender().unwrap(), principal);
+    }
+
+    #[tokio::test]
+    async fn expired_legacy_token_is_ignored() {
+        let kv = KVStoreImpl::InMemory(InMemoryKV::new());
+        let identity = generate_and_save_identity_legacy(&kv).await.unwrap();
+
+        let jar = jar_with_token(&RefreshTokenLegacy {
+            principal: identity.sender().unwrap(),
+            expiry_epoch_ms: current_epoch().as_millis() - 1,
+            registered: false,
+        });
+        assert!(try_extract_identity_legacy(&jar, &kv)
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn missing_legacy_identity_is_not_an_error() {
+        let kv = KVStoreImpl::InMemory(InMemoryKV::new());
+        let jar = jar_with_token(&RefreshTokenLegacy {
+            principal: Principal::anonymous(),
+            expiry_epoch_ms: (current_epoch() + REFRESH_MAX_AGE).as_millis(),
+            registered: false,
+        });
+        assert!(try_extract_identity_legacy(&jar, &kv)
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn legacy_identity_is_renewed_once_half_expired() {
+        let kv = KVStoreImpl::InMemory(InMemoryKV::new());
+        let identity = generate_and_save_identity_legacy(&kv).await.unwrap();
+        let principal = identity.sender().unwrap();
+
+        let fresh = jar_with_token(&RefreshTokenLegacy {
+            principal,
+            expiry_epoch_ms: (current_epoch() + REFRESH_MAX_AGE).as_millis(),
+            registered: false,
+        });
+        assert!(renew_identity_legacy(&fresh, &kv).await.unwrap().is_none());
+
+        let aging = jar_with_token(&RefreshTokenLegacy {
+            principal,
+            expiry_epoch_ms: (current_epoch() + LEGACY_RENEW_THRESHOLD / 2).as_millis(),
+            registered: true,
+        });
+        let renewed = renew_identity_legacy(&aging, &kv)
+            .await
+            .unwrap()
+            .expect("token should be renewed");
+        let renewed: RefreshTokenLegacy = serde_json::from_str(&renewed).unwrap();
+        assert_eq!(renewed.principal, principal);
+        assert!(renewed.registered, "renewal must keep the login");
+        assert!(renewed.expiry_epoch_ms > (current_epoch() + LEGACY_RENEW_THRESHOLD).as_millis());
+
+        let jar = jar_with_token(&renewed);
+        assert!(try_extract_identity_legacy(&jar, &kv)
+            .await
+            .unwrap()
+            .is_some());
+    }
+
+    #[test]
+    fn only_registered_tokens_count_as_logged_in() {
+        let principal = Principal::from_slice(&[3; 29]);
+        let live = (current_epoch() + REFRESH_MAX_AGE).as_millis();
+        let jar = |registered, expiry_epoch_ms| {
+            jar_with_token(&RefreshTokenLegacy {
+                principal,
+                expiry_epoch_ms,
+                registered,
+            })
+        };
+
+        assert_eq!(
+            registered_principal_from_cookie(&jar(true, live)),
+            Some(principal)
+        );
+        assert_eq!(registered_principal_from_cookie(&jar(false, live)), None);
+        let expired = current_epoch().as_millis() - 1;
+        assert_eq!(registered_principal_from_cookie(&jar(true, expired)), None);
+    }
+
+    #[test]
+    fn update_user_identity_sets_refresh_cookie() {
+        let resp = ResponseOptions::default();
+        let jar = SignedCookieJar::new(Key::from(&[7u8; 64]));
+        update_user_identity(&resp, jar, "refresh-jwt".into()).unwrap();
+
+        let parts = resp.0.read();
+        let set_cookies: Vec<_> = parts.headers.get_all(header::SET_COOKIE).iter().collect();
+        assert_eq!(set_cookies.len(), 1);
+        let cookie = Cookie::parse(set_cookies[0].to_str().unwrap()).unwrap();
+        assert_eq!(cookie.name(), REFRESH_TOKEN_COOKIE);
+        assert_ne!(cookie.value(), "refresh-jwt", "the cookie must be signed");
+        assert_eq!(cookie.http_only(), Some(true));
+        assert_eq!(cookie.secure(), Some(true));
+        assert_eq!(cookie.partitioned(), Some(true));
+        assert_eq!(cookie.same_site(), Some(SameSite::None));
+        assert_eq!(cookie.path(), Some("/"));
+        assert_eq!(cookie.max_age(), Some(REFRESH_MAX_AGE.try_into().unwrap()));
+    }
+}
@@ -0,0 +1,440 @@
+//! Passkey (WebAuthn) registration and login, verified by webauthn-rs
+//!
+//! The state of a pending ceremony is kept in the KV store under a random id,
+//! handed to the browser in a private cookie. It is taken atomically,
+//! so a ceremony can only be completed once
+use std::{sync::Arc, time::Duration};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use candid::Principal;
+use leptos::prelude::*;
+use rand_chacha::rand_core::{OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use webauthn_rs::prelude::{
+    DiscoverableAuthentication, DiscoverableKey, Passkey, PasskeyRegistration, Url, Uuid, Webauthn,
+    WebauthnBuilder, WebauthnError,
+};
+
+use crate::passkey::{
+    CreationChallengeResponse, PublicKeyCredential, RegisterPublicKeyCredential,
+    RequestChallengeResponse,
+};
+
+use super::store::KVStore;
+
+pub const CEREMONY_COOKIE: &str = "passkey-ceremony";
+/// How long the user has to complete a passkey prompt
+pub const CEREMONY_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+const CEREMONY_PREFIX: &str = "passkey-ceremony";
+const CREDENTIAL_PREFIX: &str = "passkey-credential";
+const ACCOUNT_PREFIX: &str = "passkey-account";
+
+#[derive(Error, Debug)]
+pub enum PasskeyError {
+    #[error("invalid passkey origin `{0}`")]
+    InvalidOrigin(String),
+    #[error("passkeys are not available on `{0}`")]
+    UnsupportedHost(String),
+    #[error("passkey request expired, please try again")]
+    CeremonyExpired,
+    #[error("passkey is already registered")]
+    AlreadyRegistered,
+    #[error("unknown passkey")]
+    UnknownCredential,
+    #[error("passkey was used twice at once, please try again")]
+    ConcurrentUse,
+    #[error("passkey verification failed: {0}")]
+    Webauthn(#[from] WebauthnError),
+}
+
+/// Relying party passkeys are scoped to
+#[derive(Clone)]
+pub struct PasskeyConfig {
+    webauthn: Arc<Webauthn>,
+    rp_name: String,
+    /// hosts of the allowed origins
+    hosts: Vec<String>,
+}
+
+impl PasskeyConfig {
+    /// `rp_id` is the domain passkeys are bound to, it must be the host
+    /// (or a parent domain) of every origin allowed to use them, e.g. `https://yral.com`
+    pub fn new(rp_id: &str, rp_name: &str, origins: &[String]) -> Result<Self, PasskeyError> {
+        let origins = origins
+            .iter()
+            .map(|origin| {
+                Url::parse(origin).map_err(|_| PasskeyError::InvalidOrigin(origin.clone()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let (first, rest) = origins
+            .split_first()
+            .ok_or_else(|| PasskeyError::InvalidOrigin(String::new()))?;
+
+        let builder = rest.iter().fold(
+            WebauthnBuilder::new(rp_id, first)?.rp_name(rp_name),
+            |builder, origin| builder.append_allowed_origin(origin),
+        );
+
+        let hosts = origins
+            .iter()
+            .filter_map(|origin| origin.host_str().map(str::to_string))
+            .collect();
+
+        Ok(Self {
+            webauthn: Arc::new(builder.build()?),
+            rp_name: rp_name.into(),
+            hosts,
+        })
+    }
+}
+
+/// Relying party of each tenant, picked by the host of the request
+#[derive(Clone)]
+pub struct PasskeyConfigs(Arc<Vec<PasskeyConfig>>);
+
+impl PasskeyConfigs {
+    pub fn new(configs: Vec<PasskeyConfig>) -> Self {
+        Self(Arc::new(configs))
+    }
+
+    /// Config whose allowed origins include `host`, the port is ignored
+    pub fn for_host(&self, host: &str) -> Result<&PasskeyConfig, PasskeyError> {
+        let hostname = host.split(':').next().unwrap_or_default();
+        self.0
+            .iter()
+            .find(|config| config.hosts.iter().any(|allowed| allowed == hostname))
+            .ok_or_else(|| PasskeyError::UnsupportedHost(host.into()))
+    }
+}
+
+/// How a passkey account is logged in to
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PasskeyAccount {
+    /// Base identity key held by the server, as a JWK
+    Server { base_identity_jwk: String },
+    /// Identity held by Yral Auth, reached with a refresh token of the user
+    /// that is renewed on every passkey login
+    YralAuth { refresh_token: String },
+}
+
+impl PasskeyAccount {
+    pub fn server(base_identity_key: &k256::SecretKey) -> Self {
+        Self::Server {
+            base_identity_jwk: base_identity_key.to_jwk_string().to_string(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+enum Ceremony {
+    Registration {
+        principal: Principal,
+        account: PasskeyAccount,
+        state: PasskeyRegistration,
+    },
+    Login(DiscoverableAuthentication),
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredCredential {
+    principal: Principal,
+    passkey: Passkey,
+}
+
+fn ceremony_key(ceremony_id: &str) -> String {
+    format!("{CEREMONY_PREFIX}:{ceremony_id}")
+}
+
+fn credential_key(credential_id: &[u8]) -> String {
+    format!(
+        "{CREDENTIAL_PREFIX}:{}",
+        URL_SAFE_NO_PAD.encode(credential_id)
+    )
+}
+
+fn account_key(principal: Principal) -> String {
+    format!("{ACCOUNT_PREFIX}:{}", principal.to_text())
+}
+
+/// WebAuthn user handle of an account, stable so authenticators keep one passkey per account
+fn user_handle(principal: Principal) -> Uuid {
+    let digest = Sha256::digest(principal.as_slice());
+    Uuid::from_bytes(digest[..16].try_into().unwrap())
+}
+
+/// Keeps the state of a ceremony until it's answered, returns its id
+async fn start_ceremony(kv: &impl KVStore, ceremony: &Ceremony) -> Result<String, ServerFnError> {
+    let mut ceremony_id = [0u8; 32];
+    OsRng.fill_bytes(&mut ceremony_id);
+    let ceremony_id = URL_SAFE_NO_PAD.encode(ceremony_id);
+
+    kv.write_with_ttl(
+        ceremony_key(&ceremony_id),
+        serde_json::to_string(ceremony)?,
+        CEREMONY_TIMEOUT,
+    )
+    .await?;
+    Ok(ceremony_id)
+}
+
+async fn take_ceremony(kv: &impl KVStore, ceremony_id: &str) -> Result<Ceremony, ServerFnError> {
+    let ceremony = kv
+        .take(ceremony_key(ceremony_id))
+        .await?
+        .ok_or(PasskeyError::CeremonyExpired)?;
+    Ok(serde_json::from_str(&ceremony)?)
+}
+
+/// Starts creating a passkey for `principal`, returns the ceremony id and the browser options
+pub async fn start_registration(
+    kv: &impl KVStore,
+    config: &PasskeyConfig,
+    principal: Principal,
+    account: PasskeyAccount,
+) -> Result<(String, CreationChallengeResponse), ServerFnError> {
+    let (options, state) = config
+        .webauthn
+        .start_passkey_registration(
+            user_handle(principal),
+            &principal.to_text(),
+            &format!("{} account", config.rp_name),
+            None,
+        )
+        .map_err(PasskeyError::from)?;
+    let ceremony = Ceremony::Registration {
+        principal,
+        account,
+        state,
+    };
+
+    Ok((start_ceremony(kv, &ceremony).await?, options))
+}
+
+/// Verifies a new passkey and binds it to the account its ceremony was started for
+pub async fn finish_registration(
+    kv: &impl KVStore,
+    config: &PasskeyConfig,
+    ceremony_id: &str,
+    registration: &RegisterPublicKeyCredential,
+) -> Result<(Principal, PasskeyAccount), ServerFnError> {
+    let Ceremony::Registration {
+        principal,
+        account,
+        state,
+    } = take_ceremony(kv, ceremony_id).await?
+    else {
+        return Err(PasskeyError::CeremonyExpired.into());
+    };
+    let passkey = config
+        .webauthn
+        .finish_passkey_registration(registration, &state)
+        .map_err(PasskeyError::from)?;
+
+    let credential = StoredCredential { principal, passkey };
+    let inserted = kv
+        .write_if_absent(
+            credential_key(credential.passkey.cred_id().as_ref()),
+            serde_json::to_string(&credential)?,
+            None,
+        )
+        .await?;
+    if !inserted {
+        return Err(PasskeyError::AlreadyRegistered.into());
+    }
+    save_account(kv, principal, &account).await?;
+
+    Ok((principal, account))
+}
+
+/// Starts logging in with any passkey of this site, returns the ceremony id and the browser options
+pub async fn start_login(
+    kv: &impl KVStore,
+    config: &PasskeyConfig,
+) -> Result<(String, RequestChallengeResponse), ServerFnError> {
+    let (options, state) = config
+        .webauthn
+        .start_discoverable_authentication()
+        .map_err(PasskeyError::from)?;
+    Ok((start_ceremony(kv, &Ceremony::Login(state)).await?, options))
+}
+
+/// Verifies a passkey login, returning the account the passkey is bound to
+///
+/// the signature counter is advanced atomically, so of two concurrent logins
+/// with the same assertion at most one succeeds
+pub async fn finish_login(
+    kv: &impl KVStore,
+    config: &PasskeyConfig,
+    ceremony_id: &str,
+    credential: &PublicKeyCredential,
+) -> Result<(Principal, PasskeyAccount), ServerFnError> {
+    let Ceremony::Login(state) = take_ceremony(kv, ceremony_id).await? else {
+        return Err(PasskeyError::CeremonyExpired.into());
+    };
+
+    let key = credential_key(credential.raw_id.as_ref());
+    let stored = kv
+        .read(key.clone())
+        .await?
+        .ok_or(PasskeyError::UnknownCredential)?;
+    let mut record: StoredCredential = serde_json::from_str(&stored)?;
+    let result = config
+        .webauthn
+        .finish_discoverable_authentication(
+            credential,
+            state,
+            &[DiscoverableKey::from(&record.passkey)],
+        )
+        .map_err(PasskeyError::from)?;
+
+    record.passkey.update_credential(&result);
+    if !kv
+        .compare_and_swap(key, stored, serde_json::to_string(&record)?)
+        .await?
+    {
+        return Err(PasskeyError::ConcurrentUse.into());
+    }
+
+    let account = account(kv, record.principal)
+        .await?
+        .ok_or(PasskeyError::UnknownCredential)?;
+    Ok((record.principal, account))
+}
+
+/// How `principal` is logged in to with its passkeys, if it has any
+pub async fn account(
+    kv: &impl KVStore,
+    principal: Principal,
+) -> Result<Option<PasskeyAccount>, ServerFnError> {
+    let Some(account) = kv.read(account_key(principal)).await? else {
+        return Ok(None);
+    };
+    Ok(Some(serde_json::from_str(&account)?))
+}
+
+/// Keeps how `principal` is logged in to for as long as it has passkeys
+pub async fn save_account(
+    kv: &impl KVStore,
+    principal: Principal,
+    account: &PasskeyAccount,
+) -> Result<(), ServerFnError> {
+    kv.write(account_key(principal), serde_json::to_string(account)?)
+        .await?;
+    Ok(())
+}
+
+/// Forgets the passkeys of `principal` and how its account is logged in to
+///
+/// credentials are keyed by their id, so finding those of one account walks all of them
+pub async fn delete_account(kv: &impl KVStore, principal: Principal) -> Result<(), ServerFnError> {
+    for (key, stored) in kv.scan_prefix(format!("{CREDENTIAL_PREFIX}:")).await? {
+        let owned = serde_json::from_str::<StoredCredential>(&stored)
+            .is_ok_and(|record| record.principal == principal);
+        if owned {
+            kv.delete(key).await?;
+        }
+    }
+    kv.delete(account_key(principal)).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use webauthn_authenticator_rs::{softpasskey::SoftPasskey, WebauthnAuthenticator};
+    use webauthn_rs_proto::AllowCredentials;
+
+    use super::super::store::{in_memory_kv::InMemoryKV, KVStoreImpl};
+    use super::*;
+
+    const ORIGIN: &str = "https://yral.com";
+
+    fn config() -> PasskeyConfig {
+        PasskeyConfig::new("yral.com", "Yral", &[ORIGIN.into()]).unwrap()
+    }
+
+    #[test]
+    fn config_is_picked_by_host() {
+        let configs = PasskeyConfigs::new(vec![
+            config(),
+            PasskeyConfig::new(
+                "hotornot.wtf",
+                "Hot or Not",
+                &["https://hotornot.wtf".into()],
+            )
+            .unwrap(),
+        ]);
+        assert_eq!(configs.for_host("yral.com").unwrap().rp_name, "Yral");
+        assert_eq!(
+            configs.for_host("hotornot.wtf:443").unwrap().rp_name,
+            "Hot or Not"
+        );
+        assert!(configs.for_host("legacy.yral.com").is_err());
+    }
+
+    #[tokio::test]
+    async fn registered_passkey_logs_in_once_per_ceremony() {
+        let kv = KVStoreImpl::InMemory(InMemoryKV::new());
+        let config = config();
+        let origin = Url::parse(ORIGIN).unwrap();
+        let principal = Principal::from_slice(&[1; 29]);
+        let account = PasskeyAccount::YralAuth {
+            refresh_token: "token".into(),
+        };
+        let mut authenticator = WebauthnAuthenticator::new(SoftPasskey::new(true));
+
+        let (ceremony_id, options) = start_registration(&kv, &config, principal, account.clone())
+            .await
+            .unwrap();
+        let registration = authenticator
+            .do_registration(origin.clone(), options)
+            .unwrap();
+        let registered = finish_registration(&kv, &config, &ceremony_id, &registration)
+            .await
+            .unwrap();
+        assert_eq!(registered, (principal, account.clone()));
+        assert!(
+            finish_registration(&kv, &config, &ceremony_id, &registration)
+                .await
+                .is_err(),
+            "a ceremony can only be completed once"
+        );
+
+        let (ceremony_id, mut options) = start_login(&kv, &config).await.unwrap();
+        // the software authenticator has no discoverable credentials
+        options.public_key.allow_credentials.push(AllowCredentials {
+            type_: "public-key".into(),
+            id: registration.raw_id.clone(),
+            transports: None,
+        });
+        let assertion = authenticator
+            .do_authentication(origin.clone(), options)
+            .unwrap();
+        let logged_in = finish_login(&kv, &config, &ceremony_id, &assertion)
+            .await
+            .unwrap();
+        assert_eq!(logged_in, (principal, account));
+        assert!(finish_login(&kv, &config, &ceremony_id, &assertion)
+            .await
+            .is_err());
+
+        delete_account(&kv, principal).await.unwrap();
+        assert!(super::account(&kv, principal).await.unwrap().is_none());
+        let (ceremony_id, mut options) = start_login(&kv, &config).await.unwrap();
+        options.public_key.allow_credentials.push(AllowCredentials {
+            type_: "public-key".into(),
+            id: registration.raw_id.clone(),
+            transports: None,
+        });
+        let assertion = authenticator.do_authentication(origin, options).unwrap();
+        assert!(
+            finish_login(&kv, &config, &ceremony_id, &assertion)
+                .await
+                .is_err(),
+            "passkeys of a deleted account can't log in"
+        );
+    }
+}
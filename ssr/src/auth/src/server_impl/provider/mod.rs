@@ -0,0 +1,263 @@
+//! Identity providers users can log in with
+//!
+//! Every provider gets its own redirect route (`/auth/{id}/redirect`),
+//! logins are dispatched to the provider registered under that id
+pub mod oidc;
+
+use std::{collections::HashMap, ops::Deref, sync::Arc, sync::LazyLock};
+
+use axum_extra::extract::cookie::{Cookie, SameSite};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use consts::LoginProvider;
+use enum_dispatch::enum_dispatch;
+use global_constants::USERNAME_MAX_LEN;
+use leptos::prelude::*;
+use leptos_axum::ResponseOptions;
+use openidconnect::{
+    core::CoreGenderClaim, AdditionalClaims, CsrfToken, EndUserEmail, IdTokenClaims, Nonce,
+    PkceCodeVerifier,
+};
+use regex::Regex;
+use sha2::{Digest, Sha256};
+use web_time::Duration;
+use yral_canisters_common::utils::time::current_epoch;
+use yral_types::delegated_identity::DelegatedIdentityWire;
+
+use self::oidc::OidcProvider;
+use super::{extract_private_jar, set_cookies, store::KVStore, yral::YralProvider};
+
+/// Id of the Yral Auth provider
+pub const YRAL_PROVIDER_ID: &str = "yral";
+
+const PKCE_VERIFIER_COOKIE: &str = "pkce-verifier";
+const CSRF_TOKEN_COOKIE: &str = "csrf-token";
+const NONCE_COOKIE: &str = "oidc-nonce";
+const CONSUMED_ID_TOKEN_PREFIX: &str = "oidc-consumed-id-token";
+
+/// Delegated identity, fallback username and email of a completed login
+pub type LoginResult = (DelegatedIdentityWire, Option<String>, Option<String>);
+
+#[enum_dispatch]
+#[allow(async_fn_in_trait)]
+pub trait IdentityProvider {
+    /// Starts a login, returning the URL the user must be sent to
+    async fn auth_url(
+        &self,
+        login_hint: String,
+        provider: LoginProvider,
+        client_redirect_uri: Option<String>,
+    ) -> Result<String, ServerFnError>;
+
+    /// Completes a login with the `state` and `code` the provider redirected back with
+    /// and sets the user's refresh token cookie
+    async fn perform_auth(&self, state: String, code: String)
+        -> Result<LoginResult, ServerFnError>;
+}
+
+#[derive(Clone)]
+#[enum_dispatch(IdentityProvider)]
+pub enum IdentityProviderImpl {
+    Yral(YralProvider),
+    Oidc(OidcProvider),
+}
+
+/// Login button metadata of a provider
+#[derive(Clone, Debug)]
+pub struct IdentityProviderInfo {
+    pub id: String,
+    pub display_name: String,
+}
+
+/// Configured identity providers, keyed by id
+#[derive(Clone, Default)]
+pub struct IdentityProviders(Arc<HashMap<String, IdentityProviderImpl>>);
+
+impl IdentityProviders {
+    pub fn new(providers: impl IntoIterator<Item = (String, IdentityProviderImpl)>) -> Self {
+        Self(Arc::new(providers.into_iter().collect()))
+    }
+
+    pub fn get(&self, id: &str) -> Result<&IdentityProviderImpl, ServerFnError> {
+        self.0
+            .get(id)
+            .ok_or_else(|| ServerFnError::new(format!("unknown identity provider `{id}`")))
+    }
+
+    /// Generic OIDC providers, which get their own login buttons
+    pub fn partners(&self) -> Vec<IdentityProviderInfo> {
+        let mut partners: Vec<_> = self
+            .0
+            .iter()
+            .filter_map(|(id, provider)| match provider {
+                IdentityProviderImpl::Oidc(oidc) => Some(IdentityProviderInfo {
+                    id: id.clone(),
+                    display_name: oidc.display_name().to_string(),
+                }),
+                IdentityProviderImpl::Yral(_) => None,
+            })
+            .collect();
+        partners.sort_by(|a, b| a.display_name.cmp(&b.display_name));
+        partners
+    }
+}
+
+/// Login cookies are namespaced per provider,
+/// so logins started with different providers don't clobber each other
+fn login_cookie(provider_id: &str, name: &str) -> String {
+    format!("{provider_id}-{name}")
+}
+
+/// Stores the PKCE verifier, CSRF token and nonce of a login in progress with `provider_id`
+pub(crate) async fn store_login_cookies(
+    provider_id: &str,
+    pkce_verifier: &PkceCodeVerifier,
+    csrf_token: &CsrfToken,
+    nonce: &Nonce,
+) -> Result<(), ServerFnError> {
+    let mut jar = extract_private_jar().await?;
+
+    let cookie_life = Duration::from_secs(60 * 10).try_into().unwrap(); // 10 minutes
+    for (name, value) in [
+        (PKCE_VERIFIER_COOKIE, pkce_verifier.secret()),
+        (CSRF_TOKEN_COOKIE, csrf_token.secret()),
+        (NONCE_COOKIE, nonce.secret()),
+    ] {
+        let cookie = Cookie::build((login_cookie(provider_id, name), value.clone()))
+            .same_site(SameSite::None)
+            .path("/")
+            .max_age(cookie_life)
+            .build();
+        jar = jar.add(cookie);
+    }
+
+    let resp: ResponseOptions = expect_context();
+    set_cookies(&resp, jar);
+
+    Ok(())
+}
+
+/// Checks `provided_csrf` against the login in progress with `provider_id`,
+/// and returns its PKCE verifier and nonce
+pub(crate) async fn take_login_cookies(
+    provider_id: &str,
+    provided_csrf: &str,
+) -> Result<(PkceCodeVerifier, Nonce), ServerFnError> {
+    let mut jar = extract_private_jar().await?;
+    let [pkce_name, csrf_name, nonce_name] =
+        [PKCE_VERIFIER_COOKIE, CSRF_TOKEN_COOKIE, NONCE_COOKIE]
+            .map(|name| login_cookie(provider_id, name));
+
+    let csrf_cookie = jar
+        .get(&csrf_name)
+        .ok_or_else(|| ServerFnError::new("CSRF token cookie not found"))?;
+    if provided_csrf != csrf_cookie.value() {
+        return Err(ServerFnError::new("CSRF token mismatch"));
+    }
+
+    let pkce_cookie = jar
+        .get(&pkce_name)
+        .ok_or_else(|| ServerFnError::new("PKCE verifier cookie not found"))?;
+    let pkce_verifier = PkceCodeVerifier::new(pkce_cookie.value().to_owned());
+
+    let nonce_cookie = jar
+        .get(&nonce_name)
+        .ok_or_else(|| ServerFnError::new("Nonce cookie not found"))?;
+    let nonce = Nonce::new(nonce_cookie.value().to_owned());
+
+    for name in [pkce_name, csrf_name, nonce_name] {
+        jar = jar.remove(Cookie::build(name).path("/"));
+    }
+    let resp: ResponseOptions = expect_context();
+    set_cookies(&resp, jar);
+
+    Ok((pkce_verifier, nonce))
+}
+
+/// Marks an ID token as consumed, rejecting it if it was already used
+///
+/// tokens are identified by their `jti`, or by a hash of the raw token if the issuer omits it.
+/// The marker is kept until the token expires, after which it is rejected anyway
+pub async fn consume_id_token<AC: AdditionalClaims>(
+    kv: &impl KVStore,
+    raw_token: &str,
+    jti: Option<&str>,
+    claims: &IdTokenClaims<AC, CoreGenderClaim>,
+) -> Result<(), ServerFnError> {
+    let token_id = jti
+        .map(str::to_string)
+        .unwrap_or_else(|| hex::encode(Sha256::digest(raw_token)));
+
+    let expiry_ms = claims.expiration().timestamp_millis().max(0) as u128;
+    let ttl_ms = expiry_ms.saturating_sub(current_epoch().as_millis());
+    if ttl_ms == 0 {
+        return Err(ServerFnError::new("ID token has expired"));
+    }
+
+    let first_use = kv
+        .write_if_absent(
+            format!("{CONSUMED_ID_TOKEN_PREFIX}:{token_id}"),
+            claims.subject().to_string(),
+            Some(Duration::from_millis(ttl_ms as u64)),
+        )
+        .await?;
+    if !first_use {
+        return Err(ServerFnError::new("ID token has already been used"));
+    }
+
+    Ok(())
+}
+
+/// `jti` claim of an ID token, which the standard claims don't expose
+///
+/// only to be called once the token has been verified
+pub(crate) fn id_token_jti(raw_token: &str) -> Option<String> {
+    #[derive(serde::Deserialize)]
+    struct TokenId {
+        jti: Option<String>,
+    }
+    let claims = raw_token.split('.').nth(1)?;
+    let claims = URL_SAFE_NO_PAD.decode(claims).ok()?;
+    serde_json::from_slice::<TokenId>(&claims).ok()?.jti
+}
+
+/// Username suggested from the local part of the user's email
+pub(crate) fn fallback_username(email: Option<&EndUserEmail>) -> Option<String> {
+    static USERNAME_REGEX: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"^([a-zA-Z0-9]){3,15}$").unwrap());
+
+    let mail: &String = email?.deref();
+    let mut username = mail.split_once("@")?.0;
+    username = username
+        .char_indices()
+        .nth(USERNAME_MAX_LEN)
+        .map(|(i, _)| &username[..i])
+        .unwrap_or(username);
+
+    USERNAME_REGEX
+        .is_match(username)
+        .then(|| username.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn jti_is_read_from_the_token_payload() {
+        let token = |payload: &str| format!("e30.{}.sig", URL_SAFE_NO_PAD.encode(payload));
+        assert_eq!(
+            id_token_jti(&token(r#"{"sub": "1", "jti": "abc"}"#)).as_deref(),
+            Some("abc")
+        );
+        assert_eq!(id_token_jti(&token(r#"{"sub": "1"}"#)), None);
+        assert_eq!(id_token_jti("not-a-jwt"), None);
+    }
+
+    #[test]
+    fn login_cookies_are_namespaced_per_provider() {
+        assert_ne!(
+            login_cookie(YRAL_PROVIDER_ID, CSRF_TOKEN_COOKIE),
+            login_cookie("partner", CSRF_TOKEN_COOKIE)
+        );
+    }
+}
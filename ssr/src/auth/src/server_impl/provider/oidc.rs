@@ -0,0 +1,158 @@
+use std::ops::Deref;
+
+use consts::LoginProvider;
+use ic_agent::Identity;
+use leptos::prelude::*;
+use leptos_axum::ResponseOptions;
+use openidconnect::{
+    core::{CoreAuthenticationFlow, CoreClient, CoreProviderMetadata},
+    reqwest::async_http_client,
+    AuthorizationCode, ClientId, ClientSecret, CsrfToken, IssuerUrl, LoginHint, Nonce,
+    PkceCodeChallenge, RedirectUrl, Scope,
+};
+use serde::Deserialize;
+
+use crate::delegate_identity;
+
+use super::{
+    super::{
+        external_subject_identity, extract_signed_jar, identity_kv, registered_refresh_token,
+        save_identity_legacy, store::KVStoreImpl, update_user_identity,
+    },
+    consume_id_token, fallback_username, id_token_jti, store_login_cookies, take_login_cookies,
+    IdentityProvider, LoginResult,
+};
+
+fn default_scopes() -> Vec<String> {
+    vec!["email".into()]
+}
+
+#[derive(Clone, Deserialize)]
+pub struct OidcProviderConfig {
+    /// Identifies the provider in its redirect route, `/auth/{id}/redirect`
+    pub id: String,
+    pub display_name: String,
+    pub issuer_url: String,
+    pub client_id: String,
+    #[serde(default)]
+    pub client_secret: Option<String>,
+    pub redirect_url: String,
+    /// Requested in addition to `openid`
+    #[serde(default = "default_scopes")]
+    pub scopes: Vec<String>,
+}
+
+/// Any OpenID Connect issuer supporting discovery
+///
+/// The issuer only authenticates the user, their base identity is generated
+/// on their first login and kept in the KV store
+#[derive(Clone)]
+pub struct OidcProvider {
+    id: String,
+    display_name: String,
+    scopes: Vec<String>,
+    client: CoreClient,
+}
+
+impl OidcProvider {
+    /// Fetches the issuer's discovery document and signing keys
+    pub async fn discover(config: OidcProviderConfig) -> Result<Self, anyhow::Error> {
+        let issuer = IssuerUrl::new(config.issuer_url)?;
+        let metadata = CoreProviderMetadata::discover_async(issuer, async_http_client).await?;
+        let client = CoreClient::from_provider_metadata(
+            metadata,
+            ClientId::new(config.client_id),
+            config.client_secret.map(ClientSecret::new),
+        )
+        .set_redirect_uri(RedirectUrl::new(config.redirect_url)?);
+
+        Ok(Self {
+            id: config.id,
+            display_name: config.display_name,
+            scopes: config.scopes,
+            client,
+        })
+    }
+
+    pub fn display_name(&self) -> &str {
+        &self.display_name
+    }
+}
+
+impl IdentityProvider for OidcProvider {
+    async fn auth_url(
+        &self,
+        login_hint: String,
+        _provider: LoginProvider,
+        _client_redirect_uri: Option<String>,
+    ) -> Result<String, ServerFnError> {
+        let (pkce_challenge, pkce_verifier) = PkceCodeChallenge::new_random_sha256();
+        let nonce = Nonce::new_random();
+
+        let mut request = self
+            .client
+            .authorize_url(
+                CoreAuthenticationFlow::AuthorizationCode,
+                CsrfToken::new_random,
+                {
+                    let nonce = nonce.clone();
+                    move || nonce
+                },
+            )
+            .set_pkce_challenge(pkce_challenge)
+            .set_login_hint(LoginHint::new(login_hint));
+        for scope in &self.scopes {
+            request = request.add_scope(Scope::new(scope.clone()));
+        }
+
+        let (auth_url, csrf_token, _) = request.url();
+        store_login_cookies(&self.id, &pkce_verifier, &csrf_token, &nonce).await?;
+
+        Ok(auth_url.to_string())
+    }
+
+    async fn perform_auth(
+        &self,
+        state: String,
+        code: String,
+    ) -> Result<LoginResult, ServerFnError> {
+        let (pkce_verifier, nonce) = take_login_cookies(&self.id, &state).await?;
+
+        let token_res = self
+            .client
+            .exchange_code(AuthorizationCode::new(code))
+            .set_pkce_verifier(pkce_verifier)
+            .request_async(async_http_client)
+            .await?;
+
+        let id_token = token_res
+            .extra_fields()
+            .id_token()
+            .ok_or_else(|| ServerFnError::new("identity provider did not return an ID token"))?;
+        let claims = id_token.claims(&self.client.id_token_verifier(), &nonce)?;
+
+        let kv: KVStoreImpl = expect_context();
+        let raw_token = id_token.to_string();
+        let jti = id_token_jti(&raw_token);
+        consume_id_token(&kv, &raw_token, jti.as_deref(), claims).await?;
+
+        let identity_kv = identity_kv();
+        let base_key =
+            external_subject_identity(&identity_kv, claims.issuer(), claims.subject()).await?;
+        let base_identity = save_identity_legacy(&identity_kv, base_key).await?;
+
+        // a registered session, so the user counts as logged in (e.g for referral rewards)
+        let jar = extract_signed_jar().await?;
+        let resp: ResponseOptions = expect_context();
+        update_user_identity(
+            &resp,
+            jar,
+            registered_refresh_token(base_identity.sender().unwrap()),
+        )?;
+
+        let email = claims.email().map(|f| f.deref().clone());
+        let username = fallback_username(claims.email());
+
+        Ok((delegate_identity(&base_identity), username, email))
+    }
+}
@@ -0,0 +1,201 @@
+//! Server side cache of refresh token exchanges
+//!
+//! Exchanging a refresh token with Yral Auth is a network round trip, which
+//! used to happen on every `extract_identity` call. The derived identity is
+//! cached (encrypted) in the KV store under a hash of the refresh token until
+//! shortly before its delegation expires, and concurrent exchanges of the same
+//! token on this instance are deduplicated
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{LazyLock, Mutex},
+};
+
+use futures::{
+    future::{BoxFuture, Shared},
+    FutureExt,
+};
+use leptos::prelude::*;
+use sha2::{Digest, Sha256};
+use web_time::Duration;
+use yral_canisters_common::utils::time::current_epoch;
+use yral_types::delegated_identity::DelegatedIdentityWire;
+
+use super::store::{encrypted_kv::EncryptedKV, KVStore};
+
+const CACHE_PREFIX: &str = "refresh-exchange";
+/// Cached identities are dropped this long before their delegation expires
+const EXPIRY_MARGIN: Duration = Duration::from_secs(5 * 60);
+
+type Exchange = Shared<BoxFuture<'static, Result<DelegatedIdentityWire, ServerFnError>>>;
+
+static IN_FLIGHT: LazyLock<Mutex<HashMap<String, Exchange>>> = LazyLock::new(Default::default);
+
+fn cache_key(refresh_token: &str) -> String {
+    format!(
+        "{CACHE_PREFIX}:{}",
+        hex::encode(Sha256::digest(refresh_token))
+    )
+}
+
+/// None if the delegation expires too soon to be worth caching
+fn cache_ttl(identity: &DelegatedIdentityWire) -> Option<Duration> {
+    let expiry_ns = identity
+        .delegation_chain
+        .iter()
+        .map(|signed| signed.delegation.expiration)
+        .min()?;
+    Duration::from_nanos(expiry_ns)
+        .checked_sub(current_epoch())?
+        .checked_sub(EXPIRY_MARGIN)
+        .filter(|ttl| !ttl.is_zero())
+}
+
+/// Removes the in-flight exchange once the caller that started it is done,
+/// even if it was cancelled
+struct InFlightGuard<'a>(&'a str);
+
+impl Drop for InFlightGuard<'_> {
+    fn drop(&mut self) {
+        IN_FLIGHT.lock().unwrap().remove(self.0);
+    }
+}
+
+/// Returns the cached identity for `refresh_token`, or runs `exchange` and caches the result
+///
+/// `exchange` is only polled if there's no cached identity and no exchange
+/// of the same token already in flight
+pub async fn cached_exchange(
+    kv: &EncryptedKV,
+    refresh_token: &str,
+    exchange: impl Future<Output = Result<DelegatedIdentityWire, ServerFnError>> + Send + 'static,
+) -> Result<DelegatedIdentityWire, ServerFnError> {
+    let key = cache_key(refresh_token);
+    if let Some(cached) = kv.read(key.clone()).await? {
+        match serde_json::from_str(&cached) {
+            Ok(identity) => return Ok(identity),
+            Err(e) => tracing::warn!("dropping malformed cached identity: {e}"),
+        }
+    }
+
+    let (exchange, _guard) = {
+        let mut in_flight = IN_FLIGHT.lock().unwrap();
+        if let Some(exchange) = in_flight.get(&key) {
+            (exchange.clone(), None)
+        } else {
+            let kv = kv.clone();
+            let store_key = key.clone();
+            let exchange = async move {
+                let identity = exchange.await?;
+                if let Some(ttl) = cache_ttl(&identity) {
+                    let value = serde_json::to_string(&identity)?;
+                    if let Err(e) = kv.write_with_ttl(store_key, value, ttl).await {
+                        tracing::warn!("failed to cache refresh token exchange: {e}");
+                    }
+                }
+                Ok(identity)
+            }
+            .boxed()
+            .shared();
+            in_flight.insert(key.clone(), exchange.clone());
+            (exchange, Some(InFlightGuard(&key)))
+        }
+    };
+
+    exchange.await
+}
+
+/// Drops the cached identity for `refresh_token`
+pub async fn invalidate(kv: &EncryptedKV, refresh_token: &str) -> Result<(), ServerFnError> {
+    kv.delete(cache_key(refresh_token)).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use ic_agent::identity::Secp256k1Identity;
+    use rand_chacha::rand_core::OsRng;
+
+    use super::super::store::{encrypted_kv::SecretKeyring, in_memory_kv::InMemoryKV, KVStoreImpl};
+    use super::*;
+    use crate::delegate_identity;
+
+    fn kv() -> EncryptedKV {
+        let keyring = SecretKeyring::new([(1, vec![1u8; 32])]).unwrap();
+        EncryptedKV::new(KVStoreImpl::InMemory(InMemoryKV::new()), keyring)
+    }
+
+    /// An exchange that counts how often it runs
+    fn exchange(
+        calls: &Arc<AtomicUsize>,
+    ) -> impl Future<Output = Result<DelegatedIdentityWire, ServerFnError>> + Send + 'static {
+        let calls = calls.clone();
+        async move {
+            calls.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            let base = Secp256k1Identity::from_private_key(k256::SecretKey::random(&mut OsRng));
+            Ok(delegate_identity(&base))
+        }
+    }
+
+    #[tokio::test]
+    async fn concurrent_exchanges_of_a_token_are_shared() {
+        let kv = kv();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let (a, b) = futures::join!(
+            cached_exchange(&kv, "single-flight", exchange(&calls)),
+            cached_exchange(&kv, "single-flight", exchange(&calls)),
+        );
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(a.unwrap().from_key, b.unwrap().from_key);
+
+        // later calls are served from the kv
+        cached_exchange(&kv, "single-flight", exchange(&calls))
+            .await
+            .unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert!(IN_FLIGHT
+            .lock()
+            .unwrap()
+            .get(&cache_key("single-flight"))
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn invalidated_tokens_are_exchanged_again() {
+        let kv = kv();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let first = cached_exchange(&kv, "invalidate", exchange(&calls))
+            .await
+            .unwrap();
+        cached_exchange(&kv, "invalidate", exchange(&calls))
+            .await
+            .unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        invalidate(&kv, "invalidate").await.unwrap();
+        let second = cached_exchange(&kv, "invalidate", exchange(&calls))
+            .await
+            .unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert_ne!(first.from_key, second.from_key);
+    }
+
+    #[test]
+    fn cache_never_outlives_the_delegation() {
+        let base = Secp256k1Identity::from_private_key(k256::SecretKey::random(&mut OsRng));
+        let mut identity = delegate_identity(&base);
+        let expiry = Duration::from_nanos(identity.delegation_chain[0].delegation.expiration);
+        let ttl = cache_ttl(&identity).unwrap();
+        assert!(current_epoch() + ttl + EXPIRY_MARGIN <= expiry);
+
+        identity.delegation_chain[0].delegation.expiration =
+            (current_epoch() + EXPIRY_MARGIN / 2).as_nanos() as u64;
+        assert_eq!(cache_ttl(&identity), None);
+    }
+}
@@ -0,0 +1,329 @@
+//! Registry of active sessions, so users can see and revoke their other logins
+//!
+//! A session is a refresh token cookie, identified by a hash of the token.
+//! Sessions are registered (and their last seen time bumped) as identities are
+//! extracted, revoked sessions are remembered until their refresh token would expire.
+//! The ids of a principal's sessions are kept in a set, so listing them is a
+//! single lookup rather than a scan of the keyspace
+use std::net::SocketAddr;
+
+use axum::{
+    extract::ConnectInfo,
+    http::{header, HeaderMap},
+};
+use candid::Principal;
+use consts::auth::REFRESH_MAX_AGE;
+use leptos::prelude::*;
+use leptos_axum::extract;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use utils::client_ip::client_ip;
+use web_time::Duration;
+use yral_canisters_common::utils::time::current_epoch;
+
+use crate::SessionInfo;
+
+use super::store::KVStore;
+
+const SESSION_PREFIX: &str = "session";
+const SESSION_INDEX_PREFIX: &str = "session-index";
+const REVOKED_PREFIX: &str = "revoked-session";
+/// Last seen is only bumped this often, to avoid a write on every request
+const TOUCH_INTERVAL: Duration = Duration::from_secs(5 * 60);
+const UNKNOWN_DEVICE: &str = "Unknown device";
+
+#[derive(Serialize, Deserialize)]
+struct SessionRecord {
+    device: String,
+    ip: Option<String>,
+    user_agent: Option<String>,
+    last_seen_ms: u64,
+}
+
+pub fn session_id(refresh_token: &str) -> String {
+    hex::encode(&Sha256::digest(refresh_token)[..16])
+}
+
+fn session_key(principal: Principal, session_id: &str) -> String {
+    format!("{SESSION_PREFIX}:{}:{session_id}", principal.to_text())
+}
+
+fn index_key(principal: Principal) -> String {
+    format!("{SESSION_INDEX_PREFIX}:{}", principal.to_text())
+}
+
+fn revoked_key(session_id: &str) -> String {
+    format!("{REVOKED_PREFIX}:{session_id}")
+}
+
+fn now_ms() -> u64 {
+    current_epoch().as_millis() as u64
+}
+
+fn device_from_user_agent(user_agent: &str) -> &'static str {
+    // Android user agents also contain "Linux", iPad ones may contain "Macintosh"
+    [
+        ("iPhone", "iPhone"),
+        ("iPad", "iPad"),
+        ("Android", "Android"),
+        ("Windows", "Windows"),
+        ("CrOS", "ChromeOS"),
+        ("Macintosh", "Mac"),
+        ("Linux", "Linux"),
+    ]
+    .into_iter()
+    .find(|(needle, _)| user_agent.contains(needle))
+    .map(|(_, device)| device)
+    .unwrap_or(UNKNOWN_DEVICE)
+}
+
+async fn record_from_request() -> SessionRecord {
+    let headers: HeaderMap = extract().await.unwrap_or_default();
+    let peer: Option<ConnectInfo<SocketAddr>> = extract().await.ok();
+    let user_agent = headers
+        .get(header::USER_AGENT)
+        .and_then(|ua| ua.to_str().ok())
+        .map(str::to_string);
+
+    SessionRecord {
+        device: user_agent
+            .as_deref()
+            .map_or(UNKNOWN_DEVICE, device_from_user_agent)
+            .into(),
+        ip: client_ip(&headers, peer.map(|ConnectInfo(addr)| addr)),
+        user_agent,
+        last_seen_ms: now_ms(),
+    }
+}
+
+pub async fn is_revoked(kv: &impl KVStore, session_id: &str) -> Result<bool, ServerFnError> {
+    Ok(kv.read(revoked_key(session_id)).await?.is_some())
+}
+
+async fn save(
+    kv: &impl KVStore,
+    principal: Principal,
+    session_id: &str,
+    record: &SessionRecord,
+) -> Result<(), ServerFnError> {
+    kv.write_with_ttl(
+        session_key(principal, session_id),
+        serde_json::to_string(record)?,
+        REFRESH_MAX_AGE,
+    )
+    .await?;
+    kv.set_add(index_key(principal), session_id.into(), REFRESH_MAX_AGE)
+        .await?;
+    Ok(())
+}
+
+/// Drops the session record and its entry in the principal's index
+async fn forget(
+    kv: &impl KVStore,
+    principal: Principal,
+    session_id: &str,
+) -> Result<(), ServerFnError> {
+    kv.delete(session_key(principal, session_id)).await?;
+    kv.set_remove(index_key(principal), session_id.into())
+        .await?;
+    Ok(())
+}
+
+/// Registers the session of the current request, or bumps its last seen time
+pub async fn touch(
+    kv: &impl KVStore,
+    principal: Principal,
+    session_id: &str,
+) -> Result<(), ServerFnError> {
+    let existing = kv.read(session_key(principal, session_id)).await?;
+    let fresh = existing
+        .and_then(|raw| serde_json::from_str::<SessionRecord>(&raw).ok())
+        .is_some_and(|record| {
+            now_ms().saturating_sub(record.last_seen_ms) < TOUCH_INTERVAL.as_millis() as u64
+        });
+    if fresh {
+        return Ok(());
+    }
+
+    save(kv, principal, session_id, &record_from_request().await).await
+}
+
+/// Moves a session to the id of its renewed refresh token
+pub async fn rename(
+    kv: &impl KVStore,
+    principal: Principal,
+    session_id: &str,
+    renewed_id: &str,
+) -> Result<(), ServerFnError> {
+    let Some(raw) = kv.read(session_key(principal, session_id)).await? else {
+        return Ok(());
+    };
+    let record: SessionRecord = serde_json::from_str(&raw)?;
+    save(kv, principal, renewed_id, &record).await?;
+    forget(kv, principal, session_id).await
+}
+
+/// Active sessions of `principal`, the current one first, then most recently seen
+pub async fn list(
+    kv: &impl KVStore,
+    principal: Principal,
+    current_session: &str,
+) -> Result<Vec<SessionInfo>, ServerFnError> {
+    let ids = kv.set_members(index_key(principal)).await?;
+    let keys = ids.iter().map(|id| session_key(principal, id)).collect();
+    let records = kv.read_many(keys).await?;
+
+    let mut sessions = vec![];
+    for (id, raw) in ids.into_iter().zip(records) {
+        let Some(raw) = raw else {
+            // the record expired before the index did
+            kv.set_remove(index_key(principal), id).await?;
+            continue;
+        };
+        let Ok(record) = serde_json::from_str::<SessionRecord>(&raw) else {
+            continue;
+        };
+        sessions.push(SessionInfo {
+            current: id == current_session,
+            id,
+            device: record.device,
+            ip: record.ip,
+            user_agent: record.user_agent,
+            last_seen_ms: record.last_seen_ms,
+        });
+    }
+    sessions.sort_by(|a, b| {
+        b.current
+            .cmp(&a.current)
+            .then(b.last_seen_ms.cmp(&a.last_seen_ms))
+    });
+
+    Ok(sessions)
+}
+
+/// Returns false if `principal` has no such session
+pub async fn revoke(
+    kv: &impl KVStore,
+    principal: Principal,
+    session_id: &str,
+) -> Result<bool, ServerFnError> {
+    let key = session_key(principal, session_id);
+    if kv.read(key.clone()).await?.is_none() {
+        return Ok(false);
+    }
+
+    kv.write_with_ttl(
+        revoked_key(session_id),
+        principal.to_text(),
+        REFRESH_MAX_AGE,
+    )
+    .await?;
+    forget(kv, principal, session_id).await?;
+    Ok(true)
+}
+
+/// Revokes every session of `principal` except `current_session`,
+/// returning how many were revoked
+pub async fn revoke_others(
+    kv: &impl KVStore,
+    principal: Principal,
+    current_session: &str,
+) -> Result<usize, ServerFnError> {
+    let mut revoked = 0;
+    for session in list(kv, principal, current_session).await? {
+        if !session.current && revoke(kv, principal, &session.id).await? {
+            revoked += 1;
+        }
+    }
+    Ok(revoked)
+}
+
+/// Revokes every session of `principal`, when its account is deleted
+pub async fn revoke_all(kv: &impl KVStore, principal: Principal) -> Result<(), ServerFnError> {
+    for session in list(kv, principal, "").await? {
+        revoke(kv, principal, &session.id).await?;
+    }
+    kv.delete(index_key(principal)).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::store::{in_memory_kv::InMemoryKV, KVStoreImpl};
+    use super::*;
+
+    fn record(last_seen_ms: u64) -> SessionRecord {
+        SessionRecord {
+            device: "Android".into(),
+            ip: Some("203.0.113.7".into()),
+            user_agent: None,
+            last_seen_ms,
+        }
+    }
+
+    #[tokio::test]
+    async fn revoked_sessions_are_unlisted_and_rejected() {
+        let kv = KVStoreImpl::InMemory(InMemoryKV::new());
+        let principal = Principal::from_slice(&[1; 29]);
+        let (current, other, stranger) = (session_id("a"), session_id("b"), session_id("c"));
+        save(&kv, principal, &current, &record(1)).await.unwrap();
+        save(&kv, principal, &other, &record(2)).await.unwrap();
+        save(&kv, Principal::anonymous(), &stranger, &record(3))
+            .await
+            .unwrap();
+
+        let sessions = list(&kv, principal, &current).await.unwrap();
+        let ids: Vec<_> = sessions.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, [current.as_str(), other.as_str()]);
+
+        assert!(!revoke(&kv, principal, &stranger).await.unwrap());
+        assert!(!is_revoked(&kv, &stranger).await.unwrap());
+
+        assert_eq!(revoke_others(&kv, principal, &current).await.unwrap(), 1);
+        assert!(is_revoked(&kv, &other).await.unwrap());
+        assert!(!is_revoked(&kv, &current).await.unwrap());
+        assert_eq!(list(&kv, principal, &current).await.unwrap().len(), 1);
+
+        revoke_all(&kv, principal).await.unwrap();
+        assert!(is_revoked(&kv, &current).await.unwrap());
+        assert!(kv
+            .set_members(index_key(principal))
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            list(&kv, Principal::anonymous(), &stranger)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn index_follows_renames_and_drops_expired_records() {
+        let kv = KVStoreImpl::InMemory(InMemoryKV::new());
+        let principal = Principal::from_slice(&[2; 29]);
+        let (old, renewed, expired) = (session_id("a"), session_id("b"), session_id("c"));
+        save(&kv, principal, &old, &record(1)).await.unwrap();
+        save(&kv, principal, &expired, &record(2)).await.unwrap();
+
+        rename(&kv, principal, &old, &renewed).await.unwrap();
+        kv.delete(session_key(principal, &expired)).await.unwrap();
+
+        let sessions = list(&kv, principal, &renewed).await.unwrap();
+        let ids: Vec<_> = sessions.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, [renewed.as_str()]);
+        assert_eq!(
+            kv.set_members(index_key(principal)).await.unwrap(),
+            [renewed]
+        );
+    }
+
+    #[test]
+    fn device_is_guessed_from_user_agent() {
+        let android = "Mozilla/5.0 (Linux; Android 14; Pixel 8) AppleWebKit/537.36";
+        assert_eq!(device_from_user_agent(android), "Android");
+        assert_eq!(device_from_user_agent("curl/8.5.0"), UNKNOWN_DEVICE);
+    }
+}
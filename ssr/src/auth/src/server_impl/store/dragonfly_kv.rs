@@ -1,4 +1,7 @@
-use super::{KVError, KVStore};
+use super::{
+    redis_ops::{self, AUTH_FIELD},
+    KVError, KVStore, TokenBucket,
+};
 use futures::StreamExt;
 use redis::aio::MultiplexedConnection;
 use redis::sentinel::SentinelClient;
@@ -533,8 +536,6 @@ impl DragonflyKV {
     }
 }
 
-const AUTH_FIELD: &str = "auth";
-
 impl KVStore for DragonflyKV {
     async fn read(&self, key: String) -> Result<Option<String>, KVError> {
         let value: Option<String> = self
@@ -548,15 +549,178 @@ impl KVStore for DragonflyKV {
     }
 
     async fn write(&self, key: String, value: String) -> Result<(), KVError> {
-        let result: () = self
+        self.0
+            .execute_with_retry(|mut conn| {
+                let key = key.clone();
+                let value = value.clone();
+                async move { redis_ops::write(&mut conn, &key, &value).await }
+            })
+            .await?;
+        Ok(())
+    }
+
+    async fn write_with_ttl(
+        &self,
+        key: String,
+        value: String,
+        ttl: Duration,
+    ) -> Result<(), KVError> {
+        self.0
+            .execute_with_retry(|mut conn| {
+                let key = key.clone();
+                let value = value.clone();
+                async move { redis_ops::write_with_ttl(&mut conn, &key, &value, ttl).await }
+            })
+            .await?;
+        Ok(())
+    }
+
+    async fn write_if_absent(
+        &self,
+        key: String,
+        value: String,
+        ttl: Option<Duration>,
+    ) -> Result<bool, KVError> {
+        let written = self
             .0
             .execute_with_retry(|mut conn| {
                 let key = key.clone();
                 let value = value.clone();
-                async move { conn.hset::<_, _, _, ()>(key, AUTH_FIELD, value).await }
+                async move { redis_ops::write_if_absent(&mut conn, &key, &value, ttl).await }
+            })
+            .await?;
+        Ok(written)
+    }
+
+    async fn replace(&self, key: String, value: String) -> Result<bool, KVError> {
+        let replaced = self
+            .0
+            .execute_with_retry(|mut conn| {
+                let key = key.clone();
+                let value = value.clone();
+                async move { redis_ops::replace(&mut conn, &key, &value).await }
+            })
+            .await?;
+        Ok(replaced)
+    }
+
+    async fn compare_and_swap(
+        &self,
+        key: String,
+        current: String,
+        value: String,
+    ) -> Result<bool, KVError> {
+        let swapped = self
+            .0
+            .execute_with_retry(|mut conn| {
+                let key = key.clone();
+                let current = current.clone();
+                let value = value.clone();
+                async move { redis_ops::compare_and_swap(&mut conn, &key, &current, &value).await }
+            })
+            .await?;
+        Ok(swapped)
+    }
+
+    async fn take(&self, key: String) -> Result<Option<String>, KVError> {
+        let value = self
+            .0
+            .execute_with_retry(|mut conn| {
+                let key = key.clone();
+                async move { redis_ops::take(&mut conn, &key).await }
+            })
+            .await?;
+        Ok(value)
+    }
+
+    async fn delete(&self, key: String) -> Result<(), KVError> {
+        self.0
+            .execute_with_retry(|mut conn| {
+                let key = key.clone();
+                async move { conn.del::<_, ()>(key).await }
+            })
+            .await?;
+        Ok(())
+    }
+
+    async fn read_many(&self, keys: Vec<String>) -> Result<Vec<Option<String>>, KVError> {
+        let values = self
+            .0
+            .execute_with_retry(|mut conn| {
+                let keys = keys.clone();
+                async move { redis_ops::read_many(&mut conn, &keys).await }
+            })
+            .await?;
+        Ok(values)
+    }
+
+    async fn write_many(&self, entries: Vec<(String, String)>) -> Result<(), KVError> {
+        self.0
+            .execute_with_retry(|mut conn| {
+                let entries = entries.clone();
+                async move { redis_ops::write_many(&mut conn, &entries).await }
+            })
+            .await?;
+        Ok(())
+    }
+
+    async fn scan_prefix(&self, prefix: String) -> Result<Vec<(String, String)>, KVError> {
+        let entries = self
+            .0
+            .execute_with_retry(|mut conn| {
+                let prefix = prefix.clone();
+                async move { redis_ops::scan_prefix(&mut conn, &prefix).await }
+            })
+            .await?;
+        Ok(entries)
+    }
+
+    async fn set_add(&self, key: String, member: String, ttl: Duration) -> Result<(), KVError> {
+        self.0
+            .execute_with_retry(|mut conn| {
+                let key = key.clone();
+                let member = member.clone();
+                async move { redis_ops::set_add(&mut conn, &key, &member, ttl).await }
+            })
+            .await?;
+        Ok(())
+    }
+
+    async fn set_remove(&self, key: String, member: String) -> Result<(), KVError> {
+        self.0
+            .execute_with_retry(|mut conn| {
+                let key = key.clone();
+                let member = member.clone();
+                async move { conn.srem::<_, _, ()>(key, member).await }
+            })
+            .await?;
+        Ok(())
+    }
+
+    async fn set_members(&self, key: String) -> Result<Vec<String>, KVError> {
+        let members = self
+            .0
+            .execute_with_retry(|mut conn| {
+                let key = key.clone();
+                async move { conn.smembers(key).await }
+            })
+            .await?;
+        Ok(members)
+    }
+
+    async fn take_token(
+        &self,
+        key: String,
+        bucket: TokenBucket,
+    ) -> Result<Option<Duration>, KVError> {
+        let wait = self
+            .0
+            .execute_with_retry(|mut conn| {
+                let key = key.clone();
+                async move { redis_ops::take_token(&mut conn, &key, bucket).await }
             })
             .await?;
-        Ok(result)
+        Ok(wait)
     }
 }
 
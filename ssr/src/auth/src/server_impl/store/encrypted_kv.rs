@@ -0,0 +1,439 @@
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use aes_gcm::{
+    aead::{Aead, AeadCore, KeyInit, OsRng, Payload},
+    Aes256Gcm, Nonce,
+};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use hkdf::Hkdf;
+use sha2::Sha256;
+
+use super::{KVError, KVStore, KVStoreImpl, TokenBucket};
+
+const CIPHERTEXT_PREFIX: &str = "enc";
+const KEY_DERIVATION_INFO: &[u8] = b"yral-kv-secret-encryption";
+const NONCE_LEN: usize = 12;
+
+/// Versioned set of master keys used to encrypt values at rest
+///
+/// Values are always encrypted with the highest key id,
+/// older keys are only kept around to decrypt (and re-encrypt) existing values
+#[derive(Clone)]
+pub struct SecretKeyring {
+    current: u32,
+    ciphers: Arc<HashMap<u32, Aes256Gcm>>,
+}
+
+impl SecretKeyring {
+    /// `master_keys` are `(key_id, key material)` pairs
+    /// returns None if no keys are given
+    pub fn new(master_keys: impl IntoIterator<Item = (u32, Vec<u8>)>) -> Option<Self> {
+        let ciphers: HashMap<_, _> = master_keys
+            .into_iter()
+            .map(|(id, master_key)| (id, derive_cipher(&master_key)))
+            .collect();
+        let current = *ciphers.keys().max()?;
+
+        Some(Self {
+            current,
+            ciphers: Arc::new(ciphers),
+        })
+    }
+
+    /// Parses a comma separated list of `<key_id>:<hex master key>`
+    pub fn from_config(config: &str) -> Result<Self, KVError> {
+        let keys = config
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                let (id, key) = entry.split_once(':').ok_or_else(|| {
+                    KVError::Crypto("master keys must be `<key_id>:<hex>`".into())
+                })?;
+                let id = id
+                    .parse()
+                    .map_err(|_| KVError::Crypto(format!("invalid key id `{id}`")))?;
+                let key = hex::decode(key)
+                    .map_err(|_| KVError::Crypto(format!("invalid hex for key id {id}")))?;
+                Ok((id, key))
+            })
+            .collect::<Result<Vec<_>, KVError>>()?;
+
+        Self::new(keys).ok_or_else(|| KVError::Crypto("no master keys configured".into()))
+    }
+
+    fn encrypt(&self, key: &str, value: &str) -> Result<String, KVError> {
+        let cipher = &self.ciphers[&self.current];
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = cipher
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: value.as_bytes(),
+                    aad: key.as_bytes(),
+                },
+            )
+            .map_err(|_| KVError::Crypto("encryption failed".into()))?;
+
+        let mut sealed = nonce.to_vec();
+        sealed.extend(ciphertext);
+        Ok(format!(
+            "{CIPHERTEXT_PREFIX}:{}:{}",
+            self.current,
+            URL_SAFE_NO_PAD.encode(sealed)
+        ))
+    }
+
+    /// Returns the plaintext and whether the value should be re-encrypted
+    /// the kv key is bound as associated data, so values can't be swapped between keys
+    fn decrypt(&self, key: &str, stored: String) -> Result<(String, bool), KVError> {
+        let Some((key_id, sealed)) = stored
+            .strip_prefix(CIPHERTEXT_PREFIX)
+            .and_then(|rest| rest.strip_prefix(':'))
+            .and_then(|rest| rest.split_once(':'))
+        else {
+            // written before encryption was enabled
+            return Ok((stored, true));
+        };
+
+        let key_id: u32 = key_id
+            .parse()
+            .map_err(|_| KVError::Crypto(format!("invalid key id for `{key}`")))?;
+        let cipher = self
+            .ciphers
+            .get(&key_id)
+            .ok_or_else(|| KVError::Crypto(format!("unknown key id {key_id}")))?;
+        let sealed = URL_SAFE_NO_PAD
+            .decode(sealed)
+            .map_err(|_| KVError::Crypto(format!("malformed ciphertext for `{key}`")))?;
+        if sealed.len() < NONCE_LEN {
+            return Err(KVError::Crypto(format!("malformed ciphertext for `{key}`")));
+        }
+        let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+
+        let plaintext = cipher
+            .decrypt(
+                Nonce::from_slice(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad: key.as_bytes(),
+                },
+            )
+            .map_err(|_| KVError::Crypto(format!("failed to decrypt `{key}`")))?;
+        let plaintext = String::from_utf8(plaintext)
+            .map_err(|_| KVError::Crypto(format!("invalid utf-8 in `{key}`")))?;
+
+        Ok((plaintext, key_id != self.current))
+    }
+}
+
+fn derive_cipher(master_key: &[u8]) -> Aes256Gcm {
+    let mut key = [0u8; 32];
+    Hkdf::<Sha256>::new(None, master_key)
+        .expand(KEY_DERIVATION_INFO, &mut key)
+        .expect("32 bytes is a valid HKDF-SHA256 output length");
+    Aes256Gcm::new(&key.into())
+}
+
+/// Envelope encryption around a [`KVStoreImpl`]
+///
+/// Keys are stored in plaintext, values are sealed with AES-256-GCM.
+/// Plaintext values and values sealed with a retired key are transparently
+/// re-encrypted with the current key when read, keeping their expiry
+#[derive(Clone)]
+pub struct EncryptedKV {
+    inner: KVStoreImpl,
+    keyring: SecretKeyring,
+}
+
+/// A value read in plaintext or sealed with a retired key, due for re-encryption
+struct StaleValue {
+    key: String,
+    /// exactly as read, the rewrite only lands if it's still there
+    stored: String,
+    plaintext: String,
+}
+
+impl EncryptedKV {
+    pub fn new(inner: KVStoreImpl, keyring: SecretKeyring) -> Self {
+        Self { inner, keyring }
+    }
+
+    fn open(
+        &self,
+        key: &str,
+        stored: String,
+        stale: &mut Vec<StaleValue>,
+    ) -> Result<String, KVError> {
+        let (plaintext, needs_rewrite) = self.keyring.decrypt(key, stored.clone())?;
+        if needs_rewrite {
+            stale.push(StaleValue {
+                key: key.to_string(),
+                stored,
+                plaintext: plaintext.clone(),
+            });
+        }
+        Ok(plaintext)
+    }
+
+    /// Re-encrypts values with the current key
+    /// failures are only logged, the value is re-encrypted on the next read
+    async fn rewrite_stale(&self, stale: Vec<StaleValue>) {
+        for StaleValue {
+            key,
+            stored,
+            plaintext,
+        } in stale
+        {
+            let sealed = match self.keyring.encrypt(&key, &plaintext) {
+                Ok(sealed) => sealed,
+                Err(e) => {
+                    tracing::warn!("failed to re-encrypt `{key}`: {e}");
+                    continue;
+                }
+            };
+            // a value written, deleted or expired since it was read must be left alone
+            if let Err(e) = self
+                .inner
+                .compare_and_swap(key.clone(), stored, sealed)
+                .await
+            {
+                tracing::warn!("failed to re-encrypt `{key}`: {e}");
+            }
+        }
+    }
+}
+
+impl KVStore for EncryptedKV {
+    async fn read(&self, key: String) -> Result<Option<String>, KVError> {
+        let Some(stored) = self.inner.read(key.clone()).await? else {
+            return Ok(None);
+        };
+        let mut stale = vec![];
+        let value = self.open(&key, stored, &mut stale)?;
+        self.rewrite_stale(stale).await;
+        Ok(Some(value))
+    }
+
+    async fn write(&self, key: String, value: String) -> Result<(), KVError> {
+        let sealed = self.keyring.encrypt(&key, &value)?;
+        self.inner.write(key, sealed).await
+    }
+
+    async fn write_with_ttl(
+        &self,
+        key: String,
+        value: String,
+        ttl: Duration,
+    ) -> Result<(), KVError> {
+        let sealed = self.keyring.encrypt(&key, &value)?;
+        self.inner.write_with_ttl(key, sealed, ttl).await
+    }
+
+    async fn write_if_absent(
+        &self,
+        key: String,
+        value: String,
+        ttl: Option<Duration>,
+    ) -> Result<bool, KVError> {
+        let sealed = self.keyring.encrypt(&key, &value)?;
+        self.inner.write_if_absent(key, sealed, ttl).await
+    }
+
+    async fn replace(&self, key: String, value: String) -> Result<bool, KVError> {
+        let sealed = self.keyring.encrypt(&key, &value)?;
+        self.inner.replace(key, sealed).await
+    }
+
+    /// `current` is compared with the decrypted value, the swap is made
+    /// against the exact ciphertext that was read
+    async fn compare_and_swap(
+        &self,
+        key: String,
+        current: String,
+        value: String,
+    ) -> Result<bool, KVError> {
+        let Some(stored) = self.inner.read(key.clone()).await? else {
+            return Ok(false);
+        };
+        let (plaintext, _) = self.keyring.decrypt(&key, stored.clone())?;
+        if plaintext != current {
+            return Ok(false);
+        }
+        let sealed = self.keyring.encrypt(&key, &value)?;
+        self.inner.compare_and_swap(key, stored, sealed).await
+    }
+
+    async fn take(&self, key: String) -> Result<Option<String>, KVError> {
+        let Some(stored) = self.inner.take(key.clone()).await? else {
+            return Ok(None);
+        };
+        let (value, _) = self.keyring.decrypt(&key, stored)?;
+        Ok(Some(value))
+    }
+
+    async fn delete(&self, key: String) -> Result<(), KVError> {
+        self.inner.delete(key).await
+    }
+
+    async fn read_many(&self, keys: Vec<String>) -> Result<Vec<Option<String>>, KVError> {
+        let stored = self.inner.read_many(keys.clone()).await?;
+        let mut stale = vec![];
+        let values = keys
+            .iter()
+            .zip(stored)
+            .map(|(key, stored)| {
+                stored
+                    .map(|stored| self.open(key, stored, &mut stale))
+                    .transpose()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        self.rewrite_stale(stale).await;
+        Ok(values)
+    }
+
+    async fn write_many(&self, entries: Vec<(String, String)>) -> Result<(), KVError> {
+        let sealed = entries
+            .into_iter()
+            .map(|(key, value)| {
+                let sealed = self.keyring.encrypt(&key, &value)?;
+                Ok((key, sealed))
+            })
+            .collect::<Result<Vec<_>, KVError>>()?;
+        self.inner.write_many(sealed).await
+    }
+
+    async fn scan_prefix(&self, prefix: String) -> Result<Vec<(String, String)>, KVError> {
+        let mut stale = vec![];
+        let entries = self
+            .inner
+            .scan_prefix(prefix)
+            .await?
+            .into_iter()
+            .map(|(key, stored)| {
+                let value = self.open(&key, stored, &mut stale)?;
+                Ok((key, value))
+            })
+            .collect::<Result<Vec<_>, KVError>>()?;
+        self.rewrite_stale(stale).await;
+        Ok(entries)
+    }
+
+    /// Sets hold no secrets, they are stored in the clear
+    async fn set_add(&self, key: String, member: String, ttl: Duration) -> Result<(), KVError> {
+        self.inner.set_add(key, member, ttl).await
+    }
+
+    async fn set_remove(&self, key: String, member: String) -> Result<(), KVError> {
+        self.inner.set_remove(key, member).await
+    }
+
+    async fn set_members(&self, key: String) -> Result<Vec<String>, KVError> {
+        self.inner.set_members(key).await
+    }
+
+    /// Buckets hold no secrets, they are stored in the clear
+    async fn take_token(
+        &self,
+        key: String,
+        bucket: TokenBucket,
+    ) -> Result<Option<Duration>, KVError> {
+        self.inner.take_token(key, bucket).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::in_memory_kv::InMemoryKV;
+    use super::*;
+
+    fn keyring(ids: &[u32]) -> SecretKeyring {
+        SecretKeyring::new(ids.iter().map(|&id| (id, vec![id as u8; 32]))).unwrap()
+    }
+
+    #[tokio::test]
+    async fn values_are_sealed_and_rotated_on_read() {
+        let inner = KVStoreImpl::InMemory(InMemoryKV::new());
+        inner
+            .write("legacy".into(), "plaintext".into())
+            .await
+            .unwrap();
+
+        let old = EncryptedKV::new(inner.clone(), keyring(&[1]));
+        old.write("rotated".into(), "secret".into()).await.unwrap();
+        let sealed = inner.read("rotated".into()).await.unwrap().unwrap();
+        assert!(sealed.starts_with("enc:1:"));
+        assert!(!sealed.contains("secret"));
+
+        let kv = EncryptedKV::new(inner.clone(), keyring(&[1, 2]));
+        for (key, value) in [("legacy", "plaintext"), ("rotated", "secret")] {
+            assert_eq!(kv.read(key.into()).await.unwrap().as_deref(), Some(value));
+            let stored = inner.read(key.into()).await.unwrap().unwrap();
+            assert!(stored.starts_with("enc:2:"), "{key} was not re-encrypted");
+        }
+    }
+
+    #[tokio::test]
+    async fn re_encryption_keeps_the_expiry() {
+        let inner = KVStoreImpl::InMemory(InMemoryKV::new());
+        let old = EncryptedKV::new(inner.clone(), keyring(&[1]));
+        old.write("permanent".into(), "a".into()).await.unwrap();
+        old.write_with_ttl("short".into(), "b".into(), Duration::from_millis(50))
+            .await
+            .unwrap();
+
+        let kv = EncryptedKV::new(inner.clone(), keyring(&[1, 2]));
+        assert_eq!(kv.read("short".into()).await.unwrap().as_deref(), Some("b"));
+        assert_eq!(
+            kv.read("permanent".into()).await.unwrap().as_deref(),
+            Some("a")
+        );
+        let stored = inner.read("short".into()).await.unwrap().unwrap();
+        assert!(stored.starts_with("enc:2:"));
+
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        assert_eq!(kv.read("short".into()).await.unwrap(), None);
+        assert_eq!(
+            kv.read("permanent".into()).await.unwrap().as_deref(),
+            Some("a")
+        );
+    }
+
+    #[tokio::test]
+    async fn ciphertext_is_bound_to_its_key() {
+        let inner = KVStoreImpl::InMemory(InMemoryKV::new());
+        let kv = EncryptedKV::new(inner.clone(), keyring(&[1]));
+        kv.write("alice".into(), "secret".into()).await.unwrap();
+
+        let sealed = inner.read("alice".into()).await.unwrap().unwrap();
+        inner.write("bob".into(), sealed).await.unwrap();
+        assert!(kv.read("bob".into()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn re_encryption_loses_to_a_concurrent_write() {
+        let inner = KVStoreImpl::InMemory(InMemoryKV::new());
+        let old = EncryptedKV::new(inner.clone(), keyring(&[1]));
+        old.write("key".into(), "old".into()).await.unwrap();
+
+        let kv = EncryptedKV::new(inner.clone(), keyring(&[1, 2]));
+        let mut stale = vec![];
+        let stored = inner.read("key".into()).await.unwrap().unwrap();
+        assert_eq!(kv.open("key", stored, &mut stale).unwrap(), "old");
+        assert_eq!(stale.len(), 1);
+
+        // written between the read and its re-encryption
+        kv.write("key".into(), "new".into()).await.unwrap();
+        kv.rewrite_stale(stale).await;
+        assert_eq!(kv.read("key".into()).await.unwrap().as_deref(), Some("new"));
+
+        old.write("key".into(), "old".into()).await.unwrap();
+        let mut stale = vec![];
+        let stored = inner.read("key".into()).await.unwrap().unwrap();
+        kv.open("key", stored, &mut stale).unwrap();
+        assert_eq!(stale.len(), 1);
+        kv.delete("key".into()).await.unwrap();
+        kv.rewrite_stale(stale).await;
+        assert_eq!(kv.read("key".into()).await.unwrap(), None);
+    }
+}
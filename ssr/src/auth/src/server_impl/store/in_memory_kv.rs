@@ -0,0 +1,368 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, RwLock},
+    time::{Duration, Instant},
+};
+
+use rand_chacha::rand_core::{OsRng, RngCore};
+
+use super::{KVError, KVStore, TokenBucket};
+
+/// Faults injected into every operation of an [`InMemoryKV`]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FaultConfig {
+    /// Delay added before every operation
+    pub latency: Duration,
+    /// Probability in `[0, 1]` of an operation failing
+    pub error_rate: f64,
+}
+
+struct Entry {
+    value: String,
+    expires_at: Option<Instant>,
+}
+
+impl Entry {
+    fn is_live(&self, now: Instant) -> bool {
+        self.expires_at.is_none_or(|expires_at| expires_at > now)
+    }
+}
+
+struct SetEntry {
+    members: HashSet<String>,
+    expires_at: Instant,
+}
+
+/// Process local KV store, for tests and local development
+#[derive(Clone, Default)]
+pub struct InMemoryKV {
+    entries: Arc<RwLock<HashMap<String, Entry>>>,
+    sets: Arc<RwLock<HashMap<String, SetEntry>>>,
+    faults: FaultConfig,
+}
+
+impl InMemoryKV {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_faults(faults: FaultConfig) -> Self {
+        Self {
+            faults,
+            ..Default::default()
+        }
+    }
+
+    async fn inject_faults(&self) -> Result<(), KVError> {
+        if !self.faults.latency.is_zero() {
+            tokio::time::sleep(self.faults.latency).await;
+        }
+        let roll = OsRng.next_u32() as f64 / (u32::MAX as f64 + 1.0);
+        if roll < self.faults.error_rate {
+            return Err(anyhow::anyhow!("injected in-memory KV failure").into());
+        }
+        Ok(())
+    }
+
+    fn insert(&self, key: String, value: String, ttl: Option<Duration>) {
+        let expires_at = ttl.map(|ttl| Instant::now() + ttl);
+        let mut entries = self.entries.write().unwrap();
+        entries.insert(key, Entry { value, expires_at });
+    }
+
+    fn get(&self, key: &str, now: Instant) -> Option<String> {
+        let entries = self.entries.read().unwrap();
+        entries
+            .get(key)
+            .filter(|entry| entry.is_live(now))
+            .map(|entry| entry.value.clone())
+    }
+}
+
+impl KVStore for InMemoryKV {
+    async fn read(&self, key: String) -> Result<Option<String>, KVError> {
+        self.inject_faults().await?;
+        Ok(self.get(&key, Instant::now()))
+    }
+
+    async fn write(&self, key: String, value: String) -> Result<(), KVError> {
+        self.inject_faults().await?;
+        self.insert(key, value, None);
+        Ok(())
+    }
+
+    async fn write_with_ttl(
+        &self,
+        key: String,
+        value: String,
+        ttl: Duration,
+    ) -> Result<(), KVError> {
+        self.inject_faults().await?;
+        self.insert(key, value, Some(ttl));
+        Ok(())
+    }
+
+    async fn write_if_absent(
+        &self,
+        key: String,
+        value: String,
+        ttl: Option<Duration>,
+    ) -> Result<bool, KVError> {
+        self.inject_faults().await?;
+        let now = Instant::now();
+        let mut entries = self.entries.write().unwrap();
+        if entries.get(&key).is_some_and(|entry| entry.is_live(now)) {
+            return Ok(false);
+        }
+        entries.insert(
+            key,
+            Entry {
+                value,
+                expires_at: ttl.map(|ttl| now + ttl),
+            },
+        );
+        Ok(true)
+    }
+
+    async fn replace(&self, key: String, value: String) -> Result<bool, KVError> {
+        self.inject_faults().await?;
+        let now = Instant::now();
+        let mut entries = self.entries.write().unwrap();
+        match entries.get_mut(&key).filter(|entry| entry.is_live(now)) {
+            Some(entry) => {
+                entry.value = value;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    async fn compare_and_swap(
+        &self,
+        key: String,
+        current: String,
+        value: String,
+    ) -> Result<bool, KVError> {
+        self.inject_faults().await?;
+        let now = Instant::now();
+        let mut entries = self.entries.write().unwrap();
+        match entries
+            .get_mut(&key)
+            .filter(|entry| entry.is_live(now) && entry.value == current)
+        {
+            Some(entry) => {
+                entry.value = value;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    async fn take(&self, key: String) -> Result<Option<String>, KVError> {
+        self.inject_faults().await?;
+        let entry = self.entries.write().unwrap().remove(&key);
+        Ok(entry
+            .filter(|entry| entry.is_live(Instant::now()))
+            .map(|entry| entry.value))
+    }
+
+    async fn delete(&self, key: String) -> Result<(), KVError> {
+        self.inject_faults().await?;
+        self.entries.write().unwrap().remove(&key);
+        self.sets.write().unwrap().remove(&key);
+        Ok(())
+    }
+
+    async fn read_many(&self, keys: Vec<String>) -> Result<Vec<Option<String>>, KVError> {
+        self.inject_faults().await?;
+        let now = Instant::now();
+        Ok(keys.iter().map(|key| self.get(key, now)).collect())
+    }
+
+    async fn write_many(&self, entries: Vec<(String, String)>) -> Result<(), KVError> {
+        self.inject_faults().await?;
+        let mut map = self.entries.write().unwrap();
+        for (key, value) in entries {
+            map.insert(
+                key,
+                Entry {
+                    value,
+                    expires_at: None,
+                },
+            );
+        }
+        Ok(())
+    }
+
+    async fn scan_prefix(&self, prefix: String) -> Result<Vec<(String, String)>, KVError> {
+        self.inject_faults().await?;
+        let now = Instant::now();
+        let mut entries = self.entries.write().unwrap();
+        entries.retain(|_, entry| entry.is_live(now));
+        Ok(entries
+            .iter()
+            .filter(|(key, _)| key.starts_with(&prefix))
+            .map(|(key, entry)| (key.clone(), entry.value.clone()))
+            .collect())
+    }
+
+    async fn set_add(&self, key: String, member: String, ttl: Duration) -> Result<(), KVError> {
+        self.inject_faults().await?;
+        let now = Instant::now();
+        let mut sets = self.sets.write().unwrap();
+        let set = sets.entry(key).or_insert_with(|| SetEntry {
+            members: HashSet::new(),
+            expires_at: now,
+        });
+        if set.expires_at <= now {
+            set.members.clear();
+        }
+        set.members.insert(member);
+        set.expires_at = now + ttl;
+        Ok(())
+    }
+
+    async fn set_remove(&self, key: String, member: String) -> Result<(), KVError> {
+        self.inject_faults().await?;
+        if let Some(set) = self.sets.write().unwrap().get_mut(&key) {
+            set.members.remove(&member);
+        }
+        Ok(())
+    }
+
+    async fn set_members(&self, key: String) -> Result<Vec<String>, KVError> {
+        self.inject_faults().await?;
+        let now = Instant::now();
+        let sets = self.sets.read().unwrap();
+        Ok(sets
+            .get(&key)
+            .filter(|set| set.expires_at > now)
+            .map(|set| set.members.iter().cloned().collect())
+            .unwrap_or_default())
+    }
+
+    async fn take_token(
+        &self,
+        key: String,
+        bucket: TokenBucket,
+    ) -> Result<Option<Duration>, KVError> {
+        self.inject_faults().await?;
+        let now = Instant::now();
+        let mut entries = self.entries.write().unwrap();
+        let state = entries
+            .get(&key)
+            .filter(|entry| entry.is_live(now))
+            .map(|entry| entry.value.as_str());
+        let (value, wait) = bucket.take(state, super::now_ms());
+        entries.insert(
+            key,
+            Entry {
+                value,
+                expires_at: Some(now + bucket.period),
+            },
+        );
+        Ok(wait)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn entries_expire_after_ttl() {
+        let kv = InMemoryKV::new();
+        kv.write_with_ttl("short".into(), "1".into(), Duration::from_millis(10))
+            .await
+            .unwrap();
+        kv.write("long".into(), "2".into()).await.unwrap();
+        assert_eq!(kv.read("short".into()).await.unwrap().as_deref(), Some("1"));
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(kv.read("short".into()).await.unwrap(), None);
+        assert_eq!(
+            kv.scan_prefix(String::new()).await.unwrap(),
+            vec![("long".to_string(), "2".to_string())]
+        );
+    }
+
+    #[tokio::test]
+    async fn sets_expire_as_a_whole() {
+        let kv = InMemoryKV::new();
+        let ttl = Duration::from_millis(10);
+        kv.set_add("set".into(), "a".into(), ttl).await.unwrap();
+        kv.set_add("set".into(), "b".into(), ttl).await.unwrap();
+        kv.set_remove("set".into(), "a".into()).await.unwrap();
+        assert_eq!(kv.set_members("set".into()).await.unwrap(), vec!["b"]);
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(kv.set_members("set".into()).await.unwrap().is_empty());
+        kv.set_add("set".into(), "c".into(), ttl).await.unwrap();
+        assert_eq!(kv.set_members("set".into()).await.unwrap(), vec!["c"]);
+    }
+
+    #[tokio::test]
+    async fn write_if_absent_only_writes_once() {
+        let kv = InMemoryKV::new();
+        let ttl = Some(Duration::from_millis(10));
+        assert!(kv
+            .write_if_absent("jti".into(), "1".into(), ttl)
+            .await
+            .unwrap());
+        assert!(!kv
+            .write_if_absent("jti".into(), "2".into(), ttl)
+            .await
+            .unwrap());
+        assert_eq!(kv.read("jti".into()).await.unwrap().as_deref(), Some("1"));
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(kv
+            .write_if_absent("jti".into(), "3".into(), ttl)
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn values_are_swapped_and_taken_once() {
+        let kv = InMemoryKV::new();
+        kv.write("key".into(), "1".into()).await.unwrap();
+        assert!(!kv
+            .compare_and_swap("key".into(), "0".into(), "2".into())
+            .await
+            .unwrap());
+        assert!(kv
+            .compare_and_swap("key".into(), "1".into(), "2".into())
+            .await
+            .unwrap());
+
+        assert_eq!(kv.take("key".into()).await.unwrap().as_deref(), Some("2"));
+        assert_eq!(kv.take("key".into()).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn token_bucket_refills_over_time() {
+        let kv = InMemoryKV::new();
+        let bucket = TokenBucket {
+            capacity: 2,
+            period: Duration::from_millis(100),
+        };
+        for _ in 0..2 {
+            assert_eq!(kv.take_token("ip".into(), bucket).await.unwrap(), None);
+        }
+        let wait = kv.take_token("ip".into(), bucket).await.unwrap().unwrap();
+        assert!(wait <= Duration::from_millis(50), "{wait:?}");
+
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        assert_eq!(kv.take_token("ip".into(), bucket).await.unwrap(), None);
+        assert!(kv.take_token("ip".into(), bucket).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn injected_errors_fail_operations() {
+        let kv = InMemoryKV::with_faults(FaultConfig {
+            latency: Duration::ZERO,
+            error_rate: 1.0,
+        });
+        assert!(kv.write("key".into(), "value".into()).await.is_err());
+    }
+}
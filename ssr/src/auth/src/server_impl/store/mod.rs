@@ -1,10 +1,16 @@
 pub mod dragonfly_kv;
+pub mod encrypted_kv;
+pub mod in_memory_kv;
 pub mod redb_kv;
 pub mod redis_kv;
+mod redis_ops;
+
+use std::time::Duration;
 
 use enum_dispatch::enum_dispatch;
 use redis::RedisError;
 use thiserror::Error;
+use yral_canisters_common::utils::time::current_epoch;
 
 #[derive(Error, Debug)]
 pub enum KVError {
@@ -18,12 +24,107 @@ pub enum KVError {
     Bb8(#[from] bb8::RunError<RedisError>),
     #[error("{0}")]
     Other(#[from] anyhow::Error),
+    #[error("secret encryption err: {0}")]
+    Crypto(String),
 }
 
 #[enum_dispatch]
-pub(crate) trait KVStore: Send {
+#[allow(async_fn_in_trait)]
+pub trait KVStore: Send {
     async fn read(&self, key: String) -> Result<Option<String>, KVError>;
+    /// Writes a value that never expires, clearing any previous expiry
     async fn write(&self, key: String, value: String) -> Result<(), KVError>;
+    /// Writes a value that is no longer readable after `ttl`
+    async fn write_with_ttl(
+        &self,
+        key: String,
+        value: String,
+        ttl: Duration,
+    ) -> Result<(), KVError>;
+    /// Atomically writes a value with `ttl` (or without an expiry)
+    /// unless `key` already holds a live value, returns whether the value was written
+    async fn write_if_absent(
+        &self,
+        key: String,
+        value: String,
+        ttl: Option<Duration>,
+    ) -> Result<bool, KVError>;
+    /// Atomically replaces the value of `key` if it holds a live value, keeping its expiry
+    /// returns whether the value was replaced
+    async fn replace(&self, key: String, value: String) -> Result<bool, KVError>;
+    /// Atomically replaces the value of `key` if it still is `current`, keeping its expiry
+    /// returns whether the value was replaced
+    async fn compare_and_swap(
+        &self,
+        key: String,
+        current: String,
+        value: String,
+    ) -> Result<bool, KVError>;
+    /// Atomically reads and deletes `key`, a live value is only ever returned once
+    async fn take(&self, key: String) -> Result<Option<String>, KVError>;
+    async fn delete(&self, key: String) -> Result<(), KVError>;
+    /// Values are returned in the same order as `keys`
+    async fn read_many(&self, keys: Vec<String>) -> Result<Vec<Option<String>>, KVError>;
+    async fn write_many(&self, entries: Vec<(String, String)>) -> Result<(), KVError>;
+    /// All live entries whose key starts with `prefix`, sets are not included
+    ///
+    /// walks the whole keyspace on redis backends, prefer a set of keys for hot paths
+    async fn scan_prefix(&self, prefix: String) -> Result<Vec<(String, String)>, KVError>;
+    /// Atomically adds `member` to the set at `key`, the whole set expires after `ttl`
+    ///
+    /// sets live next to values, a key must only ever hold one or the other
+    async fn set_add(&self, key: String, member: String, ttl: Duration) -> Result<(), KVError>;
+    async fn set_remove(&self, key: String, member: String) -> Result<(), KVError>;
+    /// Members of the set at `key`, in no particular order
+    async fn set_members(&self, key: String) -> Result<Vec<String>, KVError>;
+    /// Atomically takes a token from the bucket at `key`
+    /// returns how long until the next token if the bucket is empty
+    async fn take_token(
+        &self,
+        key: String,
+        bucket: TokenBucket,
+    ) -> Result<Option<Duration>, KVError>;
+}
+
+fn now_ms() -> u64 {
+    current_epoch().as_millis() as u64
+}
+
+/// Bucket of up to `capacity` tokens, refilled evenly over `period`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TokenBucket {
+    pub capacity: u32,
+    pub period: Duration,
+}
+
+impl TokenBucket {
+    /// Takes a token from `state`, a bucket stored as `<tokens> <updated at ms>`
+    ///
+    /// returns the new state, and how long until the next token if the bucket was empty.
+    /// Must be kept in sync with the script in `redis_ops::take_token`
+    fn take(&self, state: Option<&str>, now_ms: u64) -> (String, Option<Duration>) {
+        let capacity = self.capacity as f64;
+        let period_ms = self.period.as_millis() as f64;
+        let tokens = state
+            .and_then(|state| {
+                let (tokens, updated) = state.split_once(' ')?;
+                Some((tokens.parse::<f64>().ok()?, updated.parse::<u64>().ok()?))
+            })
+            .map(|(tokens, updated)| {
+                let refill = now_ms.saturating_sub(updated) as f64 * capacity / period_ms;
+                (tokens + refill).min(capacity)
+            })
+            .unwrap_or(capacity);
+
+        if tokens >= 1.0 {
+            return (format!("{} {now_ms}", tokens - 1.0), None);
+        }
+        let wait_ms = ((1.0 - tokens) * period_ms / capacity).ceil().max(1.0);
+        (
+            format!("{tokens} {now_ms}"),
+            Some(Duration::from_millis(wait_ms as u64)),
+        )
+    }
 }
 
 #[derive(Clone)]
@@ -32,4 +133,5 @@ pub enum KVStoreImpl {
     ReDB(redb_kv::ReDBKV),
     Redis(redis_kv::RedisKV),
     DragonflyKV(dragonfly_kv::DragonflyKV),
+    InMemory(in_memory_kv::InMemoryKV),
 }
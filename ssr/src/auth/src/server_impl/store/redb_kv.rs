@@ -1,12 +1,20 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
-use redb::{Database, TableDefinition};
+use redb::{
+    Database, MultimapTableDefinition, ReadableMultimapTable, ReadableTable, TableDefinition,
+};
 use tokio::task::spawn_blocking;
 
-use super::{KVError, KVStore};
+use super::{now_ms, KVError, KVStore, TokenBucket};
 
 const TABLE: TableDefinition<&str, &str> = TableDefinition::new("kv");
 const RAW_METADATA_TABLE: TableDefinition<&str, &str> = TableDefinition::new("kv-meta");
+/// expiry of keys written with a ttl, in milliseconds since epoch
+const EXPIRY_TABLE: TableDefinition<&str, u64> = TableDefinition::new("kv-expiry");
+const SET_TABLE: MultimapTableDefinition<&str, &str> = MultimapTableDefinition::new("kv-sets");
+
+/// How often entries whose ttl has passed are removed from disk
+const EXPIRY_SWEEP_INTERVAL: Duration = Duration::from_secs(10 * 60);
 
 #[derive(Clone)]
 pub struct ReDBKV(Arc<Database>);
@@ -14,11 +22,23 @@ pub struct ReDBKV(Arc<Database>);
 impl ReDBKV {
     #[allow(clippy::result_large_err)]
     pub fn new() -> Result<Self, redb::Error> {
-        let db = Database::create("./redb-kv.db")?;
+        Self::with_database(Database::create("./redb-kv.db")?)
+    }
+
+    #[cfg(test)]
+    fn in_memory() -> Result<Self, redb::Error> {
+        let db = Database::builder().create_with_backend(redb::backends::InMemoryBackend::new())?;
+        Self::with_database(db)
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn with_database(db: Database) -> Result<Self, redb::Error> {
         let write_txn = db.begin_write()?;
         {
             write_txn.open_table(TABLE)?;
             write_txn.open_table(RAW_METADATA_TABLE)?;
+            write_txn.open_table(EXPIRY_TABLE)?;
+            write_txn.open_multimap_table(SET_TABLE)?;
         }
         write_txn.commit()?;
         Ok(Self(Arc::new(db)))
@@ -32,6 +52,64 @@ impl ReDBKV {
         let db = self.0.clone();
         spawn_blocking(move || f(&db).map_err(|e| e.into()))
     }
+
+    /// Removes every entry whose ttl has passed, returns how many were removed
+    pub async fn purge_expired(&self) -> Result<usize, KVError> {
+        self.spawn_blocking(|db| {
+            let write_txn = db.begin_write()?;
+            let purged = {
+                let mut table = write_txn.open_table(TABLE)?;
+                let mut expiry = write_txn.open_table(EXPIRY_TABLE)?;
+                let mut sets = write_txn.open_multimap_table(SET_TABLE)?;
+                let now = now_ms();
+                let mut expired = vec![];
+                for entry in expiry.iter()? {
+                    let (key, expires_at) = entry?;
+                    if expires_at.value() <= now {
+                        expired.push(key.value().to_string());
+                    }
+                }
+                for key in &expired {
+                    table.remove(key.as_str())?;
+                    sets.remove_all(key.as_str())?;
+                    expiry.remove(key.as_str())?;
+                }
+                expired.len()
+            };
+            write_txn.commit()?;
+            Ok(purged)
+        })
+        .await
+        .unwrap()
+    }
+
+    /// Purges expired entries every [`EXPIRY_SWEEP_INTERVAL`] in the background
+    pub fn spawn_expiry_sweep(&self) {
+        let kv = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(EXPIRY_SWEEP_INTERVAL);
+            loop {
+                interval.tick().await;
+                match kv.purge_expired().await {
+                    Ok(0) => {}
+                    Ok(purged) => tracing::debug!("purged {purged} expired kv entries"),
+                    Err(e) => tracing::warn!("failed to purge expired kv entries: {e}"),
+                }
+            }
+        });
+    }
+}
+
+/// redb has no native expiry, expired entries are hidden from reads
+/// until they are overwritten or purged by [`ReDBKV::purge_expired`]
+fn is_live(
+    expiry: &impl ReadableTable<&'static str, u64>,
+    key: &str,
+    now: u64,
+) -> Result<bool, redb::Error> {
+    Ok(expiry
+        .get(key)?
+        .is_none_or(|expires_at| expires_at.value() > now))
 }
 
 impl KVStore for ReDBKV {
@@ -40,6 +118,10 @@ impl KVStore for ReDBKV {
             let read_txn = db.begin_read()?;
             let value = {
                 let table = read_txn.open_table(TABLE)?;
+                let expiry = read_txn.open_table(EXPIRY_TABLE)?;
+                if !is_live(&expiry, &key, now_ms())? {
+                    return Ok(None);
+                }
                 let v = table.get(key.as_str())?;
                 v.map(|ag| ag.value().to_string())
             };
@@ -55,6 +137,8 @@ impl KVStore for ReDBKV {
             {
                 let mut table = write_txn.open_table(TABLE)?;
                 table.insert(key.as_str(), value.as_str())?;
+                let mut expiry = write_txn.open_table(EXPIRY_TABLE)?;
+                expiry.remove(key.as_str())?;
             }
             write_txn.commit()?;
             Ok::<_, redb::Error>(())
@@ -62,4 +146,383 @@ impl KVStore for ReDBKV {
         .await
         .unwrap()
     }
+
+    async fn write_with_ttl(
+        &self,
+        key: String,
+        value: String,
+        ttl: Duration,
+    ) -> Result<(), KVError> {
+        self.spawn_blocking(move |db| {
+            let write_txn = db.begin_write()?;
+            {
+                let mut table = write_txn.open_table(TABLE)?;
+                table.insert(key.as_str(), value.as_str())?;
+                let mut expiry = write_txn.open_table(EXPIRY_TABLE)?;
+                expiry.insert(key.as_str(), now_ms() + ttl.as_millis() as u64)?;
+            }
+            write_txn.commit()?;
+            Ok(())
+        })
+        .await
+        .unwrap()
+    }
+
+    async fn write_if_absent(
+        &self,
+        key: String,
+        value: String,
+        ttl: Option<Duration>,
+    ) -> Result<bool, KVError> {
+        self.spawn_blocking(move |db| {
+            let write_txn = db.begin_write()?;
+            let written = {
+                let mut table = write_txn.open_table(TABLE)?;
+                let mut expiry = write_txn.open_table(EXPIRY_TABLE)?;
+                let now = now_ms();
+                let exists = table.get(key.as_str())?.is_some() && is_live(&expiry, &key, now)?;
+                if !exists {
+                    table.insert(key.as_str(), value.as_str())?;
+                    match ttl {
+                        Some(ttl) => expiry.insert(key.as_str(), now + ttl.as_millis() as u64)?,
+                        None => expiry.remove(key.as_str())?,
+                    };
+                }
+                !exists
+            };
+            write_txn.commit()?;
+            Ok(written)
+        })
+        .await
+        .unwrap()
+    }
+
+    async fn replace(&self, key: String, value: String) -> Result<bool, KVError> {
+        self.spawn_blocking(move |db| {
+            let write_txn = db.begin_write()?;
+            let replaced = {
+                let mut table = write_txn.open_table(TABLE)?;
+                let expiry = write_txn.open_table(EXPIRY_TABLE)?;
+                let exists =
+                    table.get(key.as_str())?.is_some() && is_live(&expiry, &key, now_ms())?;
+                if exists {
+                    table.insert(key.as_str(), value.as_str())?;
+                }
+                exists
+            };
+            write_txn.commit()?;
+            Ok(replaced)
+        })
+        .await
+        .unwrap()
+    }
+
+    async fn compare_and_swap(
+        &self,
+        key: String,
+        current: String,
+        value: String,
+    ) -> Result<bool, KVError> {
+        self.spawn_blocking(move |db| {
+            let write_txn = db.begin_write()?;
+            let swapped = {
+                let mut table = write_txn.open_table(TABLE)?;
+                let expiry = write_txn.open_table(EXPIRY_TABLE)?;
+                let matches = table
+                    .get(key.as_str())?
+                    .is_some_and(|stored| stored.value() == current)
+                    && is_live(&expiry, &key, now_ms())?;
+                if matches {
+                    table.insert(key.as_str(), value.as_str())?;
+                }
+                matches
+            };
+            write_txn.commit()?;
+            Ok(swapped)
+        })
+        .await
+        .unwrap()
+    }
+
+    async fn take(&self, key: String) -> Result<Option<String>, KVError> {
+        self.spawn_blocking(move |db| {
+            let write_txn = db.begin_write()?;
+            let value = {
+                let mut table = write_txn.open_table(TABLE)?;
+                let mut expiry = write_txn.open_table(EXPIRY_TABLE)?;
+                let live = is_live(&expiry, &key, now_ms())?;
+                let value = table
+                    .remove(key.as_str())?
+                    .map(|stored| stored.value().to_string());
+                expiry.remove(key.as_str())?;
+                value.filter(|_| live)
+            };
+            write_txn.commit()?;
+            Ok(value)
+        })
+        .await
+        .unwrap()
+    }
+
+    async fn delete(&self, key: String) -> Result<(), KVError> {
+        self.spawn_blocking(move |db| {
+            let write_txn = db.begin_write()?;
+            {
+                let mut table = write_txn.open_table(TABLE)?;
+                table.remove(key.as_str())?;
+                let mut sets = write_txn.open_multimap_table(SET_TABLE)?;
+                sets.remove_all(key.as_str())?;
+                let mut expiry = write_txn.open_table(EXPIRY_TABLE)?;
+                expiry.remove(key.as_str())?;
+            }
+            write_txn.commit()?;
+            Ok(())
+        })
+        .await
+        .unwrap()
+    }
+
+    async fn read_many(&self, keys: Vec<String>) -> Result<Vec<Option<String>>, KVError> {
+        self.spawn_blocking(move |db| {
+            let read_txn = db.begin_read()?;
+            let table = read_txn.open_table(TABLE)?;
+            let expiry = read_txn.open_table(EXPIRY_TABLE)?;
+            let now = now_ms();
+            keys.iter()
+                .map(|key| -> Result<_, redb::Error> {
+                    if !is_live(&expiry, key, now)? {
+                        return Ok(None);
+                    }
+                    Ok(table.get(key.as_str())?.map(|ag| ag.value().to_string()))
+                })
+                .collect()
+        })
+        .await
+        .unwrap()
+    }
+
+    async fn write_many(&self, entries: Vec<(String, String)>) -> Result<(), KVError> {
+        self.spawn_blocking(move |db| {
+            let write_txn = db.begin_write()?;
+            {
+                let mut table = write_txn.open_table(TABLE)?;
+                let mut expiry = write_txn.open_table(EXPIRY_TABLE)?;
+                for (key, value) in &entries {
+                    table.insert(key.as_str(), value.as_str())?;
+                    expiry.remove(key.as_str())?;
+                }
+            }
+            write_txn.commit()?;
+            Ok(())
+        })
+        .await
+        .unwrap()
+    }
+
+    async fn scan_prefix(&self, prefix: String) -> Result<Vec<(String, String)>, KVError> {
+        self.spawn_blocking(move |db| {
+            let read_txn = db.begin_read()?;
+            let table = read_txn.open_table(TABLE)?;
+            let expiry = read_txn.open_table(EXPIRY_TABLE)?;
+            let now = now_ms();
+            let mut entries = vec![];
+            for entry in table.range(prefix.as_str()..)? {
+                let (key, value) = entry?;
+                let key = key.value();
+                if !key.starts_with(prefix.as_str()) {
+                    break;
+                }
+                if is_live(&expiry, key, now)? {
+                    entries.push((key.to_string(), value.value().to_string()));
+                }
+            }
+            Ok(entries)
+        })
+        .await
+        .unwrap()
+    }
+
+    async fn set_add(&self, key: String, member: String, ttl: Duration) -> Result<(), KVError> {
+        self.spawn_blocking(move |db| {
+            let write_txn = db.begin_write()?;
+            {
+                let mut sets = write_txn.open_multimap_table(SET_TABLE)?;
+                let mut expiry = write_txn.open_table(EXPIRY_TABLE)?;
+                let now = now_ms();
+                if !is_live(&expiry, &key, now)? {
+                    sets.remove_all(key.as_str())?;
+                }
+                sets.insert(key.as_str(), member.as_str())?;
+                expiry.insert(key.as_str(), now + ttl.as_millis() as u64)?;
+            }
+            write_txn.commit()?;
+            Ok(())
+        })
+        .await
+        .unwrap()
+    }
+
+    async fn set_remove(&self, key: String, member: String) -> Result<(), KVError> {
+        self.spawn_blocking(move |db| {
+            let write_txn = db.begin_write()?;
+            {
+                let mut sets = write_txn.open_multimap_table(SET_TABLE)?;
+                sets.remove(key.as_str(), member.as_str())?;
+            }
+            write_txn.commit()?;
+            Ok(())
+        })
+        .await
+        .unwrap()
+    }
+
+    async fn set_members(&self, key: String) -> Result<Vec<String>, KVError> {
+        self.spawn_blocking(move |db| {
+            let read_txn = db.begin_read()?;
+            let sets = read_txn.open_multimap_table(SET_TABLE)?;
+            let expiry = read_txn.open_table(EXPIRY_TABLE)?;
+            if !is_live(&expiry, &key, now_ms())? {
+                return Ok(vec![]);
+            }
+            let mut members = vec![];
+            for member in sets.get(key.as_str())? {
+                members.push(member?.value().to_string());
+            }
+            Ok(members)
+        })
+        .await
+        .unwrap()
+    }
+
+    async fn take_token(
+        &self,
+        key: String,
+        bucket: TokenBucket,
+    ) -> Result<Option<Duration>, KVError> {
+        self.spawn_blocking(move |db| {
+            let write_txn = db.begin_write()?;
+            let wait = {
+                let mut table = write_txn.open_table(TABLE)?;
+                let mut expiry = write_txn.open_table(EXPIRY_TABLE)?;
+                let now = now_ms();
+                let state = if is_live(&expiry, &key, now)? {
+                    table.get(key.as_str())?.map(|ag| ag.value().to_string())
+                } else {
+                    None
+                };
+                let (state, wait) = bucket.take(state.as_deref(), now);
+                table.insert(key.as_str(), state.as_str())?;
+                expiry.insert(key.as_str(), now + bucket.period.as_millis() as u64)?;
+                wait
+            };
+            write_txn.commit()?;
+            Ok(wait)
+        })
+        .await
+        .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn entries_expire_after_ttl() {
+        let kv = ReDBKV::in_memory().unwrap();
+        kv.write_with_ttl("short".into(), "1".into(), Duration::from_millis(10))
+            .await
+            .unwrap();
+        kv.write("long".into(), "2".into()).await.unwrap();
+        assert_eq!(kv.read("short".into()).await.unwrap().as_deref(), Some("1"));
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(kv.read("short".into()).await.unwrap(), None);
+        assert_eq!(
+            kv.read_many(vec!["short".into(), "long".into()])
+                .await
+                .unwrap(),
+            vec![None, Some("2".to_string())]
+        );
+        assert_eq!(
+            kv.scan_prefix(String::new()).await.unwrap(),
+            vec![("long".to_string(), "2".to_string())]
+        );
+    }
+
+    #[tokio::test]
+    async fn write_clears_a_previous_ttl() {
+        let kv = ReDBKV::in_memory().unwrap();
+        kv.write_with_ttl("key".into(), "1".into(), Duration::from_millis(10))
+            .await
+            .unwrap();
+        kv.write("key".into(), "2".into()).await.unwrap();
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(kv.read("key".into()).await.unwrap().as_deref(), Some("2"));
+    }
+
+    #[tokio::test]
+    async fn write_if_absent_only_writes_once() {
+        let kv = ReDBKV::in_memory().unwrap();
+        let ttl = Some(Duration::from_millis(10));
+        assert!(kv
+            .write_if_absent("jti".into(), "1".into(), ttl)
+            .await
+            .unwrap());
+        assert!(!kv
+            .write_if_absent("jti".into(), "2".into(), ttl)
+            .await
+            .unwrap());
+        assert_eq!(kv.read("jti".into()).await.unwrap().as_deref(), Some("1"));
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(kv
+            .write_if_absent("jti".into(), "3".into(), ttl)
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn sets_expire_as_a_whole() {
+        let kv = ReDBKV::in_memory().unwrap();
+        let ttl = Duration::from_millis(10);
+        kv.set_add("set".into(), "a".into(), ttl).await.unwrap();
+        kv.set_add("set".into(), "b".into(), ttl).await.unwrap();
+        kv.set_remove("set".into(), "a".into()).await.unwrap();
+        assert_eq!(kv.set_members("set".into()).await.unwrap(), vec!["b"]);
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(kv.set_members("set".into()).await.unwrap().is_empty());
+        kv.set_add("set".into(), "c".into(), ttl).await.unwrap();
+        assert_eq!(kv.set_members("set".into()).await.unwrap(), vec!["c"]);
+    }
+
+    #[tokio::test]
+    async fn purge_removes_only_expired_entries() {
+        let kv = ReDBKV::in_memory().unwrap();
+        kv.write_with_ttl("short".into(), "1".into(), Duration::from_millis(10))
+            .await
+            .unwrap();
+        kv.write_with_ttl("later".into(), "2".into(), Duration::from_secs(60))
+            .await
+            .unwrap();
+        kv.write("long".into(), "3".into()).await.unwrap();
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(kv.purge_expired().await.unwrap(), 1);
+        assert_eq!(kv.purge_expired().await.unwrap(), 0);
+
+        let db = kv.0.clone();
+        let remaining = spawn_blocking(move || -> Result<_, redb::Error> {
+            let read_txn = db.begin_read()?;
+            let table = read_txn.open_table(TABLE)?;
+            let expiry = read_txn.open_table(EXPIRY_TABLE)?;
+            Ok((table.len()?, expiry.len()?))
+        })
+        .await
+        .unwrap()
+        .unwrap();
+        assert_eq!(remaining, (2, 1));
+    }
 }
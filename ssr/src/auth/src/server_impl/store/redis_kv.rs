@@ -1,7 +1,12 @@
+use std::time::Duration;
+
 use bb8_redis::RedisConnectionManager;
 use redis::{AsyncCommands, RedisError};
 
-use super::{KVError, KVStore};
+use super::{
+    redis_ops::{self, AUTH_FIELD},
+    KVError, KVStore, TokenBucket,
+};
 
 #[derive(Clone)]
 pub struct RedisKV(bb8::Pool<RedisConnectionManager>);
@@ -13,8 +18,6 @@ impl RedisKV {
     }
 }
 
-const AUTH_FIELD: &str = "auth";
-
 impl KVStore for RedisKV {
     async fn read(&self, key: String) -> Result<Option<String>, KVError> {
         let mut con = self.0.get().await?;
@@ -24,7 +27,96 @@ impl KVStore for RedisKV {
 
     async fn write(&self, key: String, value: String) -> Result<(), KVError> {
         let mut con = self.0.get().await?;
-        con.hset::<_, _, _, ()>(key, AUTH_FIELD, value).await?;
+        redis_ops::write(&mut *con, &key, &value).await?;
+        Ok(())
+    }
+
+    async fn write_with_ttl(
+        &self,
+        key: String,
+        value: String,
+        ttl: Duration,
+    ) -> Result<(), KVError> {
+        let mut con = self.0.get().await?;
+        redis_ops::write_with_ttl(&mut *con, &key, &value, ttl).await?;
+        Ok(())
+    }
+
+    async fn write_if_absent(
+        &self,
+        key: String,
+        value: String,
+        ttl: Option<Duration>,
+    ) -> Result<bool, KVError> {
+        let mut con = self.0.get().await?;
+        Ok(redis_ops::write_if_absent(&mut *con, &key, &value, ttl).await?)
+    }
+
+    async fn replace(&self, key: String, value: String) -> Result<bool, KVError> {
+        let mut con = self.0.get().await?;
+        Ok(redis_ops::replace(&mut *con, &key, &value).await?)
+    }
+
+    async fn compare_and_swap(
+        &self,
+        key: String,
+        current: String,
+        value: String,
+    ) -> Result<bool, KVError> {
+        let mut con = self.0.get().await?;
+        Ok(redis_ops::compare_and_swap(&mut *con, &key, &current, &value).await?)
+    }
+
+    async fn take(&self, key: String) -> Result<Option<String>, KVError> {
+        let mut con = self.0.get().await?;
+        Ok(redis_ops::take(&mut *con, &key).await?)
+    }
+
+    async fn delete(&self, key: String) -> Result<(), KVError> {
+        let mut con = self.0.get().await?;
+        con.del::<_, ()>(key).await?;
+        Ok(())
+    }
+
+    async fn read_many(&self, keys: Vec<String>) -> Result<Vec<Option<String>>, KVError> {
+        let mut con = self.0.get().await?;
+        Ok(redis_ops::read_many(&mut *con, &keys).await?)
+    }
+
+    async fn write_many(&self, entries: Vec<(String, String)>) -> Result<(), KVError> {
+        let mut con = self.0.get().await?;
+        redis_ops::write_many(&mut *con, &entries).await?;
         Ok(())
     }
+
+    async fn scan_prefix(&self, prefix: String) -> Result<Vec<(String, String)>, KVError> {
+        let mut con = self.0.get().await?;
+        Ok(redis_ops::scan_prefix(&mut *con, &prefix).await?)
+    }
+
+    async fn set_add(&self, key: String, member: String, ttl: Duration) -> Result<(), KVError> {
+        let mut con = self.0.get().await?;
+        redis_ops::set_add(&mut *con, &key, &member, ttl).await?;
+        Ok(())
+    }
+
+    async fn set_remove(&self, key: String, member: String) -> Result<(), KVError> {
+        let mut con = self.0.get().await?;
+        con.srem::<_, _, ()>(key, member).await?;
+        Ok(())
+    }
+
+    async fn set_members(&self, key: String) -> Result<Vec<String>, KVError> {
+        let mut con = self.0.get().await?;
+        Ok(con.smembers(key).await?)
+    }
+
+    async fn take_token(
+        &self,
+        key: String,
+        bucket: TokenBucket,
+    ) -> Result<Option<Duration>, KVError> {
+        let mut con = self.0.get().await?;
+        Ok(redis_ops::take_token(&mut *con, &key, bucket).await?)
+    }
 }
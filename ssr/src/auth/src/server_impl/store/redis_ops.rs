@@ -0,0 +1,268 @@
+//! Commands shared by the redis protocol backends
+//!
+//! Values live in the [`AUTH_FIELD`] field of a hash per key, so expiry is
+//! applied to the whole key. Sets are plain redis sets
+use std::time::Duration;
+
+use redis::{aio::ConnectionLike, RedisResult};
+
+use super::{now_ms, TokenBucket};
+
+pub(super) const AUTH_FIELD: &str = "auth";
+
+const SCAN_BATCH: usize = 500;
+
+/// Escapes glob metacharacters so `prefix` is matched literally by `SCAN MATCH`
+fn escape_glob(prefix: &str) -> String {
+    let mut escaped = String::with_capacity(prefix.len());
+    for c in prefix.chars() {
+        if matches!(c, '*' | '?' | '[' | ']' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+pub(super) async fn write(
+    con: &mut impl ConnectionLike,
+    key: &str,
+    value: &str,
+) -> RedisResult<()> {
+    redis::pipe()
+        .atomic()
+        .hset(key, AUTH_FIELD, value)
+        .ignore()
+        .persist(key)
+        .ignore()
+        .query_async(con)
+        .await
+}
+
+pub(super) async fn write_with_ttl(
+    con: &mut impl ConnectionLike,
+    key: &str,
+    value: &str,
+    ttl: Duration,
+) -> RedisResult<()> {
+    redis::pipe()
+        .atomic()
+        .hset(key, AUTH_FIELD, value)
+        .ignore()
+        .pexpire(key, ttl.as_millis() as i64)
+        .ignore()
+        .query_async(con)
+        .await
+}
+
+pub(super) async fn write_if_absent(
+    con: &mut impl ConnectionLike,
+    key: &str,
+    value: &str,
+    ttl: Option<Duration>,
+) -> RedisResult<bool> {
+    // the expiry must only be applied if the field was set, which a pipeline can't express.
+    // A ttl of 0 leaves the key without an expiry
+    let script = redis::Script::new(
+        r"
+        if redis.call('HSETNX', KEYS[1], ARGV[1], ARGV[2]) == 1 then
+            if tonumber(ARGV[3]) > 0 then
+                redis.call('PEXPIRE', KEYS[1], ARGV[3])
+            end
+            return 1
+        end
+        return 0
+        ",
+    );
+    script
+        .key(key)
+        .arg(AUTH_FIELD)
+        .arg(value)
+        .arg(ttl.map_or(0, |ttl| ttl.as_millis().max(1) as i64))
+        .invoke_async(con)
+        .await
+}
+
+pub(super) async fn replace(
+    con: &mut impl ConnectionLike,
+    key: &str,
+    value: &str,
+) -> RedisResult<bool> {
+    // HSET leaves the expiry of the key untouched
+    let script = redis::Script::new(
+        r"
+        if redis.call('HEXISTS', KEYS[1], ARGV[1]) == 1 then
+            redis.call('HSET', KEYS[1], ARGV[1], ARGV[2])
+            return 1
+        end
+        return 0
+        ",
+    );
+    script
+        .key(key)
+        .arg(AUTH_FIELD)
+        .arg(value)
+        .invoke_async(con)
+        .await
+}
+
+pub(super) async fn compare_and_swap(
+    con: &mut impl ConnectionLike,
+    key: &str,
+    current: &str,
+    value: &str,
+) -> RedisResult<bool> {
+    let script = redis::Script::new(
+        r"
+        if redis.call('HGET', KEYS[1], ARGV[1]) == ARGV[2] then
+            redis.call('HSET', KEYS[1], ARGV[1], ARGV[3])
+            return 1
+        end
+        return 0
+        ",
+    );
+    script
+        .key(key)
+        .arg(AUTH_FIELD)
+        .arg(current)
+        .arg(value)
+        .invoke_async(con)
+        .await
+}
+
+pub(super) async fn take(con: &mut impl ConnectionLike, key: &str) -> RedisResult<Option<String>> {
+    let (value,): (Option<String>,) = redis::pipe()
+        .atomic()
+        .hget(key, AUTH_FIELD)
+        .del(key)
+        .ignore()
+        .query_async(con)
+        .await?;
+    Ok(value)
+}
+
+pub(super) async fn read_many(
+    con: &mut impl ConnectionLike,
+    keys: &[String],
+) -> RedisResult<Vec<Option<String>>> {
+    if keys.is_empty() {
+        return Ok(vec![]);
+    }
+    let mut pipe = redis::pipe();
+    for key in keys {
+        pipe.hget(key, AUTH_FIELD);
+    }
+    pipe.query_async(con).await
+}
+
+pub(super) async fn write_many(
+    con: &mut impl ConnectionLike,
+    entries: &[(String, String)],
+) -> RedisResult<()> {
+    if entries.is_empty() {
+        return Ok(());
+    }
+    let mut pipe = redis::pipe();
+    pipe.atomic();
+    for (key, value) in entries {
+        pipe.hset(key, AUTH_FIELD, value)
+            .ignore()
+            .persist(key)
+            .ignore();
+    }
+    pipe.query_async(con).await
+}
+
+pub(super) async fn scan_prefix(
+    con: &mut impl ConnectionLike,
+    prefix: &str,
+) -> RedisResult<Vec<(String, String)>> {
+    let pattern = format!("{}*", escape_glob(prefix));
+    let mut keys = vec![];
+    let mut cursor = 0u64;
+    loop {
+        let (next, batch): (u64, Vec<String>) = redis::cmd("SCAN")
+            .arg(cursor)
+            .arg("MATCH")
+            .arg(&pattern)
+            .arg("COUNT")
+            .arg(SCAN_BATCH)
+            // values are hashes, sets under the same prefix would fail the HGET
+            .arg("TYPE")
+            .arg("hash")
+            .query_async(con)
+            .await?;
+        keys.extend(batch);
+        if next == 0 {
+            break;
+        }
+        cursor = next;
+    }
+    // SCAN may return a key more than once
+    keys.sort_unstable();
+    keys.dedup();
+
+    let values = read_many(con, &keys).await?;
+    Ok(keys
+        .into_iter()
+        .zip(values)
+        .filter_map(|(key, value)| Some((key, value?)))
+        .collect())
+}
+
+pub(super) async fn set_add(
+    con: &mut impl ConnectionLike,
+    key: &str,
+    member: &str,
+    ttl: Duration,
+) -> RedisResult<()> {
+    redis::pipe()
+        .atomic()
+        .sadd(key, member)
+        .ignore()
+        .pexpire(key, ttl.as_millis() as i64)
+        .ignore()
+        .query_async(con)
+        .await
+}
+
+pub(super) async fn take_token(
+    con: &mut impl ConnectionLike,
+    key: &str,
+    bucket: TokenBucket,
+) -> RedisResult<Option<Duration>> {
+    // same math as `TokenBucket::take`, the bucket must be updated atomically
+    let script = redis::Script::new(
+        r"
+        local capacity = tonumber(ARGV[2])
+        local period = tonumber(ARGV[3])
+        local now = tonumber(ARGV[4])
+        local tokens = capacity
+        local state = redis.call('HGET', KEYS[1], ARGV[1])
+        if state then
+            local sep = string.find(state, ' ')
+            local prev = tonumber(string.sub(state, 1, sep - 1))
+            local updated = tonumber(string.sub(state, sep + 1))
+            tokens = math.min(capacity, prev + math.max(0, now - updated) * capacity / period)
+        end
+        local wait = 0
+        if tokens >= 1 then
+            tokens = tokens - 1
+        else
+            wait = math.max(1, math.ceil((1 - tokens) * period / capacity))
+        end
+        redis.call('HSET', KEYS[1], ARGV[1], tokens .. ' ' .. ARGV[4])
+        redis.call('PEXPIRE', KEYS[1], period)
+        return wait
+        ",
+    );
+    let wait_ms: u64 = script
+        .key(key)
+        .arg(AUTH_FIELD)
+        .arg(bucket.capacity)
+        .arg(bucket.period.as_millis() as u64)
+        .arg(now_ms())
+        .invoke_async(con)
+        .await?;
+    Ok((wait_ms > 0).then(|| Duration::from_millis(wait_ms)))
+}
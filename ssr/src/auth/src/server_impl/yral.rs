@@ -1,14 +1,13 @@
-use std::{ops::Deref, sync::LazyLock};
+use std::ops::Deref;
 
-use axum_extra::extract::{
-    cookie::{Cookie, Key, SameSite},
-    PrivateCookieJar, SignedCookieJar,
-};
 use candid::Principal;
-use consts::LoginProvider;
-use global_constants::USERNAME_MAX_LEN;
+use consts::{
+    yral_auth::{YRAL_AUTH_ISSUER_URL, YRAL_AUTH_TRUSTED_KEY},
+    LoginProvider,
+};
+use jsonwebtoken::{Algorithm, Validation};
 use leptos::prelude::*;
-use leptos_axum::{extract_with_state, ResponseOptions};
+use leptos_axum::ResponseOptions;
 use openidconnect::{
     core::{
         CoreAuthDisplay, CoreAuthPrompt, CoreAuthenticationFlow, CoreErrorResponseType,
@@ -18,24 +17,29 @@ use openidconnect::{
         CoreTokenType,
     },
     reqwest::async_http_client,
-    AdditionalClaims, AuthorizationCode, CsrfToken, EmptyExtraTokenFields, IdTokenFields,
-    LoginHint, Nonce, OAuth2TokenResponse, PkceCodeChallenge, PkceCodeVerifier, Scope,
+    AdditionalClaims, AuthorizationCode, CsrfToken, EmptyExtraTokenFields, IdTokenClaims,
+    IdTokenFields, LoginHint, Nonce, OAuth2TokenResponse, PkceCodeChallenge, RefreshToken, Scope,
     StandardErrorResponse, StandardTokenResponse,
 };
-use regex::Regex;
 use serde::{Deserialize, Serialize};
-use web_time::Duration;
 use yral_types::delegated_identity::DelegatedIdentityWire;
 
-use super::{set_cookies, update_user_identity};
-
-const PKCE_VERIFIER_COOKIE: &str = "google-pkce-verifier";
-const CSRF_TOKEN_COOKIE: &str = "google-csrf-token";
+use super::{
+    extract_signed_jar,
+    provider::{
+        consume_id_token, fallback_username, store_login_cookies, take_login_cookies,
+        IdentityProvider, LoginResult, YRAL_PROVIDER_ID,
+    },
+    store::KVStoreImpl,
+    update_user_identity,
+};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct YralAuthAdditionalTokenClaims {
     pub ext_is_anonymous: bool,
     pub ext_delegated_identity: DelegatedIdentityWire,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jti: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -50,6 +54,8 @@ pub struct YralAuthRefreshTokenClaims {
 
 impl AdditionalClaims for YralAuthAdditionalTokenClaims {}
 
+pub type YralIdTokenClaims = IdTokenClaims<YralAuthAdditionalTokenClaims, CoreGenderClaim>;
+
 pub type YralOAuthClient = openidconnect::Client<
     YralAuthAdditionalTokenClaims,
     CoreAuthDisplay,
@@ -78,9 +84,12 @@ pub type YralOAuthClient = openidconnect::Client<
     CoreRevocationErrorResponse,
 >;
 
-pub fn token_verifier() -> CoreIdTokenVerifier<'static> {
-    // TODO: use real impl
-    CoreIdTokenVerifier::new_insecure_without_verification()
+/// Checks ID tokens against the issuer's signing keys, fetched at startup,
+/// with this client as their audience
+pub fn token_verifier(oauth2: &YralOAuthClient) -> CoreIdTokenVerifier<'_> {
+    oauth2
+        .id_token_verifier()
+        .set_allowed_algs(vec![CoreJwsSigningAlgorithm::EcdsaP256Sha256])
 }
 
 #[derive(Serialize, Deserialize)]
@@ -89,134 +98,148 @@ struct OAuthState {
     pub client_redirect_uri: Option<String>,
 }
 
-pub async fn yral_auth_url_impl(
-    oauth2: YralOAuthClient,
-    login_hint: String,
-    provider: LoginProvider,
-    client_redirect_uri: Option<String>,
-) -> Result<String, ServerFnError> {
-    let (pkce_challenge, pkce_verifier) = PkceCodeChallenge::new_random_sha256();
-
-    let oauth_state = OAuthState {
-        csrf_token: CsrfToken::new_random(),
-        client_redirect_uri,
-    };
-
-    let oauth2_request = oauth2
-        .authorize_url(
-            CoreAuthenticationFlow::AuthorizationCode,
-            move || CsrfToken::new(serde_json::to_string(&oauth_state).unwrap()),
-            Nonce::new_random,
-        )
-        .add_scope(Scope::new("openid".into()))
-        .set_pkce_challenge(pkce_challenge)
-        .set_login_hint(LoginHint::new(login_hint));
-
-    let mut oauth2_request = oauth2_request;
-    if provider != LoginProvider::Any {
-        let provider = match provider {
-            LoginProvider::Google => "google",
-            LoginProvider::Apple => "apple",
-            LoginProvider::Any => unreachable!(),
-        };
-        oauth2_request = oauth2_request.add_extra_param("provider", provider);
-    }
-
-    let (auth_url, oauth_csrf_token, _) = oauth2_request.url();
-
-    let key: Key = expect_context();
-    let mut jar: PrivateCookieJar = extract_with_state(&key).await?;
-
-    let cookie_life = Duration::from_secs(60 * 10).try_into().unwrap(); // 10 minutes
-    let pkce_cookie = Cookie::build((PKCE_VERIFIER_COOKIE, pkce_verifier.secret().clone()))
-        .same_site(SameSite::None)
-        .path("/")
-        .max_age(cookie_life)
-        .build();
-    jar = jar.add(pkce_cookie);
-
-    let csrf_cookie = Cookie::build((CSRF_TOKEN_COOKIE, oauth_csrf_token.secret().clone()))
-        .same_site(SameSite::None)
-        .path("/")
-        .max_age(cookie_life)
-        .build();
-    jar = jar.add(csrf_cookie);
-
-    let resp: ResponseOptions = expect_context();
-    set_cookies(&resp, jar);
-
-    Ok(auth_url.to_string())
-}
-
+/// For ID tokens that are not issued in response to an authorization request
+/// (refresh token and client credential grants), which carry no nonce
 pub fn no_op_nonce_verifier(_: Option<&Nonce>) -> Result<(), String> {
     Ok(())
 }
 
-pub async fn perform_yral_auth_impl(
-    provided_csrf: String,
-    auth_code: String,
-    oauth2: YralOAuthClient,
-) -> Result<(DelegatedIdentityWire, Option<String>, Option<String>), ServerFnError> {
-    let key: Key = expect_context();
-    let mut jar: PrivateCookieJar = extract_with_state(&key).await?;
-
-    let csrf_cookie = jar
-        .get(CSRF_TOKEN_COOKIE)
-        .ok_or_else(|| ServerFnError::new("CSRF token cookie not found"))?;
-    if provided_csrf != csrf_cookie.value() {
-        return Err(ServerFnError::new("CSRF token mismatch"));
-    }
-
-    let pkce_cookie = jar
-        .get(PKCE_VERIFIER_COOKIE)
-        .ok_or_else(|| ServerFnError::new("PKCE verifier cookie not found"))?;
-    let pkce_verifier = PkceCodeVerifier::new(pkce_cookie.value().to_owned());
+/// Claims of a refresh token issued by Yral Auth to this client, checked against its trusted key
+pub fn verify_refresh_token(
+    oauth2: &YralOAuthClient,
+    refresh_token: &str,
+) -> Result<YralAuthRefreshTokenClaims, jsonwebtoken::errors::Error> {
+    let mut validation = Validation::new(Algorithm::ES256);
+    validation.set_audience(&[oauth2.client_id().as_str()]);
+    validation.set_issuer(&[YRAL_AUTH_ISSUER_URL]);
+    jsonwebtoken::decode(refresh_token, &YRAL_AUTH_TRUSTED_KEY, &validation)
+        .map(|token| token.claims)
+}
 
-    jar = jar.remove(PKCE_VERIFIER_COOKIE);
-    jar = jar.remove(CSRF_TOKEN_COOKIE);
-    let resp: ResponseOptions = expect_context();
-    set_cookies(&resp, jar);
+/// Exchanges a refresh token for a fresh delegated identity
+pub async fn exchange_refresh_token(
+    oauth2: YralOAuthClient,
+    kv: KVStoreImpl,
+    refresh_token: String,
+) -> Result<DelegatedIdentityWire, ServerFnError> {
+    let (identity, _) = renew_refresh_token(oauth2, kv, refresh_token).await?;
+    Ok(identity)
+}
 
+/// Exchanges a refresh token for a fresh delegated identity and a renewed refresh token
+pub async fn renew_refresh_token(
+    oauth2: YralOAuthClient,
+    kv: KVStoreImpl,
+    refresh_token: String,
+) -> Result<(DelegatedIdentityWire, String), ServerFnError> {
     let token_res = oauth2
-        .exchange_code(AuthorizationCode::new(auth_code))
-        .set_pkce_verifier(pkce_verifier)
+        .exchange_refresh_token(&RefreshToken::new(refresh_token.clone()))
         .request_async(async_http_client)
         .await?;
+    let renewed = token_res
+        .refresh_token()
+        .map_or(refresh_token, |token| token.secret().clone());
 
-    let id_token_verifier = token_verifier();
     let id_token = token_res
         .extra_fields()
         .id_token()
-        .ok_or_else(|| ServerFnError::new("Google did not return an ID token"))?;
-    // we don't use a nonce
-    let claims = id_token.claims(&id_token_verifier, no_op_nonce_verifier)?;
-    let identity: DelegatedIdentityWire = claims.additional_claims().ext_delegated_identity.clone();
-
-    static USERNAME_REGEX: LazyLock<Regex> =
-        LazyLock::new(|| Regex::new(r"^([a-zA-Z0-9]){3,15}$").unwrap());
-    let email = claims.email().map(|f| f.deref().clone());
-
-    let username = claims.email().and_then(|e| {
-        let mail: String = e.deref().clone();
-        let mut username = mail.split_once("@")?.0;
-        username = username
-            .char_indices()
-            .nth(USERNAME_MAX_LEN)
-            .map(|(i, _)| &username[..i])
-            .unwrap_or(username);
-
-        USERNAME_REGEX
-            .is_match(username)
-            .then(|| username.to_string())
-    });
-
-    let jar: SignedCookieJar = extract_with_state(&key).await?;
-
-    let refresh_token = token_res
-        .refresh_token()
-        .expect("Yral Auth V2 must return a refresh token");
+        .expect("Yral Auth V2 must return an ID token");
+    let id_claims = id_token.claims(&token_verifier(&oauth2), no_op_nonce_verifier)?;
+    let jti = id_claims.additional_claims().jti.as_deref();
+    consume_id_token(&kv, &id_token.to_string(), jti, id_claims).await?;
+
+    Ok((
+        id_claims.additional_claims().ext_delegated_identity.clone(),
+        renewed,
+    ))
+}
 
-    update_user_identity(&resp, jar, refresh_token.secret().clone())?;
+/// Yral Auth, which issues delegated identities itself
+#[derive(Clone)]
+pub struct YralProvider(pub YralOAuthClient);
+
+impl IdentityProvider for YralProvider {
+    async fn auth_url(
+        &self,
+        login_hint: String,
+        provider: LoginProvider,
+        client_redirect_uri: Option<String>,
+    ) -> Result<String, ServerFnError> {
+        let (pkce_challenge, pkce_verifier) = PkceCodeChallenge::new_random_sha256();
+        let nonce = Nonce::new_random();
+
+        let oauth_state = OAuthState {
+            csrf_token: CsrfToken::new_random(),
+            client_redirect_uri,
+        };
 
-    Ok((identity, username, email))
+        let mut oauth2_request = self
+            .0
+            .authorize_url(
+                CoreAuthenticationFlow::AuthorizationCode,
+                move || CsrfToken::new(serde_json::to_string(&oauth_state).unwrap()),
+                {
+                    let nonce = nonce.clone();
+                    move || nonce
+                },
+            )
+            .add_scope(Scope::new("openid".into()))
+            .set_pkce_challenge(pkce_challenge)
+            .set_login_hint(LoginHint::new(login_hint));
+
+        if provider != LoginProvider::Any {
+            let provider = match provider {
+                LoginProvider::Google => "google",
+                LoginProvider::Apple => "apple",
+                LoginProvider::Any => unreachable!(),
+            };
+            oauth2_request = oauth2_request.add_extra_param("provider", provider);
+        }
+
+        let (auth_url, oauth_csrf_token, _) = oauth2_request.url();
+        store_login_cookies(YRAL_PROVIDER_ID, &pkce_verifier, &oauth_csrf_token, &nonce).await?;
+
+        Ok(auth_url.to_string())
+    }
+
+    async fn perform_auth(
+        &self,
+        state: String,
+        code: String,
+    ) -> Result<LoginResult, ServerFnError> {
+        let (pkce_verifier, nonce) = take_login_cookies(YRAL_PROVIDER_ID, &state).await?;
+
+        let token_res = self
+            .0
+            .exchange_code(AuthorizationCode::new(code))
+            .set_pkce_verifier(pkce_verifier)
+            .request_async(async_http_client)
+            .await?;
+
+        let id_token = token_res
+            .extra_fields()
+            .id_token()
+            .ok_or_else(|| ServerFnError::new("Google did not return an ID token"))?;
+        let claims = id_token.claims(&token_verifier(&self.0), &nonce)?;
+
+        let kv: KVStoreImpl = expect_context();
+        let jti = claims.additional_claims().jti.as_deref();
+        consume_id_token(&kv, &id_token.to_string(), jti, claims).await?;
+        let identity: DelegatedIdentityWire =
+            claims.additional_claims().ext_delegated_identity.clone();
+
+        let email = claims.email().map(|f| f.deref().clone());
+        let username = fallback_username(claims.email());
+
+        let jar = extract_signed_jar().await?;
+        let resp: ResponseOptions = expect_context();
+
+        let refresh_token = token_res
+            .refresh_token()
+            .expect("Yral Auth V2 must return a refresh token");
+
+        update_user_identity(&resp, jar, refresh_token.secret().clone())?;
+
+        Ok((identity, username, email))
+    }
 }
@@ -0,0 +1,278 @@
+//! Handing mobile users over to the native app
+//!
+//! links to the page a user was on survive installing the app as a deferred
+//! link. It's saved under a one-time token handed to the install (the Play
+//! install referrer on Android, the clipboard on iOS), which the app claims
+//! the link with on its first launch
+use candid::Principal;
+use codee::string::FromToStringCodec;
+use consts::APP_BANNER_DISMISSED_STORE;
+use std::time::Duration;
+
+use leptos::{prelude::*, server_fn::codec::Json};
+use leptos_icons::*;
+use leptos_router::hooks::use_location;
+use leptos_use::storage::use_local_storage;
+use serde::{Deserialize, Serialize};
+use state::{app_state::AppState, canisters::auth_state};
+use utils::{
+    host::get_host,
+    t,
+    web::{copy_to_clipboard, mobile_platform, MobilePlatform},
+};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LinkedPost {
+    pub canister_id: Principal,
+    pub post_id: String,
+}
+
+/// Post linked by `path`, any of the pages showing a single post
+pub fn linked_post(path: &str) -> Option<LinkedPost> {
+    let segments: Vec<_> = path.trim_matches('/').split('/').collect();
+    let (canister_id, post_id) = match segments.as_slice() {
+        ["hot-or-not", canister_id, post_id]
+        | ["post", canister_id, post_id]
+        | ["profile", canister_id, "post", post_id] => (canister_id, post_id),
+        _ => return None,
+    };
+    Some(LinkedPost {
+        canister_id: Principal::from_text(canister_id).ok()?,
+        post_id: post_id.to_string(),
+    })
+}
+
+/// Longest path a deferred link can open
+const MAX_DEFERRED_PATH_LEN: usize = 512;
+/// Prefix of the token copied to the clipboard on iOS, so the app can tell it apart
+pub const CLIPBOARD_TOKEN_PREFIX: &str = "yral-deferred-link:";
+/// Time the iOS app has to take over before the App Store is opened instead
+const IOS_STORE_FALLBACK_DELAY: Duration = Duration::from_millis(1500);
+
+/// Where the app should open once installed
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DeferredLink {
+    pub path: String,
+    pub user_refer: Option<Principal>,
+    pub post: Option<LinkedPost>,
+}
+
+impl DeferredLink {
+    pub fn new(path: String, user_refer: Option<Principal>) -> Self {
+        Self {
+            post: linked_post(&path),
+            path,
+            user_refer,
+        }
+    }
+
+    /// Whether `path` is a page of `app_state` the app can open
+    pub fn is_valid_path(app_state: &AppState, path: &str) -> bool {
+        path.len() <= MAX_DEFERRED_PATH_LEN
+            && path.starts_with('/')
+            && !path.starts_with("//")
+            && !path.contains(['\\', '?', '#'])
+            && !path.chars().any(char::is_control)
+            && app_state.route_enabled(path)
+            && !app_state
+                .robots_disallow
+                .iter()
+                .any(|prefix| path.starts_with(prefix))
+    }
+}
+
+/// Query string handed to the Play Store as the install referrer
+pub fn install_referrer(token: &str) -> String {
+    format!("deferred_link={}", urlencoding::encode(token))
+}
+
+#[cfg(feature = "ssr")]
+pub mod server {
+    use std::time::Duration;
+
+    pub const DEFERRED_LINK_PREFIX: &str = "deferred-link";
+    /// Time a user has to install and open the app
+    pub const DEFERRED_LINK_TTL: Duration = Duration::from_secs(60 * 60);
+
+    pub fn deferred_link_key(token: &uuid::Uuid) -> String {
+        format!("{DEFERRED_LINK_PREFIX}:{token}")
+    }
+}
+
+/// Stores the link to `path` for the app to claim with `token` on its first launch
+///
+/// the path must be a public page of the tenant and `user_refer` a registered
+/// user, an invalid referrer is dropped
+#[server(endpoint = "save_deferred_link", input = Json)]
+pub async fn save_deferred_link(
+    token: String,
+    path: String,
+    user_refer: Option<Principal>,
+) -> Result<(), ServerFnError> {
+    use auth::server_impl::store::{KVStore, KVStoreImpl};
+    use yral_canisters_client::user_info_service::{Result8, SessionType};
+    use yral_canisters_common::Canisters;
+
+    let token = uuid::Uuid::parse_str(&token)?;
+    if !DeferredLink::is_valid_path(&AppState::current(), &path) {
+        return Err(ServerFnError::new("path can't be opened in the app"));
+    }
+    let user_refer = match user_refer {
+        Some(user_refer) => {
+            let cans: Canisters<false> = expect_context();
+            let session = cans
+                .user_info_service()
+                .await
+                .get_user_session_type(user_refer)
+                .await?;
+            matches!(session, Result8::Ok(SessionType::RegisteredSession)).then_some(user_refer)
+        }
+        None => None,
+    };
+
+    let kv: KVStoreImpl = expect_context();
+    let saved = kv
+        .write_if_absent(
+            server::deferred_link_key(&token),
+            serde_json::to_string(&DeferredLink::new(path, user_refer))?,
+            Some(server::DEFERRED_LINK_TTL),
+        )
+        .await?;
+    if !saved {
+        return Err(ServerFnError::new("deferred link token was already used"));
+    }
+    Ok(())
+}
+
+/// Banner opening the current page in the tenant's app on mobiles
+#[component]
+pub fn SmartAppBanner() -> impl IntoView {
+    let app_state: AppState = expect_context();
+    let native_apps = app_state.native_apps.clone();
+    let (dismissed, set_dismissed, _) =
+        use_local_storage::<bool, FromToStringCodec>(APP_BANNER_DISMISSED_STORE);
+    // the platform is only known on the client
+    let platform = RwSignal::new(None::<MobilePlatform>);
+    Effect::new(move |_| platform.set(mobile_platform()));
+    let has_app = move || match platform.get() {
+        Some(MobilePlatform::Android) => native_apps.android.is_some(),
+        Some(MobilePlatform::Ios) => native_apps.ios.is_some(),
+        None => false,
+    };
+
+    let auth = auth_state();
+    let location = use_location();
+    let native_apps = app_state.native_apps.clone();
+    let open_app = Action::new(move |&platform: &MobilePlatform| {
+        let path = location.pathname.get_untracked();
+        let user_refer = auth.referrer_store.get_untracked();
+        let token = uuid::Uuid::new_v4().to_string();
+        if platform == MobilePlatform::Ios {
+            // read by the app on its first launch, the App Store passes nothing on
+            copy_to_clipboard(&format!("{CLIPBOARD_TOKEN_PREFIX}{token}"));
+        }
+        let native_apps = native_apps.clone();
+        async move {
+            if let Err(e) = save_deferred_link(token.clone(), path.clone(), user_refer).await {
+                log::warn!("failed to save deferred link: {e}");
+            }
+            match platform {
+                MobilePlatform::Android => {
+                    if let Some(app) = native_apps.android {
+                        let url = app.intent_url(&get_host(), &path, &install_referrer(&token));
+                        _ = window().location().set_href(&url);
+                    }
+                }
+                MobilePlatform::Ios => {
+                    if let Some(app) = native_apps.ios {
+                        _ = window().location().set_href(&app.app_url(&path));
+                        // the page stays visible if the app isn't installed
+                        set_timeout(
+                            move || {
+                                if !document().hidden() {
+                                    _ = window().location().set_href(&app.store_url());
+                                }
+                            },
+                            IOS_STORE_FALLBACK_DELAY,
+                        );
+                    }
+                }
+            }
+        }
+    });
+
+    let name = app_state.name;
+    let icon = format!("/{}/favicon-apple.png", app_state.asset_path());
+
+    view! {
+        <Show when=move || !dismissed.get() && has_app()>
+            <div class="flex fixed inset-x-0 top-0 z-50 gap-3 items-center py-2 px-4 text-white bg-neutral-900">
+                <button
+                    on:click=move |_| set_dismissed(true)
+                    class="p-1 text-sm rounded-full bg-neutral-600"
+                >
+                    <Icon icon=icondata::ChCross />
+                </button>
+                <img src=icon.clone() class="w-10 h-10 rounded-lg" alt=name />
+                <div class="flex flex-col flex-1">
+                    <span class="text-sm font-bold">{name}</span>
+                    <span class="text-xs text-neutral-400">{t!("app-banner-tagline")}</span>
+                    // the clipboard is how the install finds its way back to this page
+                    <Show when=move || platform.get() == Some(MobilePlatform::Ios)>
+                        <span class="text-xs text-neutral-400">
+                            {t!("app-banner-clipboard-notice")}
+                        </span>
+                    </Show>
+                </div>
+                <button
+                    on:click=move |_| {
+                        if let Some(platform) = platform.get_untracked() {
+                            open_app.dispatch(platform);
+                        }
+                    }
+                    disabled=move || open_app.pending().get()
+                    class="py-1.5 px-4 text-sm font-bold rounded-full bg-primary-600"
+                >
+                    {t!("open")}
+                </button>
+            </div>
+        </Show>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn post_links_are_recognised() {
+        let canister_id = Principal::anonymous();
+        let post = LinkedPost {
+            canister_id,
+            post_id: "12".to_string(),
+        };
+        for path in [
+            format!("/hot-or-not/{canister_id}/12"),
+            format!("/post/{canister_id}/12"),
+            format!("/profile/{canister_id}/post/12"),
+        ] {
+            assert_eq!(linked_post(&path), Some(post.clone()));
+        }
+        assert_eq!(linked_post(&format!("/profile/{canister_id}/posts")), None);
+    }
+
+    #[test]
+    fn only_public_pages_are_deferred() {
+        let app_state = AppState::from_type(&state::app_type::AppType::HotOrNot);
+        assert!(DeferredLink::is_valid_path(&app_state, "/post/aaaaa-aa/12"));
+        for path in [
+            "post/aaaaa-aa/12",
+            "//evil.com/post",
+            "/post?next=https://evil.com",
+            "/logout",
+            "/pnd/withdraw",
+        ] {
+            assert!(!DeferredLink::is_valid_path(&app_state, path), "{path}");
+        }
+    }
+}
@@ -0,0 +1,158 @@
+//! Merging what an anonymous user accrued into the account they log in to
+#[cfg(feature = "ssr")]
+mod server_impl;
+
+use candid::Principal;
+use leptos::{prelude::*, server_fn::codec::Json};
+use serde::{Deserialize, Serialize};
+use utils::{i18n::use_i18n, t, time::sleep};
+use web_time::Duration;
+use yral_types::delegated_identity::DelegatedIdentityWire;
+
+use crate::modal::Modal;
+
+/// An interrupted merge resumes where it stopped, see [`merge_with_retries`]
+const MERGE_ATTEMPTS: usize = 3;
+const MERGE_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// What was moved from an anonymous account into a logged in one
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct AccountMergeReport {
+    pub from: Principal,
+    pub into: Principal,
+    pub sats_moved: u64,
+    pub follows_moved: usize,
+    /// Posts published again on the logged in account, the originals can't
+    /// be deleted and stay on the anonymous one
+    pub posts_moved: usize,
+    /// Posts that failed to move, they stay on the anonymous account
+    pub posts_not_moved: usize,
+}
+
+impl AccountMergeReport {
+    pub fn new(from: Principal, into: Principal) -> Self {
+        Self {
+            from,
+            into,
+            sats_moved: 0,
+            follows_moved: 0,
+            posts_moved: 0,
+            posts_not_moved: 0,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sats_moved == 0
+            && self.follows_moved == 0
+            && self.posts_moved == 0
+            && self.posts_not_moved == 0
+    }
+}
+
+/// Moves the SATS balance, follows and posts of `anonymous` into `registered`
+///
+/// both identities are verified, `registered` must also be the caller's login.
+/// Merges are logged, so retrying (or repeating) a merge never moves anything twice,
+/// see [`merge_with_retries`]
+#[server(endpoint = "merge_anonymous_account", input = Json, output = Json)]
+pub async fn merge_anonymous_account(
+    anonymous: DelegatedIdentityWire,
+    registered: DelegatedIdentityWire,
+) -> Result<AccountMergeReport, ServerFnError> {
+    server_impl::merge_anonymous_account(anonymous, registered).await
+}
+
+/// [`merge_anonymous_account`], retried while the guest identity is still at hand,
+/// it's gone once the login completes
+pub async fn merge_with_retries(
+    anonymous: DelegatedIdentityWire,
+    registered: DelegatedIdentityWire,
+) -> Result<AccountMergeReport, ServerFnError> {
+    let mut attempt = 1;
+    loop {
+        match merge_anonymous_account(anonymous.clone(), registered.clone()).await {
+            Err(e) if attempt < MERGE_ATTEMPTS => {
+                leptos::logging::warn!("guest account merge attempt {attempt} failed: {e}");
+                sleep(MERGE_RETRY_DELAY).await;
+                attempt += 1;
+            }
+            res => return res,
+        }
+    }
+}
+
+/// Report of the last merge, shown until dismissed
+#[derive(Clone, Copy, Default)]
+pub struct AccountMergeNotice(pub RwSignal<Option<AccountMergeReport>>);
+
+#[component]
+pub fn AccountMergeSummary() -> impl IntoView {
+    let AccountMergeNotice(notice) = expect_context();
+    let i18n = use_i18n();
+    let show = RwSignal::new(false);
+    Effect::new(move |_| {
+        if notice.with(Option::is_some) {
+            show.set(true);
+        }
+    });
+    // closing the modal dismisses the report
+    Effect::new(move |_| {
+        if !show.get() && notice.with_untracked(Option::is_some) {
+            notice.set(None);
+        }
+    });
+
+    view! {
+        <Modal show>
+            {move || {
+                notice
+                    .get()
+                    .map(|report| {
+                        let lines = [
+                            (report.sats_moved > 0)
+                                .then(|| {
+                                    i18n.tr("sats-moved", &[("count", report.sats_moved.to_string())])
+                                }),
+                            (report.follows_moved > 0)
+                                .then(|| {
+                                    i18n.tr(
+                                        "follows-moved",
+                                        &[("count", report.follows_moved.to_string())],
+                                    )
+                                }),
+                            (report.posts_moved > 0)
+                                .then(|| {
+                                    i18n.tr(
+                                        "posts-moved",
+                                        &[("count", report.posts_moved.to_string())],
+                                    )
+                                }),
+                            (report.posts_not_moved > 0)
+                                .then(|| {
+                                    i18n.tr(
+                                        "posts-not-moved",
+                                        &[
+                                            ("count", report.posts_not_moved.to_string()),
+                                            ("profile", report.from.to_text()),
+                                        ],
+                                    )
+                                }),
+                        ];
+                        view! {
+                            <div class="flex flex-col gap-3 items-center text-center text-white">
+                                <span class="text-lg font-bold">{t!("merge-welcome")}</span>
+                                <span class="text-sm text-neutral-300">
+                                    {t!("merge-summary")}
+                                </span>
+                                {lines
+                                    .into_iter()
+                                    .flatten()
+                                    .map(|line| view! { <span class="text-sm">{line}</span> })
+                                    .collect_view()}
+                            </div>
+                        }
+                    })
+            }}
+        </Modal>
+    }
+}
@@ -0,0 +1,722 @@
+use std::time::Duration;
+
+use auth::{
+    delegate_identity_with_policy,
+    server_impl::store::{KVStore, KVStoreImpl},
+    DelegationPolicy,
+};
+use candid::Principal;
+use consts::{OFF_CHAIN_AGENT_URL, UPLOAD_URL};
+use hon_worker_common::{SatsBalanceUpdateRequestV2, WORKER_URL};
+use ic_agent::{
+    identity::{DelegatedIdentity, Secp256k1Identity},
+    Identity,
+};
+use leptos::prelude::*;
+use num_bigint::{BigInt, BigUint};
+use serde::{Deserialize, Serialize};
+use state::server::HonWorkerJwt;
+use yral_canisters_client::{
+    local::USER_INFO_SERVICE_ID,
+    user_info_service::{Result2, Result8, SessionType},
+    user_post_service::Result1,
+};
+use yral_canisters_common::{
+    utils::time::current_epoch, utils::token::load_sats_balance, Canisters,
+};
+use yral_types::delegated_identity::DelegatedIdentityWire;
+
+use super::AccountMergeReport;
+use crate::auth_providers::server_impl::ensure_user_logged_in_with_oauth;
+
+const MERGE_LOG_PREFIX: &str = "account-merge";
+const MERGE_LOCK_PREFIX: &str = "account-merge-lock";
+/// A crashed merge can be retried after this long, the lock is renewed
+/// before every step of a running merge, and each step is a single request
+const MERGE_LOCK_TTL: Duration = Duration::from_secs(60);
+const PAGE_SIZE: u64 = 100;
+
+/// Debit of the guest's SATS, logged before it's sent
+///
+/// the worker only applies an update to the balance it was computed from,
+/// so a retry can tell whether the debit went through and replays it otherwise
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+struct PendingDebit {
+    previous_balance: u64,
+    amount: u64,
+}
+
+/// Credit of the moved SATS to the account, logged before it's sent, see [`PendingDebit`]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+struct PendingCredit {
+    previous_balance: u64,
+    amount: u64,
+}
+
+/// Progress of a merge, each step is recorded as soon as it's done
+#[derive(Serialize, Deserialize)]
+struct MergeLog {
+    report: AccountMergeReport,
+    pending_debit: Option<PendingDebit>,
+    #[serde(default)]
+    pending_credit: Option<PendingCredit>,
+    sats_debited: bool,
+    sats_credited: bool,
+    follows_moved: bool,
+    /// Guest posts already published on the account
+    posts_moved: Vec<String>,
+    completed: bool,
+}
+
+impl MergeLog {
+    fn new(from: Principal, into: Principal) -> Self {
+        Self {
+            report: AccountMergeReport::new(from, into),
+            pending_debit: None,
+            pending_credit: None,
+            sats_debited: false,
+            sats_credited: false,
+            follows_moved: false,
+            posts_moved: vec![],
+            completed: false,
+        }
+    }
+}
+
+fn merge_log_key(from: Principal) -> String {
+    format!("{MERGE_LOG_PREFIX}:{}", from.to_text())
+}
+
+/// A post of the guest account
+#[derive(Clone, Debug, PartialEq)]
+struct GuestPost {
+    id: String,
+    video_uid: String,
+    description: String,
+    hashtags: Vec<String>,
+    is_nsfw: bool,
+}
+
+/// What a merge reads and changes outside the KV store
+trait MergeOps {
+    async fn is_registered(&self, principal: Principal) -> Result<bool, ServerFnError>;
+
+    async fn sats_balance(&self, principal: Principal) -> Result<u64, ServerFnError>;
+
+    /// Moves `delta` SATS, only if the balance is still `previous_balance`
+    async fn update_sats_balance(
+        &self,
+        principal: Principal,
+        previous_balance: u64,
+        delta: i64,
+    ) -> Result<(), ServerFnError>;
+
+    async fn following(&self, principal: Principal) -> Result<Vec<Principal>, ServerFnError>;
+
+    /// Follows `target` as the account merged into
+    async fn follow(&self, target: Principal) -> Result<(), ServerFnError>;
+
+    async fn posts(&self, principal: Principal) -> Result<Vec<GuestPost>, ServerFnError>;
+
+    /// Publishes the video of `post` on the account merged into
+    async fn publish(&self, post: &GuestPost) -> Result<(), ServerFnError>;
+}
+
+/// Delegated identity, once its delegation chain is verified
+///
+/// only unrestricted session delegations are accepted, so a delegation handed
+/// to another service can't be used to take over the account
+fn verified_identity(wire: DelegatedIdentityWire) -> Result<DelegatedIdentity, ServerFnError> {
+    let now_ns = current_epoch().as_nanos() as u64;
+    if wire
+        .delegation_chain
+        .iter()
+        .any(|signed| signed.delegation.expiration < now_ns || signed.delegation.targets.is_some())
+    {
+        return Err(ServerFnError::new("identity is expired or restricted"));
+    }
+
+    let to_secret = k256::SecretKey::from_jwk(&wire.to_secret)?;
+    let identity = DelegatedIdentity::new(
+        wire.from_key,
+        Box::new(Secp256k1Identity::from_private_key(to_secret)),
+        wire.delegation_chain,
+    )?;
+    Ok(identity)
+}
+
+fn verified_principal(wire: DelegatedIdentityWire) -> Result<Principal, ServerFnError> {
+    verified_identity(wire)?
+        .sender()
+        .map_err(ServerFnError::new)
+}
+
+/// Merge effects on the canisters and services of the app
+struct LiveOps<'a> {
+    cans: &'a Canisters<false>,
+    jwt: HonWorkerJwt,
+    registered: DelegatedIdentityWire,
+    /// Delegation of the account merged into, restricted to publishing posts
+    upload_identity: DelegatedIdentityWire,
+}
+
+impl<'a> LiveOps<'a> {
+    async fn new(
+        cans: &'a Canisters<false>,
+        registered: DelegatedIdentityWire,
+    ) -> Result<Self, ServerFnError> {
+        let identity = verified_identity(registered.clone())?;
+        let policy =
+            DelegationPolicy::upload(USER_INFO_SERVICE_ID, cans.user_post_service().await.0);
+        Ok(Self {
+            cans,
+            jwt: expect_context(),
+            upload_identity: delegate_identity_with_policy(&identity, &policy),
+            registered,
+        })
+    }
+}
+
+impl MergeOps for LiveOps<'_> {
+    async fn is_registered(&self, principal: Principal) -> Result<bool, ServerFnError> {
+        let session = self
+            .cans
+            .user_info_service()
+            .await
+            .get_user_session_type(principal)
+            .await?;
+        Ok(matches!(
+            session,
+            Result8::Ok(SessionType::RegisteredSession)
+        ))
+    }
+
+    async fn sats_balance(&self, principal: Principal) -> Result<u64, ServerFnError> {
+        let balance = load_sats_balance(principal).await?.balance;
+        u64::try_from(&balance).map_err(|_| ServerFnError::new("SATS balance out of range"))
+    }
+
+    async fn update_sats_balance(
+        &self,
+        principal: Principal,
+        previous_balance: u64,
+        delta: i64,
+    ) -> Result<(), ServerFnError> {
+        let req_url = format!("{WORKER_URL}v2/update_balance/{principal}");
+        let res = reqwest::Client::new()
+            .post(req_url)
+            .json(&SatsBalanceUpdateRequestV2 {
+                previous_balance: BigUint::from(previous_balance),
+                delta: BigInt::from(delta),
+                is_airdropped: false,
+            })
+            .bearer_auth(&self.jwt.0)
+            .send()
+            .await?;
+
+        if !res.status().is_success() {
+            return Err(ServerFnError::new(format!(
+                "worker error: {}",
+                res.text().await?
+            )));
+        }
+        Ok(())
+    }
+
+    async fn following(&self, principal: Principal) -> Result<Vec<Principal>, ServerFnError> {
+        let service = self.cans.user_info_service().await;
+        let mut following = vec![];
+        let mut cursor = None;
+        loop {
+            let page = match service
+                .get_following(principal, cursor, PAGE_SIZE, None)
+                .await?
+            {
+                Result2::Ok(page) => page,
+                Result2::Err(e) => return Err(ServerFnError::new(e)),
+            };
+            following.extend(page.following.into_iter().map(|item| item.principal_id));
+            cursor = page.next_cursor;
+            if cursor.is_none() {
+                return Ok(following);
+            }
+        }
+    }
+
+    async fn follow(&self, target: Principal) -> Result<(), ServerFnError> {
+        let url = OFF_CHAIN_AGENT_URL.join("api/v1/user/follow")?;
+        let res = reqwest::Client::new()
+            .post(url)
+            .json(&serde_json::json!({
+                "delegated_identity_wire": self.registered,
+                "target_principal": target,
+                "follower_username": null,
+            }))
+            .send()
+            .await?;
+
+        if !res.status().is_success() {
+            return Err(ServerFnError::new(format!(
+                "follow failed: {}",
+                res.text().await?
+            )));
+        }
+        Ok(())
+    }
+
+    async fn posts(&self, principal: Principal) -> Result<Vec<GuestPost>, ServerFnError> {
+        let service = self.cans.user_post_service().await;
+        let mut ids = vec![];
+        loop {
+            let page = match service
+                .get_posts_of_this_user_profile_with_pagination(
+                    principal,
+                    ids.len() as u64,
+                    PAGE_SIZE,
+                )
+                .await?
+            {
+                Result1::Ok(posts) => posts,
+                Result1::Err(e) => {
+                    return Err(ServerFnError::new(format!("failed to get posts: {e:?}")))
+                }
+            };
+            let page_len = page.len();
+            ids.extend(page.into_iter().map(|post| post.id));
+            if page_len < PAGE_SIZE as usize {
+                break;
+            }
+        }
+
+        let mut posts = Vec::with_capacity(ids.len());
+        for id in ids {
+            let details = self
+                .cans
+                .get_post_details(USER_INFO_SERVICE_ID, id.clone())
+                .await?
+                .ok_or_else(|| ServerFnError::new(format!("post {id} not found")))?;
+            posts.push(GuestPost {
+                id,
+                video_uid: details.uid,
+                description: details.description,
+                hashtags: details.hastags,
+                is_nsfw: details.is_nsfw,
+            });
+        }
+        Ok(posts)
+    }
+
+    async fn publish(&self, post: &GuestPost) -> Result<(), ServerFnError> {
+        let res = reqwest::Client::new()
+            .post(format!("{UPLOAD_URL}/update_metadata"))
+            .json(&serde_json::json!({
+                "video_uid": post.video_uid,
+                "delegated_identity_wire": self.upload_identity,
+                "meta": {
+                    "title": post.description,
+                    "description": post.description,
+                    "tags": post.hashtags.join(","),
+                },
+                "post_details": {
+                    "is_nsfw": post.is_nsfw,
+                    "hashtags": post.hashtags,
+                    "description": post.description,
+                    "video_uid": post.video_uid,
+                    "creator_consent_for_inclusion_in_hot_or_not": false,
+                },
+            }))
+            .send()
+            .await?;
+
+        if !res.status().is_success() {
+            return Err(ServerFnError::new(format!(
+                "publish failed: {}",
+                res.text().await?
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Exclusive right to merge a guest account, held by a random token
+struct MergeLock {
+    key: String,
+    token: String,
+}
+
+impl MergeLock {
+    async fn acquire(kv: &impl KVStore, from: Principal) -> Result<Option<Self>, ServerFnError> {
+        let lock = Self {
+            key: format!("{MERGE_LOCK_PREFIX}:{}", from.to_text()),
+            token: uuid::Uuid::new_v4().to_string(),
+        };
+        let acquired = kv
+            .write_if_absent(lock.key.clone(), lock.token.clone(), Some(MERGE_LOCK_TTL))
+            .await?;
+        Ok(acquired.then_some(lock))
+    }
+
+    /// Extends the lock for another step, fails if it expired and was taken over
+    async fn renew(&self, kv: &impl KVStore) -> Result<(), ServerFnError> {
+        if kv.read(self.key.clone()).await?.as_ref() != Some(&self.token) {
+            return Err(ServerFnError::new("merge took too long, please try again"));
+        }
+        kv.write_with_ttl(self.key.clone(), self.token.clone(), MERGE_LOCK_TTL)
+            .await?;
+        Ok(())
+    }
+
+    async fn release(self, kv: &impl KVStore) -> Result<(), ServerFnError> {
+        if kv.read(self.key.clone()).await? == Some(self.token) {
+            kv.delete(self.key).await?;
+        }
+        Ok(())
+    }
+}
+
+async fn save_log(kv: &impl KVStore, log: &MergeLog) -> Result<(), ServerFnError> {
+    kv.write(merge_log_key(log.report.from), serde_json::to_string(log)?)
+        .await?;
+    Ok(())
+}
+
+async fn debit_guest(
+    kv: &impl KVStore,
+    ops: &impl MergeOps,
+    log: &mut MergeLog,
+) -> Result<(), ServerFnError> {
+    let from = log.report.from;
+    let balance = ops.sats_balance(from).await?;
+    let debit = match log.pending_debit {
+        // the debit went through before the merge was interrupted
+        Some(debit) if balance == debit.previous_balance - debit.amount => None,
+        Some(debit) => Some(debit),
+        None if balance == 0 => None,
+        None => {
+            let debit = PendingDebit {
+                previous_balance: balance,
+                amount: balance,
+            };
+            log.pending_debit = Some(debit);
+            save_log(kv, log).await?;
+            Some(debit)
+        }
+    };
+    if let Some(debit) = debit {
+        let delta =
+            -i64::try_from(debit.amount).map_err(|_| ServerFnError::new("SATS out of range"))?;
+        ops.update_sats_balance(from, debit.previous_balance, delta)
+            .await?;
+    }
+
+    log.report.sats_moved = log.pending_debit.map_or(0, |debit| debit.amount);
+    log.sats_debited = true;
+    save_log(kv, log).await
+}
+
+async fn credit_account(
+    kv: &impl KVStore,
+    ops: &impl MergeOps,
+    log: &mut MergeLog,
+) -> Result<(), ServerFnError> {
+    let into = log.report.into;
+    let amount = log.report.sats_moved;
+    let balance = ops.sats_balance(into).await?;
+    let credit = match log.pending_credit {
+        // the credit went through before the merge was interrupted
+        Some(credit) if balance == credit.previous_balance + credit.amount => None,
+        Some(credit) => Some(credit),
+        None if amount == 0 => None,
+        None => {
+            let credit = PendingCredit {
+                previous_balance: balance,
+                amount,
+            };
+            log.pending_credit = Some(credit);
+            save_log(kv, log).await?;
+            Some(credit)
+        }
+    };
+    if let Some(credit) = credit {
+        let delta =
+            i64::try_from(credit.amount).map_err(|_| ServerFnError::new("SATS out of range"))?;
+        ops.update_sats_balance(into, credit.previous_balance, delta)
+            .await?;
+    }
+
+    log.sats_credited = true;
+    save_log(kv, log).await
+}
+
+async fn run_merge(
+    kv: &impl KVStore,
+    ops: &impl MergeOps,
+    lock: &MergeLock,
+    log: &mut MergeLog,
+) -> Result<(), ServerFnError> {
+    let AccountMergeReport { from, into, .. } = log.report;
+
+    if !log.sats_debited {
+        lock.renew(kv).await?;
+        debit_guest(kv, ops, log).await?;
+    }
+
+    if !log.sats_credited {
+        lock.renew(kv).await?;
+        credit_account(kv, ops, log).await?;
+    }
+
+    if !log.follows_moved {
+        lock.renew(kv).await?;
+        let already_following = ops.following(into).await?;
+        for target in ops.following(from).await? {
+            if target == into || already_following.contains(&target) {
+                continue;
+            }
+            lock.renew(kv).await?;
+            // a follow that fails (e.g deleted account) shouldn't block the merge
+            match ops.follow(target).await {
+                Ok(()) => log.report.follows_moved += 1,
+                Err(e) => tracing::warn!("merge {from} -> {into}: failed to follow {target}: {e}"),
+            }
+        }
+        log.follows_moved = true;
+        save_log(kv, log).await?;
+    }
+
+    lock.renew(kv).await?;
+    let published: Vec<_> = ops
+        .posts(into)
+        .await?
+        .into_iter()
+        .map(|post| post.video_uid)
+        .collect();
+    log.report.posts_not_moved = 0;
+    for post in ops.posts(from).await? {
+        if log.posts_moved.contains(&post.id) {
+            continue;
+        }
+        // published before the merge was interrupted
+        if published.contains(&post.video_uid) {
+            log.posts_moved.push(post.id);
+            continue;
+        }
+        lock.renew(kv).await?;
+        match ops.publish(&post).await {
+            Ok(()) => {
+                log.posts_moved.push(post.id);
+                save_log(kv, log).await?;
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "merge {from} -> {into}: failed to move post {}: {e}",
+                    post.id
+                );
+                log.report.posts_not_moved += 1;
+            }
+        }
+    }
+    log.report.posts_moved = log.posts_moved.len();
+    log.completed = log.report.posts_not_moved == 0;
+    save_log(kv, log).await
+}
+
+pub async fn merge_anonymous_account(
+    anonymous: DelegatedIdentityWire,
+    registered: DelegatedIdentityWire,
+) -> Result<AccountMergeReport, ServerFnError> {
+    let from = verified_principal(anonymous)?;
+    let into = verified_principal(registered.clone())?;
+    ensure_user_logged_in_with_oauth(into).await?;
+    if from == into {
+        // the anonymous identity became the logged in one, nothing to move
+        return Ok(AccountMergeReport::new(from, into));
+    }
+
+    let kv: KVStoreImpl = expect_context();
+    let Some(lock) = MergeLock::acquire(&kv, from).await? else {
+        return Err(ServerFnError::new("merge already in progress"));
+    };
+    let cans: Canisters<false> = expect_context();
+    let res = match LiveOps::new(&cans, registered).await {
+        Ok(ops) => merge_locked(&kv, &ops, &lock, from, into).await,
+        Err(e) => Err(e),
+    };
+    lock.release(&kv).await?;
+    res
+}
+
+async fn merge_locked(
+    kv: &impl KVStore,
+    ops: &impl MergeOps,
+    lock: &MergeLock,
+    from: Principal,
+    into: Principal,
+) -> Result<AccountMergeReport, ServerFnError> {
+    let mut log = match kv.read(merge_log_key(from)).await? {
+        Some(log) => serde_json::from_str::<MergeLog>(&log)?,
+        None => MergeLog::new(from, into),
+    };
+    if log.report.into != into {
+        return Err(ServerFnError::new(
+            "this guest account was already merged into another account",
+        ));
+    }
+    if log.completed {
+        return Ok(log.report);
+    }
+
+    if ops.is_registered(from).await? {
+        return Err(ServerFnError::new("only guest accounts can be merged"));
+    }
+    run_merge(kv, ops, lock, &mut log).await?;
+
+    Ok(log.report)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, sync::Mutex};
+
+    use auth::server_impl::store::in_memory_kv::InMemoryKV;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct FakeState {
+        balances: HashMap<Principal, u64>,
+        following: HashMap<Principal, Vec<Principal>>,
+        posts: HashMap<Principal, Vec<GuestPost>>,
+        /// The next balance update of this principal is applied but reported as failed
+        lose_next_update: Option<Principal>,
+    }
+
+    struct FakeOps {
+        into: Principal,
+        state: Mutex<FakeState>,
+    }
+
+    impl MergeOps for FakeOps {
+        async fn is_registered(&self, principal: Principal) -> Result<bool, ServerFnError> {
+            Ok(principal == self.into)
+        }
+
+        async fn sats_balance(&self, principal: Principal) -> Result<u64, ServerFnError> {
+            let state = self.state.lock().unwrap();
+            Ok(state.balances.get(&principal).copied().unwrap_or_default())
+        }
+
+        async fn update_sats_balance(
+            &self,
+            principal: Principal,
+            previous_balance: u64,
+            delta: i64,
+        ) -> Result<(), ServerFnError> {
+            let mut state = self.state.lock().unwrap();
+            let balance = state.balances.entry(principal).or_default();
+            if *balance != previous_balance {
+                return Err(ServerFnError::new("balance changed"));
+            }
+            *balance = balance.checked_add_signed(delta).unwrap();
+            if state.lose_next_update == Some(principal) {
+                state.lose_next_update = None;
+                return Err(ServerFnError::new("connection reset"));
+            }
+            Ok(())
+        }
+
+        async fn following(&self, principal: Principal) -> Result<Vec<Principal>, ServerFnError> {
+            let state = self.state.lock().unwrap();
+            Ok(state.following.get(&principal).cloned().unwrap_or_default())
+        }
+
+        async fn follow(&self, target: Principal) -> Result<(), ServerFnError> {
+            let mut state = self.state.lock().unwrap();
+            state.following.entry(self.into).or_default().push(target);
+            Ok(())
+        }
+
+        async fn posts(&self, principal: Principal) -> Result<Vec<GuestPost>, ServerFnError> {
+            let state = self.state.lock().unwrap();
+            Ok(state.posts.get(&principal).cloned().unwrap_or_default())
+        }
+
+        async fn publish(&self, post: &GuestPost) -> Result<(), ServerFnError> {
+            let mut state = self.state.lock().unwrap();
+            let posts = state.posts.entry(self.into).or_default();
+            posts.push(GuestPost {
+                id: format!("{}-moved", post.id),
+                ..post.clone()
+            });
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn interrupted_merge_moves_everything_once() {
+        let kv = InMemoryKV::new();
+        let from = Principal::from_slice(&[1; 29]);
+        let into = Principal::from_slice(&[2; 29]);
+        let followed = Principal::from_slice(&[3; 29]);
+        let post = GuestPost {
+            id: "1".into(),
+            video_uid: "video".into(),
+            description: "a post".into(),
+            hashtags: vec!["yral".into()],
+            is_nsfw: false,
+        };
+        let ops = FakeOps {
+            into,
+            state: Mutex::new(FakeState {
+                balances: HashMap::from([(from, 50), (into, 10)]),
+                following: HashMap::from([(from, vec![followed, into])]),
+                posts: HashMap::from([(from, vec![post])]),
+                lose_next_update: Some(from),
+            }),
+        };
+        let lock = MergeLock::acquire(&kv, from).await.unwrap().unwrap();
+
+        // the guest is debited, but the merge doesn't hear back
+        assert!(merge_locked(&kv, &ops, &lock, from, into).await.is_err());
+        let report = merge_locked(&kv, &ops, &lock, from, into).await.unwrap();
+        assert_eq!(report.sats_moved, 50);
+        assert_eq!(report.follows_moved, 1);
+        assert_eq!(report.posts_moved, 1);
+        assert_eq!(report.posts_not_moved, 0);
+
+        // repeating a merge moves nothing twice
+        assert_eq!(
+            merge_locked(&kv, &ops, &lock, from, into).await.unwrap(),
+            report
+        );
+        let state = ops.state.lock().unwrap();
+        assert_eq!(state.balances[&from], 0);
+        assert_eq!(state.balances[&into], 60);
+        assert_eq!(state.following[&into], vec![followed]);
+        assert_eq!(state.posts[&into].len(), 1);
+    }
+
+    #[tokio::test]
+    async fn lost_credit_is_not_replayed() {
+        let kv = InMemoryKV::new();
+        let from = Principal::from_slice(&[1; 29]);
+        let into = Principal::from_slice(&[2; 29]);
+        let ops = FakeOps {
+            into,
+            state: Mutex::new(FakeState {
+                balances: HashMap::from([(from, 50), (into, 10)]),
+                lose_next_update: Some(into),
+                ..Default::default()
+            }),
+        };
+        let lock = MergeLock::acquire(&kv, from).await.unwrap().unwrap();
+
+        // the account is credited, but the merge doesn't hear back
+        assert!(merge_locked(&kv, &ops, &lock, from, into).await.is_err());
+        let report = merge_locked(&kv, &ops, &lock, from, into).await.unwrap();
+        assert_eq!(report.sats_moved, 50);
+        let state = ops.state.lock().unwrap();
+        assert_eq!(state.balances[&from], 0);
+        assert_eq!(state.balances[&into], 60);
+    }
+}
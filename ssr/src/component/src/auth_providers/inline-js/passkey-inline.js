@@ -0,0 +1,99 @@
+function toBase64Url(buffer) {
+  const bytes = new Uint8Array(buffer);
+  let binary = "";
+  for (const byte of bytes) {
+    binary += String.fromCharCode(byte);
+  }
+  return btoa(binary).replace(/\+/g, "-").replace(/\//g, "_").replace(/=+$/, "");
+}
+
+function fromBase64Url(value) {
+  const base64 = value.replace(/-/g, "+").replace(/_/g, "/");
+  const binary = atob(base64.padEnd(base64.length + ((4 - (base64.length % 4)) % 4), "="));
+  return Uint8Array.from(binary, (c) => c.charCodeAt(0));
+}
+
+// options are serialized by webauthn-rs, unset fields are dropped
+// as browsers reject `null` for most of them
+function parseOptions(optionsJson) {
+  return JSON.parse(optionsJson, (_, value) => (value === null ? undefined : value));
+}
+
+function ensureSupported() {
+  if (!window.PublicKeyCredential || !navigator.credentials) {
+    throw "Passkeys are not supported on this browser";
+  }
+}
+
+// errors are rethrown as strings, so they can be shown as is
+function describeError(err) {
+  if (err && err.name === "NotAllowedError") {
+    return "Passkey request was cancelled";
+  }
+  return (err && err.message) || String(err);
+}
+
+export async function createPasskey(optionsJson) {
+  ensureSupported();
+  const { publicKey } = parseOptions(optionsJson);
+  publicKey.challenge = fromBase64Url(publicKey.challenge);
+  publicKey.user.id = fromBase64Url(publicKey.user.id);
+  for (const credential of publicKey.excludeCredentials || []) {
+    credential.id = fromBase64Url(credential.id);
+  }
+  // logins don't ask for a username, so the passkey must be discoverable
+  publicKey.authenticatorSelection = {
+    ...publicKey.authenticatorSelection,
+    residentKey: "required",
+    requireResidentKey: true,
+  };
+
+  let credential;
+  try {
+    credential = await navigator.credentials.create({ publicKey });
+  } catch (err) {
+    throw describeError(err);
+  }
+
+  const { response } = credential;
+  return JSON.stringify({
+    id: credential.id,
+    rawId: toBase64Url(credential.rawId),
+    type: credential.type,
+    response: {
+      attestationObject: toBase64Url(response.attestationObject),
+      clientDataJSON: toBase64Url(response.clientDataJSON),
+    },
+    extensions: {},
+  });
+}
+
+export async function getPasskey(optionsJson) {
+  ensureSupported();
+  const { publicKey } = parseOptions(optionsJson);
+  publicKey.challenge = fromBase64Url(publicKey.challenge);
+  for (const credential of publicKey.allowCredentials || []) {
+    credential.id = fromBase64Url(credential.id);
+  }
+
+  let credential;
+  try {
+    credential = await navigator.credentials.get({ publicKey });
+  } catch (err) {
+    throw describeError(err);
+  }
+
+  const { response } = credential;
+  return JSON.stringify({
+    id: credential.id,
+    rawId: toBase64Url(credential.rawId),
+    type: credential.type,
+    response: {
+      authenticatorData: toBase64Url(response.authenticatorData),
+      clientDataJSON: toBase64Url(response.clientDataJSON),
+      signature: toBase64Url(response.signature),
+      userHandle: response.userHandle ? toBase64Url(response.userHandle) : null,
+    },
+    extensions: {},
+  });
+}
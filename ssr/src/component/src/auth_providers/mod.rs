@@ -1,8 +1,11 @@
+pub mod account_merge;
+pub mod passkey;
 #[cfg(feature = "ssr")]
 mod server_impl;
 #[cfg(any(feature = "oauth-ssr", feature = "oauth-hydrate"))]
 pub mod yral;
 
+use account_merge::{merge_with_retries, AccountMergeNotice};
 use candid::Principal;
 use codee::string::JsonSerdeCodec;
 use consts::auth::REFRESH_MAX_AGE;
@@ -177,6 +180,7 @@ pub fn LoginProviders(
                 .max_age(REFRESH_MAX_AGE.as_millis() as i64),
         );
 
+    let AccountMergeNotice(merge_notice) = expect_context();
     let login_action = Action::new(move |new_id: &NewIdentity| {
         // Clone the necessary parts
         let new_id = new_id.clone();
@@ -189,6 +193,12 @@ pub fn LoginProviders(
         // Capture the context signal setter
         send_wrap(async move {
             let referrer = auth.referrer_store.get_untracked();
+            // a guest's activity is merged into the account they log in to
+            let guest_identity = if auth.is_logged_in_with_oauth().get_untracked() {
+                None
+            } else {
+                auth.user_identity.await.ok().map(|id| id.id_wire)
+            };
 
             let mut canisters = auth
                 .set_new_identity_and_wait_for_authentication(new_id.clone(), true)
@@ -199,7 +209,7 @@ pub fn LoginProviders(
             if canisters.user_principal()
                 != Principal::self_authenticating(&new_id.id_wire.from_key)
             {
-                canisters = Canisters::authenticate_with_network(new_id.id_wire).await?;
+                canisters = Canisters::authenticate_with_network(new_id.id_wire.clone()).await?;
             }
 
             if let Err(e) = handle_user_login(
@@ -214,6 +224,14 @@ pub fn LoginProviders(
                 log::warn!("failed to handle user login, err {e}. skipping");
             }
 
+            if let Some(guest_identity) = guest_identity {
+                match merge_with_retries(guest_identity, new_id.id_wire).await {
+                    Ok(report) if !report.is_empty() => merge_notice.set(Some(report)),
+                    Ok(_) => {}
+                    Err(e) => log::warn!("failed to merge guest account, err {e}. skipping"),
+                }
+            }
+
             let _ = LoginSuccessful.send_event(canisters.clone());
 
             if reload_window {
@@ -286,6 +304,7 @@ pub fn LoginProviders(
                             #[cfg(any(feature = "oauth-ssr", feature = "oauth-hydrate"))]
                             view! { <yral::YralAuthProvider /> }
                         }
+                        <passkey::PasskeyAuthProvider />
                     </div>
                     <div class="flex flex-col items-center text-center text-md">
                         <div>"By signing up, you agree to our"</div>
@@ -0,0 +1,110 @@
+use auth::{
+    login_with_passkey, passkey_login_options, passkey_registration_options, register_passkey,
+};
+use leptos::{ev, prelude::*};
+use utils::types::NewIdentity;
+use yral_types::delegated_identity::DelegatedIdentityWire;
+
+#[cfg(feature = "hydrate")]
+use wasm_bindgen::prelude::*;
+
+use super::{LoginProvButton, LoginProvCtx, ProviderKind};
+
+#[cfg(feature = "hydrate")]
+#[wasm_bindgen(module = "/src/auth_providers/inline-js/passkey-inline.js")]
+extern "C" {
+    #[wasm_bindgen(catch, js_name = createPasskey)]
+    async fn create_passkey(options_json: &str) -> Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(catch, js_name = getPasskey)]
+    async fn get_passkey(options_json: &str) -> Result<JsValue, JsValue>;
+}
+
+/// Prompts the user for a passkey, `register` creates a new one
+/// instead of using an existing one
+#[cfg(feature = "hydrate")]
+async fn passkey_ceremony(register: bool) -> Result<DelegatedIdentityWire, ServerFnError> {
+    let js_error =
+        |e: JsValue| ServerFnError::new(e.as_string().unwrap_or_else(|| format!("{e:?}")));
+    let response = |res: JsValue| res.as_string().unwrap_or_default();
+
+    if register {
+        let options = serde_json::to_string(&passkey_registration_options().await?)?;
+        let res = create_passkey(&options).await.map_err(js_error)?;
+        register_passkey(serde_json::from_str(&response(res))?).await
+    } else {
+        let options = serde_json::to_string(&passkey_login_options().await?)?;
+        let res = get_passkey(&options).await.map_err(js_error)?;
+        login_with_passkey(serde_json::from_str(&response(res))?).await
+    }
+}
+
+#[cfg(not(feature = "hydrate"))]
+async fn passkey_ceremony(_register: bool) -> Result<DelegatedIdentityWire, ServerFnError> {
+    Err(ServerFnError::new(
+        "passkeys are only available in the browser",
+    ))
+}
+
+#[component]
+pub fn PasskeyAuthProvider() -> impl IntoView {
+    let ctx: LoginProvCtx = expect_context();
+    let signing_in = move || ctx.processing.get() == Some(ProviderKind::Passkey);
+    let registering = RwSignal::new(false);
+    let error = RwSignal::new(None::<String>);
+
+    let ceremony = Action::new_unsync_local(move |register: &bool| {
+        let register = *register;
+        async move {
+            let res = passkey_ceremony(register).await;
+            ctx.set_processing.set(None);
+            match res {
+                Ok(id_wire) => ctx
+                    .login_complete
+                    .set(NewIdentity::new_without_username(id_wire)),
+                Err(ServerFnError::ServerError(e)) => error.set(Some(e)),
+                Err(e) => error.set(Some(e.to_string())),
+            }
+        }
+    });
+    let start = move |ev: ev::MouseEvent, register: bool| {
+        ev.stop_propagation();
+        error.set(None);
+        registering.set(register);
+        ceremony.dispatch_local(register);
+    };
+
+    view! {
+        <LoginProvButton
+            prov=ProviderKind::Passkey
+            class="flex gap-3 justify-center items-center py-3 w-full font-bold text-white rounded-md border border-white/50 hover:bg-white/10"
+            on_click=move |ev| start(ev, false)
+        >
+            <span>
+                {move || {
+                    if signing_in() && !registering.get() {
+                        "Waiting for passkey..."
+                    } else {
+                        "Login with a passkey"
+                    }
+                }}
+            </span>
+        </LoginProvButton>
+        <LoginProvButton
+            prov=ProviderKind::Passkey
+            class="text-sm text-neutral-300 underline hover:text-white"
+            on_click=move |ev| start(ev, true)
+        >
+            {move || {
+                if signing_in() && registering.get() {
+                    "Creating passkey..."
+                } else {
+                    "Create a passkey"
+                }
+            }}
+        </LoginProvButton>
+        <Show when=move || error.with(Option::is_some)>
+            <span class="text-sm text-center text-red-500">{move || error.get()}</span>
+        </Show>
+    }
+}
@@ -28,28 +28,41 @@ pub async fn mark_user_registered(user_principal: Principal) -> Result<bool, Ser
     mark_user_registered_impl(user_principal, user_canister).await
 }
 
-async fn ensure_user_logged_in_with_oauth(user_principal: Principal) -> Result<(), ServerFnError> {
+pub(super) async fn ensure_user_logged_in_with_oauth(
+    user_principal: Principal,
+) -> Result<(), ServerFnError> {
     #[cfg(feature = "oauth-ssr")]
     {
         use std::env;
 
         use auth::server_impl::yral::YralAuthRefreshTokenClaims;
-        use axum_extra::extract::{SignedCookieJar, cookie::Key};
+        use auth::server_impl::{extract_signed_jar, registered_principal_from_cookie};
         use consts::{
             auth::REFRESH_TOKEN_COOKIE,
             yral_auth::{YRAL_AUTH_CLIENT_ID_ENV, YRAL_AUTH_ISSUER_URL, YRAL_AUTH_TRUSTED_KEY},
         };
         use jsonwebtoken::Validation;
-        use leptos_axum::extract_with_state;
 
-        let key: Key = expect_context();
-        let jar: SignedCookieJar = extract_with_state(&key).await?;
+        let jar = extract_signed_jar().await?;
+
+        // partner identity provider logins are held by this server
+        if let Some(principal) = registered_principal_from_cookie(&jar) {
+            return if principal == user_principal {
+                Ok(())
+            } else {
+                Err(ServerFnError::new("not logged in"))
+            };
+        }
 
         let Some(refresh_token) = jar.get(REFRESH_TOKEN_COOKIE) else {
             return Err(ServerFnError::new("not logged in"));
         };
 
+        #[cfg(not(feature = "local-bin"))]
         let client_id = env::var(YRAL_AUTH_CLIENT_ID_ENV).expect("expected to have client id");
+        #[cfg(feature = "local-bin")]
+        let client_id = env::var(YRAL_AUTH_CLIENT_ID_ENV)
+            .unwrap_or_else(|_| consts::yral_auth::YRAL_AUTH_LOCAL_CLIENT_ID.into());
 
         let mut token_validation = Validation::new(jsonwebtoken::Algorithm::ES256);
         token_validation.set_audience(&[client_id]);
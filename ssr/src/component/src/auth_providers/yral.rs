@@ -8,6 +8,7 @@ use leptos_use::{
     storage::use_local_storage, use_cookie_with_options, use_event_listener, use_interval_fn,
     use_window, UseCookieOptions,
 };
+use serde::{Deserialize, Serialize};
 use state::canisters::auth_state;
 use utils::{mixpanel::mixpanel_events::*, types::NewIdentity};
 use yral_canisters_common::yral_auth_login_hint;
@@ -16,21 +17,50 @@ pub type YralAuthMessage = Result<NewIdentity, String>;
 
 use super::{LoginProvButton, LoginProvCtx, ProviderKind};
 
+/// Must match `auth::server_impl::provider::YRAL_PROVIDER_ID`
+const YRAL_PROVIDER_ID: &str = "yral";
+
+/// An OIDC issuer configured in addition to Yral Auth
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PartnerIdentityProvider {
+    pub id: String,
+    pub display_name: String,
+}
+
 #[server]
-async fn yral_auth_login_url(
+async fn identity_provider_login_url(
+    provider_id: String,
     login_hint: String,
     provider: LoginProvider,
 ) -> Result<String, ServerFnError> {
-    use auth::server_impl::yral::yral_auth_url_impl;
-    use auth::server_impl::yral::YralOAuthClient;
+    use auth::server_impl::provider::{IdentityProvider, IdentityProviders};
 
-    let oauth2: YralOAuthClient = expect_context();
+    let providers: IdentityProviders = expect_context();
 
-    let url = yral_auth_url_impl(oauth2, login_hint, provider, None).await?;
+    let url = providers
+        .get(&provider_id)?
+        .auth_url(login_hint, provider, None)
+        .await?;
 
     Ok(url)
 }
 
+#[server]
+async fn partner_identity_providers() -> Result<Vec<PartnerIdentityProvider>, ServerFnError> {
+    use auth::server_impl::provider::IdentityProviders;
+
+    let providers: IdentityProviders = expect_context();
+
+    Ok(providers
+        .partners()
+        .into_iter()
+        .map(|info| PartnerIdentityProvider {
+            id: info.id,
+            display_name: info.display_name,
+        })
+        .collect())
+}
+
 #[component]
 pub fn YralAuthProvider() -> impl IntoView {
     let ctx: LoginProvCtx = expect_context();
@@ -50,10 +80,18 @@ pub fn YralAuthProvider() -> impl IntoView {
     );
     let auth = auth_state();
 
+    let partners = OnceResource::new(partner_identity_providers());
+
     let open_yral_auth = Action::new_unsync_local(
-        move |(target, origin, provider): &(leptos::web_sys::Window, String, LoginProvider)| {
+        move |(target, origin, provider_id, provider): &(
+            leptos::web_sys::Window,
+            String,
+            String,
+            LoginProvider,
+        )| {
             let target = target.clone();
             let origin = origin.clone();
+            let provider_id = provider_id.clone();
             let provider = *provider;
 
             let url_fut = async move {
@@ -61,7 +99,7 @@ pub fn YralAuthProvider() -> impl IntoView {
                 let id = DelegatedIdentity::try_from(id.id_wire)?;
                 let login_hint = yral_auth_login_hint(&id)?;
 
-                yral_auth_login_url(login_hint, provider).await
+                identity_provider_login_url(provider_id, login_hint, provider).await
             };
 
             async move {
@@ -79,7 +117,7 @@ pub fn YralAuthProvider() -> impl IntoView {
         },
     );
 
-    let on_click = move |provider: LoginProvider, auth_journey: &str| {
+    let on_click = move |provider_id: &str, provider: LoginProvider, auth_journey: &str| {
         let window = window();
         let origin = window.origin();
 
@@ -91,7 +129,12 @@ pub fn YralAuthProvider() -> impl IntoView {
         let target = window.open().transpose().and_then(|w| w.ok()).unwrap();
 
         // load yral auth url in background
-        open_yral_auth.dispatch_local((target.clone(), origin.clone(), provider));
+        open_yral_auth.dispatch_local((
+            target.clone(),
+            origin.clone(),
+            provider_id.to_string(),
+            provider,
+        ));
 
         // Check if the target window was closed by the user
         let target_c = target.clone();
@@ -142,7 +185,7 @@ pub fn YralAuthProvider() -> impl IntoView {
                 ev.stop_propagation();
                 signing_in_provider.set(LoginProvider::Google);
                 MixpanelGlobalProps::set_auth_journey("google".to_string());
-                on_click(signing_in_provider.get(), "google");
+                on_click(YRAL_PROVIDER_ID, signing_in_provider.get(), "google");
             }
         >
             <img class="size-5" src="/img/common/google.svg" />
@@ -164,7 +207,7 @@ pub fn YralAuthProvider() -> impl IntoView {
                 ev.stop_propagation();
                 signing_in_provider.set(LoginProvider::Apple);
                 MixpanelGlobalProps::set_auth_journey("apple".to_string());
-                on_click(signing_in_provider.get(), "apple");
+                on_click(YRAL_PROVIDER_ID, signing_in_provider.get(), "apple");
             }
         >
             <img class="size-5" src="/img/common/apple.svg" />
@@ -179,5 +222,30 @@ pub fn YralAuthProvider() -> impl IntoView {
                 )}
             </span>
         </LoginProvButton>
+        <Suspense>
+            {move || Suspend::new(async move {
+                let partners = partners.await.unwrap_or_default();
+                partners
+                    .into_iter()
+                    .map(|partner| {
+                        let PartnerIdentityProvider { id, display_name } = partner;
+                        view! {
+                            <LoginProvButton
+                                prov=ProviderKind::YralAuth
+                                class="flex gap-3 justify-center items-center py-3 w-full font-bold text-black bg-white rounded-md hover:bg-white/95"
+                                on_click=move |ev| {
+                                    ev.stop_propagation();
+                                    signing_in_provider.set(LoginProvider::Any);
+                                    MixpanelGlobalProps::set_auth_journey(id.clone());
+                                    on_click(&id, LoginProvider::Any, &id);
+                                }
+                            >
+                                <span>{format!("Login with {display_name}")}</span>
+                            </LoginProvButton>
+                        }
+                    })
+                    .collect_view()
+            })}
+        </Suspense>
     }
 }
@@ -4,6 +4,7 @@ use leptos_router::components::Outlet;
 // use leptos_router::hooks::use_navigate;
 use leptos_use::{use_cookie_with_options, use_event_listener, use_window, UseCookieOptions};
 
+use crate::app_banner::SmartAppBanner;
 use codee::string::FromToStringCodec;
 use consts::{ACCOUNT_CONNECTED_STORE, NOTIFICATIONS_ENABLED_STORE, NOTIFICATION_MIGRATED_STORE};
 use leptos_use::storage::use_local_storage;
@@ -145,6 +146,7 @@ fn CtxProvider(children: Children) -> impl IntoView {
 pub fn BaseRoute() -> impl IntoView {
     view! {
         <CtxProvider>
+            <SmartAppBanner />
             <Outlet />
         </CtxProvider>
     }
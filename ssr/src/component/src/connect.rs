@@ -1,6 +1,6 @@
 use codee::string::JsonSerdeCodec;
 use consts::{auth::REFRESH_MAX_AGE, AUTH_JOURNEY_PAGE};
-use leptos::prelude::*;
+use leptos::{prelude::*, text_prop::TextProp};
 use leptos_router::hooks::use_location;
 use leptos_use::{use_cookie_with_options, UseCookieOptions};
 use state::canisters::auth_state;
@@ -8,6 +8,7 @@ use state::canisters::auth_state;
 use crate::buttons::HighlightedButton;
 use utils::{
     event_streaming::events::{LoginCta, LoginJoinOverlayViewed},
+    i18n::use_i18n,
     mixpanel::mixpanel_events::{BottomNavigationCategory, MixPanelEvent, MixpanelGlobalProps},
 };
 
@@ -15,12 +16,15 @@ use super::login_modal::LoginModal;
 
 #[component]
 pub fn ConnectLogin(
-    #[prop(optional, default = "Login")] login_text: &'static str,
+    /// Defaults to the translated "Login"
+    #[prop(optional, into)]
+    login_text: Option<TextProp>,
     #[prop(optional, default = "menu")] cta_location: &'static str,
     #[prop(optional, default = RwSignal::new(false))] show_login: RwSignal<bool>,
     #[prop(optional, into)] redirect_to: Option<String>,
 ) -> impl IntoView {
     let auth = auth_state();
+    let i18n = use_i18n();
     LoginJoinOverlayViewed.send_event(auth.event_ctx());
 
     let loc = use_location();
@@ -55,7 +59,16 @@ pub fn ConnectLogin(
                 login_click_action.dispatch(());
             }
         >
-            {move || if show_login() { "Connecting..." } else { login_text }}
+            {move || {
+                if show_login() {
+                    i18n.tr("connecting", &[])
+                } else {
+                    login_text
+                        .as_ref()
+                        .map(|text| text.get().to_string())
+                        .unwrap_or_else(|| i18n.tr("login", &[]))
+                }
+            }}
         </HighlightedButton>
         <LoginModal show=show_login redirect_to />
     }
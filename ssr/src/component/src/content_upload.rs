@@ -1,20 +1,14 @@
 use super::spinner::Spinner;
-use auth::delegate_short_lived_identity;
+use auth::{delegate_identity_with_policy, DelegationPolicy};
 use state::{canisters::auth_state, content_seed_client::ContentSeedClient};
 #[derive(Default, Clone, Copy)]
 pub struct AuthorizedUserToSeedContent(pub RwSignal<Option<(bool, Principal)>>);
 use candid::Principal;
 use leptos::prelude::*;
-use yral_canisters_common::Canisters;
 
 #[component]
 fn YoutubeUploadInner(#[prop(optional)] url: String) -> impl IntoView {
     let url_value = RwSignal::new(url);
-    let create_short_lived_delegated_identity = |canisters: &Canisters<true>| {
-        let id = canisters.identity();
-        delegate_short_lived_identity(id)
-    };
-
     let auth = auth_state();
     let on_submit: Action<(), String> = Action::new_unsync(move |_| async move {
         let cans = match auth.auth_cans().await {
@@ -22,7 +16,10 @@ fn YoutubeUploadInner(#[prop(optional)] url: String) -> impl IntoView {
             Err(e) => return e.to_string(),
         };
 
-        let delegated_identity = create_short_lived_delegated_identity(&cans);
+        // the seeded content is posted as the user
+        let policy =
+            DelegationPolicy::upload(cans.user_canister(), cans.user_post_service().await.0);
+        let delegated_identity = delegate_identity_with_policy(cans.identity(), &policy);
         let content_seed_client: ContentSeedClient = expect_context();
         let res = content_seed_client
             .upload_content(url_value(), delegated_identity)
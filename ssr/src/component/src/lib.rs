@@ -1,4 +1,5 @@
 pub mod action_btn;
+pub mod app_banner;
 pub mod auth_providers;
 pub mod back_btn;
 pub mod base_route;
@@ -28,6 +29,7 @@ pub mod option;
 pub mod overlay;
 pub mod popup;
 pub mod profile_placeholders;
+pub mod share_meta;
 pub mod share_popup;
 pub mod show_any;
 pub mod skeleton;
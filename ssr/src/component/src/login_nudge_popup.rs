@@ -10,6 +10,7 @@ use utils::{
     mixpanel::mixpanel_events::{
         BottomNavigationCategory, MixPanelEvent, MixpanelGlobalProps, StakeType,
     },
+    t,
 };
 
 use crate::{buttons::HighlightedButton, login_icons::*, overlay::ShadowOverlay};
@@ -78,23 +79,23 @@ pub fn LoginNudgePopup(
                     <div class="flex z-[2] relative flex-col items-center gap-6 text-white justify-center p-12">
                         <img src="/img/login/unlock.png" class="h-24" alt="Unlock Icon" />
 
-                        <div class="text-center text-2xl font-semibold">Unlock Higher Bets</div>
+                        <div class="text-center text-2xl font-semibold">{t!("unlock-higher-bets")}</div>
 
                         <div class="text-center text-sm text-neutral-300">
-                            "You're just 1 step away from high-stake fun."<br />
-                            Log in to unlock 5 YRAL bets!
+                            {t!("login-nudge-one-step")}<br />
+                            {t!("login-nudge-unlock-bets")}
                         </div>
 
                         <div class="flex flex-col px-8 items-start gap-4 w-full max-w-xs text-sm">
-                            <span class="text-center text-sm text-neutral-300">Why log in?</span>
+                            <span class="text-center text-sm text-neutral-300">{t!("why-log-in")}</span>
 
                             <div class="flex items-center gap-2 text-neutral-200">
                                 <Icon icon=Crown />
-                                <span>Higher bets, higher rewards</span>
+                                <span>{t!("higher-bets-higher-rewards")}</span>
                             </div>
                             <div class="flex items-center gap-2 text-neutral-200">
                                 <Icon icon=Dollar />
-                                <span>Enable withdrawals</span>
+                                <span>{t!("enable-withdrawals")}</span>
                             </div>
                         </div>
 
@@ -106,7 +107,7 @@ pub fn LoginNudgePopup(
                                 login_click_action.dispatch(());
                             }
                         >
-                            "Login Now"
+                            {t!("login-now")}
                         </HighlightedButton>
                     </div>
                 </div>
@@ -2,7 +2,7 @@ use codee::string::FromToStringCodec;
 use consts::NOTIFICATIONS_ENABLED_STORE;
 use leptos::html::Input;
 use leptos::web_sys::{Notification, NotificationPermission};
-use leptos::{ev, prelude::*};
+use leptos::{ev, prelude::*, text_prop::TextProp};
 use leptos_icons::*;
 use leptos_use::storage::use_local_storage;
 use leptos_use::use_event_listener;
@@ -11,6 +11,7 @@ use utils::mixpanel::mixpanel_events::*;
 use utils::notifications::{
     get_device_registeration_token, get_fcm_token, notification_permission_granted,
 };
+use utils::t;
 use yral_metadata_client::MetadataClient;
 
 use crate::toggle::Toggle;
@@ -20,12 +21,12 @@ pub fn NotificationToggle(
     #[prop(optional)] show_icon: bool,
     #[prop(optional)] show_label: bool,
     #[prop(optional)] icon: Option<icondata::Icon>,
-    #[prop(optional)] label_text: Option<String>,
+    #[prop(optional, into)] label_text: Option<TextProp>,
     #[prop(optional)] custom_class: Option<String>,
 ) -> impl IntoView {
     // Default values
     let icon = icon.unwrap_or(icondata::BiCommentDotsRegular);
-    let label_text = label_text.unwrap_or_else(|| "Enable Notifications".to_string());
+    let label_text = label_text.unwrap_or_else(|| t!("enable-notifications").into());
     let custom_class =
         custom_class.unwrap_or_else(|| "flex items-center justify-between w-full".to_string());
 
@@ -143,7 +144,7 @@ pub fn NotificationToggle(
             <div class=custom_class>
                 <div class="flex flex-row gap-4 items-center flex-1">
                     {show_icon.then(|| view! { <Icon attr:class="text-2xl flex-shrink-0" icon=icon /> })}
-                    {show_label.then(|| view! { <span class="text-wrap">{label_text}</span> })}
+                    {show_label.then(|| view! { <span class="text-wrap">{move || label_text.get()}</span> })}
                 </div>
                 <div class="flex-shrink-0">
                     <Toggle checked=notifs_enabled_signal node_ref=toggle_ref />
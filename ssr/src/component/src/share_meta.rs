@@ -0,0 +1,246 @@
+//! Link previews (Open Graph, Twitter Card and JSON-LD) of shared pages
+use leptos::prelude::*;
+use leptos_meta::{Link, Meta};
+use serde::{Deserialize, Serialize};
+use state::{app_state::AppState, canisters::unauth_canisters};
+use utils::{bg_url, host::get_host, mp4_url, send_wrap};
+use yral_canisters_common::utils::{posts::PostDetails, profile::ProfileDetails};
+
+/// Preview image for pages without one of their own
+pub const DEFAULT_PREVIEW_IMAGE: &str = "/img/common/preview.webp";
+/// Size of the embedded player, videos are portrait
+pub const PLAYER_WIDTH: u32 = 360;
+pub const PLAYER_HEIGHT: u32 = 640;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum PreviewKind {
+    Post,
+    Profile,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SharedVideo {
+    pub mp4_url: String,
+    /// Page playing only this video, served outside the app for embeds
+    pub player_path: String,
+    pub views: u64,
+}
+
+/// What a link to a page unfurls to
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SharePreview {
+    pub kind: PreviewKind,
+    pub title: String,
+    pub description: String,
+    /// Canonical path of the page
+    pub path: String,
+    /// Absolute, or a path on this site
+    pub image: String,
+    pub author: String,
+    pub author_path: String,
+    /// None if the video shouldn't play inline (e.g NSFW posts)
+    pub video: Option<SharedVideo>,
+}
+
+impl SharePreview {
+    /// `site_name` is the name of the tenant the post is shared from
+    pub fn for_post(post: &PostDetails, site_name: &str) -> Self {
+        let author = post.username_or_fallback();
+        let description = if post.description.is_empty() {
+            format!("Watch @{author}'s video on {site_name}")
+        } else {
+            post.description.clone()
+        };
+        let (image, video) = if post.is_nsfw {
+            (DEFAULT_PREVIEW_IMAGE.to_string(), None)
+        } else {
+            let video = SharedVideo {
+                mp4_url: mp4_url(&post.uid),
+                player_path: format!("/embed/{}/{}", post.canister_id, post.post_id),
+                views: post.views,
+            };
+            (bg_url(&post.uid), Some(video))
+        };
+
+        Self {
+            kind: PreviewKind::Post,
+            title: format!("@{author} on {site_name}"),
+            description,
+            path: format!("/hot-or-not/{}/{}", post.canister_id, post.post_id),
+            image,
+            author,
+            author_path: format!("/profile/{}/posts", post.username_or_principal()),
+            video,
+        }
+    }
+
+    pub fn for_profile(profile: &ProfileDetails, site_name: &str) -> Self {
+        let author = profile.username_or_fallback();
+        let author_path = format!("/profile/{}/posts", profile.username_or_principal());
+        let description = profile
+            .bio
+            .clone()
+            .filter(|bio| !bio.is_empty())
+            .unwrap_or_else(|| format!("Check out @{author}'s videos on {site_name}"));
+
+        Self {
+            kind: PreviewKind::Profile,
+            title: format!("@{author} on {site_name}"),
+            description,
+            path: author_path.clone(),
+            image: profile.profile_pic_or_random(),
+            author,
+            author_path,
+            video: None,
+        }
+    }
+}
+
+/// Origin of the site serving the current request
+pub fn site_origin() -> String {
+    format!("https://{}", get_host())
+}
+
+/// Origin the current tenant's pages are indexed under, whichever host serves them
+pub fn canonical_origin() -> String {
+    format!("https://{}", AppState::current().app_type.canonical_host())
+}
+
+/// Resolves `url` against `origin` if it's a path
+pub fn absolute_url(origin: &str, url: &str) -> String {
+    if url.starts_with('/') {
+        format!("{origin}{url}")
+    } else {
+        url.to_string()
+    }
+}
+
+/// schema.org VideoObject of a video post, escaped to be inlined in a `<script>`
+///
+/// page urls point to `canonical`, media is served from `origin`
+fn video_json_ld(preview: &SharePreview, origin: &str, canonical: &str) -> Option<String> {
+    let video = preview.video.as_ref()?;
+    let json_ld = serde_json::json!({
+        "@context": "https://schema.org",
+        "@type": "VideoObject",
+        "name": preview.title,
+        "description": preview.description,
+        "thumbnailUrl": [absolute_url(origin, &preview.image)],
+        "contentUrl": video.mp4_url,
+        "embedUrl": absolute_url(origin, &video.player_path),
+        "url": absolute_url(canonical, &preview.path),
+        "author": {
+            "@type": "Person",
+            "name": preview.author,
+            "url": absolute_url(canonical, &preview.author_path),
+        },
+        "interactionStatistic": {
+            "@type": "InteractionCounter",
+            "interactionType": { "@type": "WatchAction" },
+            "userInteractionCount": video.views,
+        },
+    });
+    Some(json_ld.to_string().replace("</", "<\\/"))
+}
+
+#[component]
+pub fn SharePreviewTags(preview: SharePreview) -> impl IntoView {
+    let site_name = AppState::current().name;
+    let origin = site_origin();
+    let canonical = canonical_origin();
+    let url = absolute_url(&canonical, &preview.path);
+    let image = absolute_url(&origin, &preview.image);
+    let json_ld = video_json_ld(&preview, &origin, &canonical);
+    let og_type = match (preview.kind, &preview.video) {
+        (PreviewKind::Post, Some(_)) => "video.other",
+        (PreviewKind::Post, None) => "website",
+        (PreviewKind::Profile, _) => "profile",
+    };
+    let oembed = (preview.kind == PreviewKind::Post).then(|| {
+        format!(
+            "{origin}/oembed?format=json&url={}",
+            urlencoding::encode(&url)
+        )
+    });
+    let video_tags = preview.video.clone().map(|video| {
+        let player = absolute_url(&origin, &video.player_path);
+        view! {
+            <Meta property="og:video" content=video.mp4_url.clone() />
+            <Meta property="og:video:secure_url" content=video.mp4_url.clone() />
+            <Meta property="og:video:type" content="video/mp4" />
+            <Meta property="og:video:width" content=PLAYER_WIDTH.to_string() />
+            <Meta property="og:video:height" content=PLAYER_HEIGHT.to_string() />
+            <Meta name="twitter:player" content=player />
+            <Meta name="twitter:player:width" content=PLAYER_WIDTH.to_string() />
+            <Meta name="twitter:player:height" content=PLAYER_HEIGHT.to_string() />
+            <Meta name="twitter:player:stream" content=video.mp4_url />
+            <Meta name="twitter:player:stream:content_type" content="video/mp4" />
+        }
+    });
+    let twitter_card = if video_tags.is_some() {
+        "player"
+    } else {
+        "summary_large_image"
+    };
+
+    view! {
+        <Meta property="og:site_name" content=site_name />
+        <Meta property="og:type" content=og_type />
+        <Meta property="og:url" content=url.clone() />
+        <Meta property="og:title" content=preview.title.clone() />
+        <Meta property="og:description" content=preview.description.clone() />
+        <Meta property="og:image" content=image.clone() />
+        <Meta name="twitter:card" content=twitter_card />
+        <Meta name="twitter:title" content=preview.title />
+        <Meta name="twitter:description" content=preview.description />
+        <Meta name="twitter:image" content=image />
+        {video_tags}
+        <Link rel="canonical" href=url />
+        {oembed
+            .map(|href| {
+                view! { <Link rel="alternate" type_="application/json+oembed" href /> }
+            })}
+        {json_ld
+            .map(|json_ld| {
+                view! { <script type="application/ld+json" inner_html=json_ld></script> }
+            })}
+    }
+}
+
+/// Link preview of a post
+///
+/// render it from the page's post resource, made blocking so crawlers
+/// that don't run javascript see it
+#[component]
+pub fn PostSharePreview(post: PostDetails) -> impl IntoView {
+    let site_name = AppState::current().name;
+    view! { <SharePreviewTags preview=SharePreview::for_post(&post, site_name) /> }
+}
+
+/// Link preview of a profile, `id` is a username or principal
+#[component]
+pub fn ProfileSharePreview(id: String) -> impl IntoView {
+    let site_name = AppState::current().name;
+    let preview = Resource::new_blocking(
+        || (),
+        move |_| {
+            let id = id.clone();
+            send_wrap(async move {
+                let profile = unauth_canisters()
+                    .get_profile_details(id)
+                    .await
+                    .ok()
+                    .flatten()?;
+                Some(SharePreview::for_profile(&profile, site_name))
+            })
+        },
+    );
+
+    view! {
+        <Suspense>
+            {move || Suspend::new(async move {
+                preview.await.map(|preview| view! { <SharePreviewTags preview /> })
+            })}
+        </Suspense>
+    }
+}
@@ -4,6 +4,7 @@ use leptos_icons::*;
 use state::app_state::AppState;
 use utils::{
     host::get_host,
+    t,
     web::{copy_to_clipboard, share_url},
 };
 
@@ -34,7 +35,9 @@ pub fn ShareContent(
                     alt=format!("{} Logo", app_state.clone().unwrap().name)
                 />
 
-                <span class="text-xl font-semibold text-center md:text-2xl">Share this app</span>
+                <span class="text-xl font-semibold text-center md:text-2xl">
+                    {t!("share-this-app")}
+                </span>
             </div>
             <SocialShare message=message.clone() share_link=share_link_social.clone() />
             <div class="flex overflow-x-auto justify-center items-center px-10 mx-1 space-x-2 w-full h-10 rounded-xl border-2 md:h-20 border-neutral-700">
@@ -50,7 +53,7 @@ pub fn ShareContent(
                 on:click=move |_| show_popup.set(false)
                 class="py-4 w-3/4 text-lg text-center text-white rounded-full bg-primary-600"
             >
-                Back
+                {t!("back")}
             </button>
 
         </div>
@@ -11,6 +11,10 @@ pub fn VideoPlayer(
     #[prop(into)] muted: Signal<bool>,
     #[prop(into)] autoplay: Signal<bool>,
     #[prop(into)] high_priority: bool,
+    /// the start of the video is in the prefetch cache, so it can be buffered
+    /// right away without touching the network
+    #[prop(optional, into)]
+    prefetched: Signal<bool>,
 ) -> impl IntoView {
     // TODO: experiment with preload attr to see how it affects ux
     let _ = high_priority;
@@ -33,6 +37,7 @@ pub fn VideoPlayer(
                 playsinline
                 disablepictureinpicture
                 disableremoteplayback
+                preload=move || prefetched.get().then_some("auto")
                 // preload={if high_priority { "auto" } else { "metadata" }}
             ></video>
         </label>
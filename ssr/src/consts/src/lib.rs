@@ -35,12 +35,17 @@ pub static CF_BASE_URL: Lazy<Url> =
 pub const NOTIFICATIONS_ENABLED_STORE: &str = "yral-notifications-enabled";
 pub const NOTIFICATION_MIGRATED_STORE: &str = "notifications-migrated";
 pub const NSFW_TOGGLE_STORE: &str = "nsfw-enabled";
+pub const APP_BANNER_DISMISSED_STORE: &str = "app-banner-dismissed";
 pub const NSFW_ENABLED_COOKIE: &str = "nsfw-enabled-cookie";
 pub const REFERRER_COOKIE: &str = "referrer";
+pub const LOCALE_COOKIE: &str = "locale";
 pub const USER_CANISTER_ID_STORE: &str = "user-canister-id";
 pub const USER_PRINCIPAL_STORE: &str = "user-principal";
 pub const USER_INTERNAL_STORE: &str = "user-internal";
 pub const WALLET_BALANCE_STORE_KEY: &str = "wallet-balance-sats";
+/// prefix, the user's principal is appended to this
+pub const SEEN_POSTS_STORE: &str = "seen-posts";
+pub const LOCAL_FEED_CACHE_STORE: &str = "local-feed-cache";
 
 pub static OFF_CHAIN_AGENT_URL: Lazy<Url> =
     Lazy::new(|| Url::parse("https://offchain.yral.com").unwrap());
@@ -64,6 +69,10 @@ pub const CLOUDFLARE_ACCOUNT_ID: &str = "a209c523d2d9646cc56227dbe6ce3ede";
 pub const AUTH_UTIL_COOKIES_MAX_AGE_MS: i64 = 400 * 24 * 60 * 60 * 1000; // 400 days
 
 pub const MAX_VIDEO_ELEMENTS_FOR_FEED: usize = 200;
+/// Number of posts kept behind the current post in the feed, older posts are evicted
+pub const FEED_HISTORY_DEPTH: usize = 20;
+/// Upper bound on recommendations waiting to be moved into the feed
+pub const MAX_PENDING_FEED_POSTS: usize = 150;
 
 pub mod social {
     pub const TELEGRAM_YRAL: &str = "https://t.me/+c-LTX0Cp-ENmMzI1";
@@ -77,6 +86,11 @@ pub mod auth {
 
     /// Delegation Expiry, 7 days
     pub const DELEGATION_MAX_AGE: Duration = Duration::from_secs(60 * 60 * 24 * 7);
+    /// Expiry of delegations handed to the upload service, 1 day
+    /// uploads are processed in the background, after the user has left
+    pub const UPLOAD_DELEGATION_MAX_AGE: Duration = Duration::from_secs(60 * 60 * 24);
+    /// Expiry of delegations handed to the video generation service, 1 hour
+    pub const VIDEO_GEN_DELEGATION_MAX_AGE: Duration = Duration::from_secs(60 * 60);
     /// Refresh expiry, 29 days
     pub const REFRESH_MAX_AGE: Duration = Duration::from_secs(60 * 60 * 24 * 29);
     pub const REFRESH_TOKEN_COOKIE: &str = "user-identity";
@@ -94,10 +108,25 @@ pub mod yral_auth {
     use jsonwebtoken::DecodingKey;
     use std::sync::LazyLock;
 
+    #[cfg(not(feature = "local-bin"))]
     pub const YRAL_AUTH_AUTHORIZATION_URL: &str = "https://auth.yral.com/oauth/auth";
+    #[cfg(not(feature = "local-bin"))]
     pub const YRAL_AUTH_TOKEN_URL: &str = "https://auth.yral.com/oauth/token";
+    #[cfg(not(feature = "local-bin"))]
     pub const YRAL_AUTH_ISSUER_URL: &str = "https://auth.yral.com";
 
+    // in-process mock issuer, started by `init::containers::TestContainers`
+    #[cfg(feature = "local-bin")]
+    pub const YRAL_AUTH_AUTHORIZATION_URL: &str = "http://127.0.0.1:4010/oauth/auth";
+    #[cfg(feature = "local-bin")]
+    pub const YRAL_AUTH_TOKEN_URL: &str = "http://127.0.0.1:4010/oauth/token";
+    #[cfg(feature = "local-bin")]
+    pub const YRAL_AUTH_ISSUER_URL: &str = "http://127.0.0.1:4010";
+    /// Used if `YRAL_AUTH_CLIENT_ID` is not set
+    #[cfg(feature = "local-bin")]
+    pub const YRAL_AUTH_LOCAL_CLIENT_ID: &str = "yral-local";
+
+    #[cfg(not(feature = "local-bin"))]
     pub static YRAL_AUTH_TRUSTED_KEY: LazyLock<DecodingKey> = LazyLock::new(|| {
         let pem = "-----BEGIN PUBLIC KEY-----
 MFkwEwYHKoZIzj0CAQYIKoZIzj0DAQcDQgAEoqN3/0RNfrnrnYGxKBgy/qHnmITr
@@ -106,6 +135,15 @@ MFkwEwYHKoZIzj0CAQYIKoZIzj0DAQcDQgAEoqN3/0RNfrnrnYGxKBgy/qHnmITr
         DecodingKey::from_ec_pem(pem.as_bytes()).unwrap()
     });
 
+    #[cfg(feature = "local-bin")]
+    pub static YRAL_AUTH_TRUSTED_KEY: LazyLock<DecodingKey> = LazyLock::new(|| {
+        let pem = "-----BEGIN PUBLIC KEY-----
+MFkwEwYHKoZIzj0CAQYIKoZIzj0DAQcDQgAEcIa0OXwsZUR6iHJsmUUcKIJ0RQd7
+8zRZLsnG5o6HXwb7uww6HNzUMxT+HK+C+a4RaCboEUAeS4dWGj60as/BcQ==
+-----END PUBLIC KEY-----";
+        DecodingKey::from_ec_pem(pem.as_bytes()).unwrap()
+    });
+
     pub const YRAL_AUTH_CLIENT_ID_ENV: &str = "YRAL_AUTH_CLIENT_ID";
 }
 
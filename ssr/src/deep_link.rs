@@ -0,0 +1,96 @@
+//! Files the mobile OSes fetch to open our links in the native apps, and
+//! claiming deferred links on the app's first launch
+use auth::server_impl::store::KVStore;
+use axum::{
+    extract::State,
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use component::app_banner::{server::deferred_link_key, DeferredLink};
+use serde::Deserialize;
+use serde_json::json;
+use state::{
+    app_state::{self, NativeApps},
+    app_type::AppType,
+    server::AppState,
+};
+
+fn native_apps(headers: &HeaderMap) -> NativeApps {
+    let host = headers
+        .get(header::HOST)
+        .and_then(|host| host.to_str().ok())
+        .unwrap_or_default();
+    app_state::AppState::from_type(&AppType::from_host(host)).native_apps
+}
+
+/// Universal links, served at both the legacy and the `.well-known` path
+pub async fn apple_app_site_association(headers: HeaderMap) -> Response {
+    let Some(ios) = native_apps(&headers).ios else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    Json(json!({
+        "applinks": {
+            "details": [{
+                "appIDs": [ios.app_id],
+                "components": [{ "/": "/*" }],
+            }],
+        },
+        "webcredentials": {
+            "apps": [ios.app_id],
+        },
+    }))
+    .into_response()
+}
+
+/// Android App Links
+pub async fn assetlinks(headers: HeaderMap) -> Response {
+    let statements: Vec<_> = native_apps(&headers)
+        .android
+        .into_iter()
+        .map(|android| {
+            json!({
+                "relation": ["delegate_permission/common.handle_all_urls"],
+                "target": {
+                    "namespace": "android_app",
+                    "package_name": android.package_name,
+                    "sha256_cert_fingerprints": android.sha256_cert_fingerprints,
+                },
+            })
+        })
+        .collect();
+    Json(statements).into_response()
+}
+
+#[derive(Deserialize)]
+pub struct DeferredLinkClaim {
+    /// Token handed to the install, see [`component::app_banner::install_referrer`]
+    token: String,
+}
+
+/// Link saved by [`component::app_banner::save_deferred_link`] under the
+/// claimed token, a link can only be claimed once
+pub async fn claim_deferred_link(
+    State(app_state): State<AppState>,
+    Json(claim): Json<DeferredLinkClaim>,
+) -> Response {
+    let Ok(token) = uuid::Uuid::parse_str(&claim.token) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let link = match app_state.kv.take(deferred_link_key(&token)).await {
+        Ok(Some(link)) => link,
+        Ok(None) => return StatusCode::NOT_FOUND.into_response(),
+        Err(e) => {
+            tracing::warn!("failed to claim deferred link: {e}");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    match serde_json::from_str::<DeferredLink>(&link) {
+        Ok(link) => Json(link).into_response(),
+        Err(e) => {
+            tracing::warn!("invalid deferred link: {e}");
+            StatusCode::NOT_FOUND.into_response()
+        }
+    }
+}
@@ -26,6 +26,19 @@ pub struct TestContainers {
     redis: MaybeContainer<GenericImage>,
     metadata: MaybeContainer<YralMetadata>,
     backend: MaybeContainer<YralBackend>,
+    #[cfg(feature = "oauth-ssr")]
+    mock_oidc: Option<MockOidc>,
+}
+
+/// Stops the in-process mock issuer on drop, like the containers
+#[cfg(feature = "oauth-ssr")]
+struct MockOidc(tokio::task::JoinHandle<()>);
+
+#[cfg(feature = "oauth-ssr")]
+impl Drop for MockOidc {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
 }
 
 impl TestContainers {
@@ -93,4 +106,10 @@ impl TestContainers {
         let img = YralBackend::new(YRAL_BACKEND_CONTAINER_TAG.into());
         self.backend = Some(Self::start_image(img, backend::AGENT_PORT).await);
     }
+
+    /// Stand-in for Yral Auth, see [`super::mock_oidc`]
+    #[cfg(feature = "oauth-ssr")]
+    pub async fn start_mock_oidc(&mut self) {
+        self.mock_oidc = Some(MockOidc(super::mock_oidc::serve().await));
+    }
 }
@@ -1,10 +1,15 @@
 #[cfg(feature = "local-bin")]
 pub mod containers;
+#[cfg(all(feature = "local-bin", feature = "oauth-ssr"))]
+pub mod mock_oidc;
 
 use std::env;
 
-use auth::server_impl::store::KVStoreImpl;
-use axum_extra::extract::cookie::Key;
+use auth::server_impl::{
+    cookie_keyring::CookieKeyring,
+    passkey::{PasskeyConfig, PasskeyConfigs},
+    store::{encrypted_kv::SecretKeyring, KVStoreImpl},
+};
 use leptos::prelude::*;
 use leptos_axum::AxumRouteListing;
 use state::server::AppState;
@@ -20,58 +25,183 @@ fn init_cf() -> gob_cloudflare::CloudflareAuth {
     CloudflareAuth::new(creds)
 }
 
-fn init_cookie_key() -> Key {
-    let cookie_key_raw = {
-        #[cfg(not(feature = "local-bin"))]
-        {
-            let cookie_key_str = env::var("COOKIE_KEY").expect("`COOKIE_KEY` is required!");
-            hex::decode(cookie_key_str).expect("Invalid `COOKIE_KEY` (must be length 128 hex)")
-        }
-        #[cfg(feature = "local-bin")]
-        {
-            use rand_chacha::rand_core::{OsRng, RngCore};
-            let mut cookie_key = [0u8; 64];
-            OsRng.fill_bytes(&mut cookie_key);
-            cookie_key.to_vec()
-        }
-    };
-    Key::from(&cookie_key_raw)
+/// In-memory KV store, opted into with `KV_STORE=memory`
+/// faults can be injected with `KV_FAULT_LATENCY_MS` and `KV_FAULT_ERROR_RATE`
+fn init_in_memory_kv() -> KVStoreImpl {
+    use auth::server_impl::store::in_memory_kv::{FaultConfig, InMemoryKV};
+    use std::time::Duration;
+
+    let latency_ms = env::var("KV_FAULT_LATENCY_MS")
+        .map(|ms| ms.parse().expect("Invalid `KV_FAULT_LATENCY_MS`"))
+        .unwrap_or(0);
+    let error_rate = env::var("KV_FAULT_ERROR_RATE")
+        .map(|rate| rate.parse().expect("Invalid `KV_FAULT_ERROR_RATE`"))
+        .unwrap_or(0.0);
+
+    log::warn!("initializing in-memory KV store, data will not persist across restarts");
+    KVStoreImpl::InMemory(InMemoryKV::with_faults(FaultConfig {
+        latency: Duration::from_millis(latency_ms),
+        error_rate,
+    }))
+}
+
+/// `COOKIE_KEY` signs new cookies, `COOKIE_KEY_FALLBACKS` (comma separated)
+/// are only used to verify cookies signed before a rotation
+fn init_cookie_keyring() -> CookieKeyring {
+    #[cfg(not(feature = "local-bin"))]
+    {
+        CookieKeyring::from_env().unwrap_or_else(|e| panic!("invalid cookie keys: {e}"))
+    }
+    #[cfg(feature = "local-bin")]
+    {
+        use rand_chacha::rand_core::{OsRng, RngCore};
+        let mut cookie_key = [0u8; 64];
+        OsRng.fill_bytes(&mut cookie_key);
+        CookieKeyring::new(axum_extra::extract::cookie::Key::from(&cookie_key), vec![])
+    }
 }
 
+/// One relying party per tenant, usable from its canonical host and its `passkey_rp_id`
+fn init_passkey_configs() -> PasskeyConfigs {
+    #[cfg(not(feature = "local-bin"))]
+    use state::{app_state::AppState as Tenant, app_type::AppType};
+
+    #[cfg(feature = "local-bin")]
+    let configs = vec![
+        PasskeyConfig::new("localhost", "Yral", &["http://localhost:3000".into()])
+            .expect("Invalid passkey config"),
+    ];
+    #[cfg(not(feature = "local-bin"))]
+    let configs = AppType::ALL
+        .iter()
+        .map(|app_type| {
+            let tenant = Tenant::from_type(app_type);
+            let mut origins = vec![format!("https://{}", app_type.canonical_host())];
+            if tenant.passkey_rp_id != app_type.canonical_host() {
+                origins.push(format!("https://{}", tenant.passkey_rp_id));
+            }
+            PasskeyConfig::new(tenant.passkey_rp_id, tenant.name, &origins)
+                .unwrap_or_else(|e| panic!("Invalid passkey config for {}: {e}", tenant.name))
+        })
+        .collect();
+
+    PasskeyConfigs::new(configs)
+}
+
+/// `KV_SECRET_KEYS` is a comma separated list of `<key_id>:<hex master key>`
+/// the highest key id is used for new writes, older ids are kept for decryption
+fn init_secret_keyring() -> SecretKeyring {
+    #[cfg(not(feature = "local-bin"))]
+    {
+        let keys = env::var("KV_SECRET_KEYS").expect("`KV_SECRET_KEYS` is required!");
+        SecretKeyring::from_config(&keys).expect("Invalid `KV_SECRET_KEYS`")
+    }
+    #[cfg(feature = "local-bin")]
+    {
+        use rand_chacha::rand_core::{OsRng, RngCore};
+        let mut master_key = [0u8; 32];
+        OsRng.fill_bytes(&mut master_key);
+        SecretKeyring::new([(1, master_key.to_vec())]).unwrap()
+    }
+}
+
+/// ID tokens are verified against the signing keys Yral Auth publishes,
+/// so its discovery document must be reachable at startup
 #[cfg(feature = "oauth-ssr")]
-fn init_yral_oauth() -> auth::server_impl::yral::YralOAuthClient {
+async fn init_yral_oauth() -> auth::server_impl::yral::YralOAuthClient {
     use auth::server_impl::yral::YralOAuthClient;
     use consts::yral_auth::{
         YRAL_AUTH_AUTHORIZATION_URL, YRAL_AUTH_CLIENT_ID_ENV, YRAL_AUTH_ISSUER_URL,
         YRAL_AUTH_TOKEN_URL,
     };
+    use openidconnect::{core::CoreProviderMetadata, reqwest::async_http_client};
     use openidconnect::{AuthType, AuthUrl, TokenUrl};
     use openidconnect::{ClientId, ClientSecret, IssuerUrl, RedirectUrl};
 
-    let client_id = env::var(YRAL_AUTH_CLIENT_ID_ENV)
-        .unwrap_or_else(|_| panic!("`{YRAL_AUTH_CLIENT_ID_ENV}` is required!"));
-    let client_secret =
-        env::var("YRAL_AUTH_CLIENT_SECRET").expect("`YRAL_AUTH_CLIENT_SECRET` is required!");
-    let redirect_uri =
-        env::var("YRAL_AUTH_REDIRECT_URL").expect("`YRAL_AUTH_REDIRECT_URL` is required!");
+    #[cfg(not(feature = "local-bin"))]
+    let (client_id, client_secret, redirect_uri) = (
+        env::var(YRAL_AUTH_CLIENT_ID_ENV)
+            .unwrap_or_else(|_| panic!("`{YRAL_AUTH_CLIENT_ID_ENV}` is required!")),
+        Some(env::var("YRAL_AUTH_CLIENT_SECRET").expect("`YRAL_AUTH_CLIENT_SECRET` is required!")),
+        env::var("YRAL_AUTH_REDIRECT_URL").expect("`YRAL_AUTH_REDIRECT_URL` is required!"),
+    );
+    // the mock issuer accepts any client
+    #[cfg(feature = "local-bin")]
+    let (client_id, client_secret, redirect_uri) = (
+        env::var(YRAL_AUTH_CLIENT_ID_ENV)
+            .unwrap_or_else(|_| consts::yral_auth::YRAL_AUTH_LOCAL_CLIENT_ID.into()),
+        env::var("YRAL_AUTH_CLIENT_SECRET").ok(),
+        env::var("YRAL_AUTH_REDIRECT_URL")
+            .unwrap_or_else(|_| "http://127.0.0.1:3000/auth/yral/redirect".into()),
+    );
+
+    let issuer = IssuerUrl::new(YRAL_AUTH_ISSUER_URL.to_string()).unwrap();
+    let metadata = CoreProviderMetadata::discover_async(issuer.clone(), async_http_client)
+        .await
+        .expect("failed to fetch the Yral Auth signing keys");
 
     YralOAuthClient::new(
         ClientId::new(client_id),
-        Some(ClientSecret::new(client_secret)),
-        IssuerUrl::new(YRAL_AUTH_ISSUER_URL.to_string()).unwrap(),
+        client_secret.map(ClientSecret::new),
+        issuer,
         AuthUrl::new(YRAL_AUTH_AUTHORIZATION_URL.to_string()).unwrap(),
         Some(TokenUrl::new(YRAL_AUTH_TOKEN_URL.to_string()).unwrap()),
         None,
-        Default::default(),
+        metadata.jwks().clone(),
     )
     .set_redirect_uri(RedirectUrl::new(redirect_uri).unwrap())
     .set_auth_type(AuthType::RequestBody)
 }
 
+/// Yral Auth, plus the generic OIDC providers in `OIDC_PROVIDERS`,
+/// a JSON list of [`auth::server_impl::provider::oidc::OidcProviderConfig`]
+#[cfg(feature = "oauth-ssr")]
+async fn init_identity_providers(
+    yral_oauth_client: auth::server_impl::yral::YralOAuthClient,
+) -> auth::server_impl::provider::IdentityProviders {
+    use auth::server_impl::provider::{
+        oidc::{OidcProvider, OidcProviderConfig},
+        IdentityProviderImpl, IdentityProviders, YRAL_PROVIDER_ID,
+    };
+    use auth::server_impl::yral::YralProvider;
+
+    let mut providers = vec![(
+        YRAL_PROVIDER_ID.to_string(),
+        IdentityProviderImpl::Yral(YralProvider(yral_oauth_client)),
+    )];
+
+    let configs: Vec<OidcProviderConfig> = env::var("OIDC_PROVIDERS")
+        .map(|raw| serde_json::from_str(&raw).expect("Invalid `OIDC_PROVIDERS`"))
+        .unwrap_or_default();
+    for config in configs {
+        let id = config.id.clone();
+        assert_ne!(
+            id, YRAL_PROVIDER_ID,
+            "`{YRAL_PROVIDER_ID}` is a reserved provider id"
+        );
+        // a partner being down must not take the whole app down with it
+        let provider = match OidcProvider::discover(config).await {
+            Ok(provider) => provider,
+            Err(e) => {
+                log::error!("failed to discover OIDC provider `{id}`, skipping it: {e}");
+                continue;
+            }
+        };
+        log::info!("registered OIDC provider `{id}`");
+        providers.push((id, IdentityProviderImpl::Oidc(provider)));
+    }
+
+    IdentityProviders::new(providers)
+}
+
 #[cfg(feature = "oauth-ssr")]
 fn init_yral_auth_migration_key() -> jsonwebtoken::EncodingKey {
+    #[cfg(not(feature = "local-bin"))]
     let raw_pem = env::var("YRAL_AUTH_MIGRATION_ES256_PEM")
         .expect("`YRAL_AUTH_MIGRATION_ES256_PEM` is required!");
+    #[cfg(feature = "local-bin")]
+    let raw_pem = env::var("YRAL_AUTH_MIGRATION_ES256_PEM")
+        .unwrap_or_else(|_| mock_oidc::SIGNING_KEY_PEM.into());
     let enc_key = jsonwebtoken::EncodingKey::from_ec_pem(raw_pem.as_bytes())
         .expect("Invalid `YRAL_AUTH_MIGRATION_ES256_PEM`");
 
@@ -121,6 +251,19 @@ fn init_admin_canisters() -> state::admin_canisters::AdminCanisters {
     }
 }
 
+/// `ML_FEED_SOURCE` optionally holds a JSON [`utils::ml_feed::FeedSourceConfig`],
+/// the default recsys service is used if it isn't set
+fn init_feed_source() -> utils::ml_feed::FeedSourceImpl {
+    use utils::ml_feed::FeedSourceConfig;
+
+    let config = env::var("ML_FEED_SOURCE")
+        .map(|raw| serde_json::from_str(&raw).expect("Invalid `ML_FEED_SOURCE`"))
+        .unwrap_or_else(|_| FeedSourceConfig::default());
+    log::info!("using ML feed source: {config:?}");
+
+    config.build().expect("failed to initialize ML feed source")
+}
+
 #[cfg(feature = "qstash")]
 fn init_qstash_client() -> utils::qstash::QStashClient {
     use utils::qstash::QStashClient;
@@ -154,6 +297,10 @@ impl AppStateBuilder {
     }
 
     async fn init_redis_kv(&mut self) -> KVStoreImpl {
+        if env::var("KV_STORE").is_ok_and(|store| store == "memory") {
+            return init_in_memory_kv();
+        }
+
         #[cfg(feature = "redis-kv")]
         {
             #[cfg(feature = "local-bin")]
@@ -188,7 +335,9 @@ impl AppStateBuilder {
         {
             use auth::server_impl::store::redb_kv::ReDBKV;
             log::info!("initiating reDB instnace enabled (since no feature flag passed)");
-            KVStoreImpl::ReDB(ReDBKV::new().expect("Failed to initialize ReDB"))
+            let kv = ReDBKV::new().expect("Failed to initialize ReDB");
+            kv.spawn_expiry_sweep();
+            KVStoreImpl::ReDB(kv)
         }
     }
 
@@ -196,13 +345,20 @@ impl AppStateBuilder {
         // if feature is local it will return local redis instance and if redis-kv not passed it will return reRB
         // else it will return dragonfly kv
         let kv = self.init_redis_kv().await;
+        // read once, so an invalid `TRUSTED_PROXY` fails startup rather than requests
+        log::info!("trusted proxy: {:?}", utils::client_ip::trusted_proxy());
 
         #[cfg(feature = "local-bin")]
         {
             self.containers.start_backend().await;
             self.containers.start_metadata().await;
+            #[cfg(feature = "oauth-ssr")]
+            self.containers.start_mock_oidc().await;
         }
 
+        #[cfg(feature = "oauth-ssr")]
+        let yral_oauth_client = init_yral_oauth().await;
+
         let app_state = AppState {
             leptos_options: self.leptos_options,
             canisters: Canisters::default(),
@@ -212,9 +368,13 @@ impl AppStateBuilder {
             #[cfg(feature = "cloudflare")]
             cloudflare: init_cf(),
             kv,
-            cookie_key: init_cookie_key(),
+            secret_keyring: init_secret_keyring(),
+            cookie_keyring: init_cookie_keyring(),
+            passkey_configs: init_passkey_configs(),
+            #[cfg(feature = "oauth-ssr")]
+            identity_providers: init_identity_providers(yral_oauth_client.clone()).await,
             #[cfg(feature = "oauth-ssr")]
-            yral_oauth_client: init_yral_oauth(),
+            yral_oauth_client,
             #[cfg(feature = "oauth-ssr")]
             yral_auth_migration_key: init_yral_auth_migration_key(),
             #[cfg(feature = "ga4")]
@@ -226,6 +386,7 @@ impl AppStateBuilder {
                 let jwt = env::var("HON_WORKER_JWT").expect("`HON_WORKER_JWT` is required!");
                 HonWorkerJwt(std::sync::Arc::new(jwt))
             },
+            feed_source: init_feed_source(),
         };
 
         AppStateRes {
@@ -2,11 +2,19 @@
 #![allow(clippy::empty_docs)]
 pub mod app;
 pub mod canister_ids;
+#[cfg(feature = "ssr")]
+pub mod deep_link;
 pub mod error_template;
 #[cfg(feature = "ssr")]
 pub mod fallback;
 #[cfg(feature = "ssr")]
 pub mod init;
+#[cfg(feature = "ssr")]
+pub mod oembed;
+#[cfg(feature = "ssr")]
+pub mod rate_limit;
+#[cfg(feature = "ssr")]
+pub mod sitemap;
 
 #[cfg(feature = "hydrate")]
 #[wasm_bindgen::prelude::wasm_bindgen]
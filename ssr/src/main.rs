@@ -5,13 +5,23 @@ use axum::{
     http::Request,
     response::{IntoResponse, Response},
 };
-use axum::{routing::get, Router};
+use axum::{
+    middleware,
+    routing::{get, post},
+    Router,
+};
+use hot_or_not_web_leptos_ssr::deep_link::{
+    apple_app_site_association, assetlinks, claim_deferred_link,
+};
 use hot_or_not_web_leptos_ssr::fallback::file_and_error_handler;
+use hot_or_not_web_leptos_ssr::oembed::{embed_player, oembed};
+use hot_or_not_web_leptos_ssr::rate_limit::rate_limit;
+use hot_or_not_web_leptos_ssr::sitemap::{robots_txt, sitemap_index, sitemap_shard};
 use sentry_tower::{NewSentryLayer, SentryHttpLayer};
+use state::app_type::is_cors_allowed_origin;
 use state::server::AppState;
 use tower::ServiceBuilder;
 use tracing::instrument;
-use utils::host::is_host_or_origin_from_preview_domain;
 
 use hot_or_not_web_leptos_ssr::app::shell;
 use hot_or_not_web_leptos_ssr::{app::App, init::AppStateBuilder};
@@ -34,11 +44,14 @@ pub async fn server_fn_handler(
             #[cfg(feature = "cloudflare")]
             provide_context(app_state.cloudflare.clone());
             provide_context(app_state.kv.clone());
-            provide_context(app_state.cookie_key.clone());
+            provide_context(app_state.secret_keyring.clone());
+            provide_context(app_state.cookie_keyring.clone());
+            provide_context(app_state.passkey_configs.clone());
 
             #[cfg(feature = "oauth-ssr")]
             {
                 provide_context(app_state.yral_oauth_client.clone());
+                provide_context(app_state.identity_providers.clone());
                 provide_context(app_state.yral_auth_migration_key.clone());
             }
 
@@ -49,6 +62,7 @@ pub async fn server_fn_handler(
             provide_context(app_state.qstash.clone());
 
             provide_context(app_state.hon_worker_jwt.clone());
+            provide_context(app_state.feed_source.clone());
         },
         request,
     )
@@ -67,9 +81,14 @@ pub async fn leptos_routes_handler(state: State<AppState>, req: Request<AxumBody
             #[cfg(feature = "cloudflare")]
             provide_context(app_state.cloudflare.clone());
             provide_context(app_state.kv.clone());
-            provide_context(app_state.cookie_key.clone());
+            provide_context(app_state.secret_keyring.clone());
+            provide_context(app_state.cookie_keyring.clone());
+            provide_context(app_state.passkey_configs.clone());
             #[cfg(feature = "oauth-ssr")]
-            provide_context(app_state.yral_oauth_client.clone());
+            {
+                provide_context(app_state.yral_oauth_client.clone());
+                provide_context(app_state.identity_providers.clone());
+            }
 
             #[cfg(feature = "ga4")]
             provide_context(app_state.grpc_offchain_channel.clone());
@@ -78,6 +97,7 @@ pub async fn leptos_routes_handler(state: State<AppState>, req: Request<AxumBody
             provide_context(app_state.qstash.clone());
 
             provide_context(app_state.hon_worker_jwt.clone());
+            provide_context(app_state.feed_source.clone());
         },
         move || shell(app_state.leptos_options.clone()),
     );
@@ -141,12 +161,31 @@ async fn main_impl() -> Result<(), Box<dyn std::error::Error>> {
         .layer(NewSentryLayer::new_from_top())
         .layer(SentryHttpLayer::with_transaction());
 
+    let rate_limit_layer = middleware::from_fn_with_state(res.app_state.clone(), rate_limit);
+
     // build our application with a route
     let app = Router::new()
         .route(
             "/api/{*fn_name}",
-            get(server_fn_handler).post(server_fn_handler),
+            get(server_fn_handler)
+                .post(server_fn_handler)
+                .layer(rate_limit_layer),
+        )
+        .route("/oembed", get(oembed))
+        .route("/embed/{canister_id}/{post_id}", get(embed_player))
+        .route(
+            "/.well-known/apple-app-site-association",
+            get(apple_app_site_association),
         )
+        .route(
+            "/apple-app-site-association",
+            get(apple_app_site_association),
+        )
+        .route("/.well-known/assetlinks.json", get(assetlinks))
+        .route("/deferred-link/claim", post(claim_deferred_link))
+        .route("/robots.txt", get(robots_txt))
+        .route("/sitemap.xml", get(sitemap_index))
+        .route("/sitemap/{kind}/{file}", get(sitemap_shard))
         .layer(
             CorsLayer::new()
                 .allow_credentials(true)
@@ -159,11 +198,7 @@ async fn main_impl() -> Result<(), Box<dyn std::error::Error>> {
                 ])
                 .allow_methods([Method::POST, Method::GET, Method::PUT, Method::OPTIONS])
                 .allow_origin(AllowOrigin::predicate(|origin, _| {
-                    if let Ok(host) = origin.to_str() {
-                        is_host_or_origin_from_preview_domain(host) || host == "legacy.yral.com"
-                    } else {
-                        false
-                    }
+                    origin.to_str().is_ok_and(is_cors_allowed_origin)
                 })),
         )
         .leptos_routes_with_handler(routes, get(leptos_routes_handler))
@@ -0,0 +1,215 @@
+//! oEmbed provider for links to posts, see <https://oembed.com>,
+//! and the player embedded by it and by Twitter cards
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{Html, IntoResponse, Response},
+    Json,
+};
+use candid::Principal;
+use component::{
+    app_banner::{linked_post, LinkedPost},
+    share_meta::{absolute_url, SharePreview, PLAYER_HEIGHT, PLAYER_WIDTH},
+};
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use state::{app_state::AppState as Tenant, app_type::AppType, server::AppState};
+
+#[derive(Deserialize)]
+pub struct OEmbedQuery {
+    url: String,
+    format: Option<String>,
+    maxwidth: Option<u32>,
+    maxheight: Option<u32>,
+}
+
+#[derive(Serialize)]
+struct VideoEmbed {
+    version: &'static str,
+    #[serde(rename = "type")]
+    kind: &'static str,
+    title: String,
+    author_name: String,
+    author_url: String,
+    provider_name: &'static str,
+    provider_url: String,
+    thumbnail_url: String,
+    html: String,
+    width: u32,
+    height: u32,
+}
+
+/// Player size within the consumer's bounds, keeping its aspect ratio
+fn player_size(maxwidth: Option<u32>, maxheight: Option<u32>) -> (u32, u32) {
+    let scale = [
+        maxwidth.map(|width| width as f64 / PLAYER_WIDTH as f64),
+        maxheight.map(|height| height as f64 / PLAYER_HEIGHT as f64),
+    ]
+    .into_iter()
+    .flatten()
+    .fold(1.0, f64::min);
+    (
+        (PLAYER_WIDTH as f64 * scale) as u32,
+        (PLAYER_HEIGHT as f64 * scale) as u32,
+    )
+}
+
+fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+pub async fn oembed(
+    State(app_state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<OEmbedQuery>,
+) -> Response {
+    // json is the only format we provide
+    if query
+        .format
+        .as_deref()
+        .is_some_and(|format| format != "json")
+    {
+        return StatusCode::NOT_IMPLEMENTED.into_response();
+    }
+    let Some(host) = headers.get(header::HOST).and_then(|h| h.to_str().ok()) else {
+        return StatusCode::BAD_REQUEST.into_response();
+    };
+    let tenant = Tenant::from_type(&AppType::from_host(host));
+    let canonical_host = tenant.app_type.canonical_host();
+    // only links to this site are embeddable
+    let Some(LinkedPost {
+        canister_id,
+        post_id,
+    }) = Url::parse(&query.url)
+        .ok()
+        .filter(|url| {
+            url.host_str().is_some_and(|url_host| {
+                [canonical_host, host.split(':').next().unwrap_or_default()].contains(&url_host)
+            })
+        })
+        .and_then(|url| linked_post(url.path()))
+    else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+
+    let preview = match embeddable_post(&app_state, tenant.name, canister_id, post_id).await {
+        Ok(preview) => preview,
+        Err(status) => return status.into_response(),
+    };
+    let Some(video) = &preview.video else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+
+    let origin = format!("https://{host}");
+    let canonical = format!("https://{canonical_host}");
+    let (width, height) = player_size(query.maxwidth, query.maxheight);
+    let player = escape_html(&absolute_url(&origin, &video.player_path));
+    let html = format!(
+        r#"<iframe src="{player}" width="{width}" height="{height}" frameborder="0" allow="autoplay; encrypted-media; fullscreen" allowfullscreen></iframe>"#
+    );
+
+    Json(VideoEmbed {
+        version: "1.0",
+        kind: "video",
+        title: preview.title,
+        author_name: preview.author,
+        author_url: absolute_url(&canonical, &preview.author_path),
+        provider_name: tenant.name,
+        provider_url: canonical,
+        thumbnail_url: absolute_url(&origin, &preview.image),
+        html,
+        width,
+        height,
+    })
+    .into_response()
+}
+
+/// Preview of a post that can be played in an embed, shared from the site `site_name`
+async fn embeddable_post(
+    app_state: &AppState,
+    site_name: &str,
+    canister_id: Principal,
+    post_id: String,
+) -> Result<SharePreview, StatusCode> {
+    let post = match app_state
+        .canisters
+        .get_post_details(canister_id, post_id)
+        .await
+    {
+        Ok(Some(post)) => post,
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            tracing::warn!("embed: failed to fetch post: {e}");
+            return Err(StatusCode::BAD_GATEWAY);
+        }
+    };
+    let preview = SharePreview::for_post(&post, site_name);
+    if preview.video.is_none() {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    Ok(preview)
+}
+
+/// Page playing only the video of a post, loaded in iframes by oEmbed consumers
+/// and Twitter player cards instead of the whole app
+pub async fn embed_player(
+    State(app_state): State<AppState>,
+    headers: HeaderMap,
+    Path((canister_id, post_id)): Path<(Principal, String)>,
+) -> Response {
+    let host = headers
+        .get(header::HOST)
+        .and_then(|h| h.to_str().ok())
+        .unwrap_or_default();
+    let tenant = Tenant::from_type(&AppType::from_host(host));
+    let preview = match embeddable_post(&app_state, tenant.name, canister_id, post_id).await {
+        Ok(preview) => preview,
+        Err(status) => return status.into_response(),
+    };
+    let Some(video) = &preview.video else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+
+    let title = escape_html(&preview.title);
+    let mp4_url = escape_html(&video.mp4_url);
+    let poster = escape_html(&preview.image);
+    let post_path = escape_html(&preview.path);
+    let site_name = escape_html(tenant.name);
+    let html = format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<meta name="robots" content="noindex">
+<title>{title}</title>
+<style>html,body{{margin:0;height:100%;background:#000}}video{{width:100%;height:100%;object-fit:contain}}a{{position:fixed;top:8px;right:8px;color:#fff;font:14px sans-serif}}</style>
+</head>
+<body>
+<video src="{mp4_url}" poster="{poster}" controls playsinline autoplay muted loop></video>
+<a href="{post_path}" target="_blank" rel="noopener">{site_name}</a>
+</body>
+</html>"#
+    );
+
+    ([(header::CACHE_CONTROL, "public, max-age=300")], Html(html)).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn player_fits_within_bounds() {
+        assert_eq!(player_size(None, None), (PLAYER_WIDTH, PLAYER_HEIGHT));
+        assert_eq!(player_size(Some(180), Some(1000)), (180, 320));
+        assert_eq!(
+            player_size(Some(1000), Some(1000)),
+            (PLAYER_WIDTH, PLAYER_HEIGHT)
+        );
+    }
+}
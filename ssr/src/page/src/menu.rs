@@ -10,6 +10,7 @@ use consts::NSFW_ENABLED_COOKIE;
 use leptos::html::{Div, Input};
 use leptos::prelude::window;
 use leptos::prelude::*;
+use leptos::text_prop::TextProp;
 use leptos_icons::*;
 use leptos_meta::*;
 use leptos_router::hooks::use_query_map;
@@ -19,11 +20,12 @@ use state::canisters::auth_state;
 use state::content_seed_client::ContentSeedClient;
 use utils::mixpanel::mixpanel_events::*;
 use utils::send_wrap;
+use utils::t;
 use yral_canisters_common::utils::profile::ProfileDetails;
 
 #[component]
 fn MenuItem(
-    #[prop(into)] text: String,
+    #[prop(into)] text: TextProp,
     #[prop(into)] href: String,
     #[prop(into)] icon: icondata::Icon,
     #[prop(into, optional)] target: String,
@@ -40,7 +42,7 @@ fn MenuItem(
         <a on:click=move |_| track_menu_clicked.clone()()  href=href class="flex items-center justify-between w-full" target=target>
             <div class="flex flex-row gap-4 items-center">
                 <Icon attr:class="text-xl" icon=icon />
-                <span class="text-base">{text}</span>
+                <span class="text-base">{move || text.get()}</span>
             </div>
             <Icon attr:class="text-xl" icon=icondata::AiRightOutlined />
         </a>
@@ -50,7 +52,7 @@ fn MenuItem(
 
 #[component]
 fn MenuItemWithToggle(
-    #[prop(into)] text: String,
+    #[prop(into)] text: TextProp,
     #[prop(into)] icon: icondata::Icon,
     checked: Signal<bool>,
     node_ref: NodeRef<Input>,
@@ -59,7 +61,7 @@ fn MenuItemWithToggle(
         <div class="flex items-center justify-between w-full">
             <div class="flex flex-row gap-4 items-center flex-1">
                 <Icon attr:class="text-xl flex-shrink-0" icon=icon />
-                <span class="text-base">{text}</span>
+                <span class="text-base">{move || text.get()}</span>
             </div>
             <div class="flex-shrink-0">
                 <Toggle checked=checked node_ref=node_ref />
@@ -69,10 +71,10 @@ fn MenuItemWithToggle(
 }
 
 #[component]
-fn SectionHeader(#[prop(into)] text: String) -> impl IntoView {
+fn SectionHeader(#[prop(into)] text: TextProp) -> impl IntoView {
     view! {
         <div class="font-semibold text-xl text-neutral-50">
-            {text}
+            {move || text.get()}
         </div>
     }
 }
@@ -81,7 +83,7 @@ fn SectionHeader(#[prop(into)] text: String) -> impl IntoView {
 fn MenuFooter() -> impl IntoView {
     view! {
         <div class="flex flex-col gap-4 items-center pt-10 pb-8 w-full">
-            <span class="text-sm text-white/50">Follow us on</span>
+            <span class="text-sm text-white/50">{t!("follow-us-on")}</span>
             <div class="flex flex-row gap-4">
                 <Telegram />
                 <Discord />
@@ -207,7 +209,9 @@ pub fn Menu() -> impl IntoView {
     );
 
     let app_state = use_context::<AppState>();
-    let page_title = app_state.unwrap().name.to_owned() + " - Menu";
+    let app_name = app_state.unwrap().name;
+    let menu = t!("menu");
+    let page_title = move || format!("{app_name} - {}", menu());
 
     let upload_content_mount_point = NodeRef::<Div>::new();
 
@@ -224,7 +228,7 @@ pub fn Menu() -> impl IntoView {
             <div class="flex flex-col gap-20 items-center pb-16 w-full">
                 <TitleText justify_center=false>
                     <div class="flex flex-row justify-center">
-                        <span class="text-2xl font-bold">Menu</span>
+                        <span class="text-2xl font-bold">{t!("menu")}</span>
                     </div>
                 </TitleText>
                 <div class="flex flex-col gap-4 items-center w-11/12 lg:w-8/12">
@@ -245,7 +249,7 @@ pub fn Menu() -> impl IntoView {
                             </div>
                         </div>
                         <div class="px-8 w-full font-sans text-sm text-center text-neutral-400">
-                            {r#"Your YRAL account is ready. Log in with Google to save your progress and continue seamlessly."#}
+                            {t!("account-ready")}
                         </div>
                     </Show>
                     <div node_ref=upload_content_mount_point />
@@ -254,10 +258,10 @@ pub fn Menu() -> impl IntoView {
             <div class="flex flex-col gap-6 py-8 px-8 w-full">
 
                 // Settings section
-                <SectionHeader text="Settings" />
+                <SectionHeader text=t!("settings") />
                 <div class="flex flex-col gap-4">
                     <MenuItemWithToggle
-                        text="Show NSFW videos"
+                        text=t!("show-nsfw-videos")
                         icon=icondata::AiEyeOutlined
                         checked=nsfw_enabled_signal
                         node_ref=nsfw_toggle_ref
@@ -266,29 +270,28 @@ pub fn Menu() -> impl IntoView {
                         show_icon=true
                         show_label=true
                         icon=icondata::BiCommentDotsRegular
-                        label_text="Enable Notifications".to_string()
                     />
                 </div>
 
                 <div class="h-px bg-white/10 w-full" />
 
                 // Legal section
-                <SectionHeader text="Legal" />
+                <SectionHeader text=t!("legal") />
                 <div class="flex flex-col gap-4">
-                    <MenuItem click_cta_type=MixpanelMenuClickedCTAType::TermsOfService href="/terms-of-service" text="Terms of service" icon=icondata::TbBook2 />
-                    <MenuItem click_cta_type=MixpanelMenuClickedCTAType::PrivacyPolicy href="/privacy-policy" text="Privacy Policy" icon=icondata::TbLock />
-                    <MenuItem click_cta_type=MixpanelMenuClickedCTAType::AboutUs href="/about-us" text="About Us" icon=icondata::TbInfoCircle />
+                    <MenuItem click_cta_type=MixpanelMenuClickedCTAType::TermsOfService href="/terms-of-service" text=t!("terms-of-service") icon=icondata::TbBook2 />
+                    <MenuItem click_cta_type=MixpanelMenuClickedCTAType::PrivacyPolicy href="/privacy-policy" text=t!("privacy-policy") icon=icondata::TbLock />
+                    <MenuItem click_cta_type=MixpanelMenuClickedCTAType::AboutUs href="/about-us" text=t!("about-us") icon=icondata::TbInfoCircle />
                 </div>
 
                 <div class="h-px bg-white/10 w-full" />
 
                 // Help section
-                <SectionHeader text="Help" />
+                <SectionHeader text=t!("help") />
                 <div class="flex flex-col gap-4">
                     <MenuItem
                         click_cta_type=MixpanelMenuClickedCTAType::TalkToTheTeam
                         href=domain_specific_href("TELEGRAM")
-                        text="Talk to the team"
+                        text=t!("talk-to-the-team")
                         icon=icondata::BiMessageDetailSolid
                         target="_blank"
                     />
@@ -299,11 +302,11 @@ pub fn Menu() -> impl IntoView {
                         >
                             <div class="flex flex-row gap-4 items-center">
                                 <Icon attr:class="text-xl" icon=icondata::RiDeleteBinSystemLine />
-                                <span class="text-base">Delete Account</span>
+                                <span class="text-base">{t!("delete-account")}</span>
                             </div>
                             <Icon attr:class="text-xl" icon=icondata::AiRightOutlined />
                         </a>
-                        <MenuItem click_cta_type=MixpanelMenuClickedCTAType::LogOut href="/logout" text="Logout" icon=icondata::FiLogOut />
+                        <MenuItem click_cta_type=MixpanelMenuClickedCTAType::LogOut href="/logout" text=t!("log-out") icon=icondata::FiLogOut />
                     </Show>
                 </div>
             </div>
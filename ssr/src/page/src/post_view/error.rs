@@ -1 +1,38 @@
+use leptos::prelude::*;
+use utils::{i18n::use_i18n, ml_feed::FeedError, t};
 
+/// Translation key of the message shown for `error`
+fn feed_error_key(error: &FeedError) -> &'static str {
+    match error {
+        FeedError::Empty => "feed-caught-up",
+        FeedError::Network(_) => "feed-offline",
+        FeedError::Upstream { .. } | FeedError::CircuitOpen => "feed-unavailable",
+        FeedError::Decode(_) | FeedError::ServerFn(_) => "feed-failed",
+    }
+}
+
+/// Banner shown over the feed when no more videos could be loaded
+#[component]
+pub fn FeedErrorOverlay(error: RwSignal<Option<FeedError>>, retry: Callback<()>) -> impl IntoView {
+    let i18n = use_i18n();
+    view! {
+        <Show when=move || error.with(|e| e.is_some())>
+            <div class="flex fixed bottom-24 left-1/2 flex-col gap-3 items-center py-3 px-5 w-11/12 max-w-md text-center rounded-lg -translate-x-1/2 z-30 bg-black/80 text-white/90">
+                <span class="text-sm">
+                    {move || {
+                        error.with(|e| e.as_ref().map(|e| i18n.tr(feed_error_key(e), &[])))
+                    }}
+                </span>
+                <button
+                    class="py-2 px-5 text-sm font-bold text-black bg-white rounded-full"
+                    on:click=move |_| {
+                        error.set(None);
+                        retry.run(());
+                    }
+                >
+                    {t!("try-again")}
+                </button>
+            </div>
+        </Show>
+    }
+}
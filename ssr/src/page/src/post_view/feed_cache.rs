@@ -0,0 +1,324 @@
+//! Server side cache of recent ML feed recommendations, stored in the KV store
+use leptos::{prelude::*, server_fn::codec::Json};
+use utils::ml_feed::{FeedError, FeedRequest, FeedSource, PostItem};
+
+/// Fetch recommendations from the configured feed source,
+/// served from the cache if the feed source is slow or failing
+#[server(endpoint = "ml_feed_cached", input = Json, output = Json)]
+pub async fn get_ml_feed_cached(req: FeedRequest) -> Result<Vec<PostItem>, FeedError> {
+    server::cached_recommendations(req).await
+}
+
+/// Feed source for use on the client,
+/// proxies every request through [`get_ml_feed_cached`]
+#[derive(Clone, Copy, Default)]
+pub struct CachedFeedSource;
+
+impl FeedSource for CachedFeedSource {
+    async fn recommendations(&self, req: FeedRequest) -> Result<Vec<PostItem>, FeedError> {
+        get_ml_feed_cached(req).await
+    }
+}
+
+#[cfg(feature = "ssr")]
+pub mod server {
+    use std::{future::Future, time::Duration};
+
+    use auth::server_impl::{
+        extract_signed_jar, principal_from_refresh_cookie,
+        store::{KVStore, KVStoreImpl},
+    };
+    use candid::Principal;
+    use leptos::prelude::use_context;
+    use utils::ml_feed::{
+        feed_source, ContentFilter, FeedError, FeedRequest, FeedSource, PostItem, RecType,
+    };
+
+    /// How long a user's last recommendations stay usable
+    const USER_FEED_TTL: Duration = Duration::from_secs(15 * 60);
+    /// How long the global popular feed stays usable
+    const GLOBAL_FEED_TTL: Duration = Duration::from_secs(30 * 60);
+    /// How long the pool of posts the root page samples from stays usable
+    const ROOT_FEED_TTL: Duration = Duration::from_secs(5 * 60);
+    /// Feed source requests taking longer than this are answered from the cache
+    const SLOW_FEED_THRESHOLD: Duration = Duration::from_secs(2);
+
+    #[derive(Clone)]
+    pub struct FeedCache {
+        kv: KVStoreImpl,
+    }
+
+    impl FeedCache {
+        pub fn new(kv: KVStoreImpl) -> Self {
+            Self { kv }
+        }
+
+        /// Uses the KV store provided as context, if any
+        pub fn from_context() -> Option<Self> {
+            use_context::<KVStoreImpl>().map(Self::new)
+        }
+
+        fn user_key(user_id: Principal, content: ContentFilter) -> String {
+            format!(
+                "ml-feed-cache:user:{}:{}",
+                user_id.to_text(),
+                content.as_str()
+            )
+        }
+
+        fn global_key(content: ContentFilter) -> String {
+            format!("ml-feed-cache:global:{}", content.as_str())
+        }
+
+        fn root_key(content: ContentFilter) -> String {
+            format!("ml-feed-cache:root:{}", content.as_str())
+        }
+
+        async fn read(&self, key: String) -> Option<Vec<PostItem>> {
+            let raw = match self.kv.read(key).await {
+                Ok(raw) => raw?,
+                Err(e) => {
+                    leptos::logging::warn!("failed to read feed cache: {e}");
+                    return None;
+                }
+            };
+            serde_json::from_str(&raw).ok()
+        }
+
+        async fn write(&self, key: String, posts: &[PostItem], ttl: Duration) {
+            let Ok(raw) = serde_json::to_string(posts) else {
+                return;
+            };
+            if let Err(e) = self.kv.write_with_ttl(key, raw, ttl).await {
+                leptos::logging::warn!("failed to write feed cache: {e}");
+            }
+        }
+
+        pub async fn user_feed(&self, req: &FeedRequest) -> Option<Vec<PostItem>> {
+            let mut posts = self.read(Self::user_key(req.user_id, req.content)).await?;
+            posts.retain(|post| !req.exclude.contains(&post.video_id));
+            (!posts.is_empty()).then_some(posts)
+        }
+
+        pub async fn store_user_feed(&self, req: &FeedRequest, posts: &[PostItem]) {
+            self.write(
+                Self::user_key(req.user_id, req.content),
+                posts,
+                USER_FEED_TTL,
+            )
+            .await;
+        }
+
+        /// Popular posts not tied to any user
+        pub async fn global_feed(&self, content: ContentFilter) -> Option<Vec<PostItem>> {
+            self.read(Self::global_key(content))
+                .await
+                .filter(|posts| !posts.is_empty())
+        }
+
+        pub async fn store_global_feed(&self, content: ContentFilter, posts: &[PostItem]) {
+            self.write(Self::global_key(content), posts, GLOBAL_FEED_TTL)
+                .await;
+        }
+
+        /// Posts the first posts of the root page are picked from
+        pub async fn root_feed_pool(&self, content: ContentFilter) -> Option<Vec<PostItem>> {
+            self.read(Self::root_key(content))
+                .await
+                .filter(|posts| !posts.is_empty())
+        }
+
+        pub async fn store_root_feed_pool(&self, content: ContentFilter, posts: &[PostItem]) {
+            self.write(Self::root_key(content), posts, ROOT_FEED_TTL)
+                .await;
+        }
+
+        /// Store a fresh response in the cache, under the user if they're
+        /// the one requesting it
+        async fn store(&self, req: &FeedRequest, posts: &[PostItem], is_owner: bool) {
+            if posts.is_empty() {
+                return;
+            }
+            if is_owner {
+                self.store_user_feed(req, posts).await;
+            }
+            if matches!(req.rec_type, RecType::Popularity) {
+                self.store_global_feed(req.content, posts).await;
+            }
+        }
+
+        /// Cached posts to serve in place of a fresh response
+        ///
+        /// a user's own recommendations are only served to that user
+        async fn fallback(&self, req: &FeedRequest, is_owner: bool) -> Option<Vec<PostItem>> {
+            if is_owner {
+                if let Some(posts) = self.user_feed(req).await {
+                    return Some(posts);
+                }
+            }
+            let mut posts = self.global_feed(req.content).await?;
+            posts.retain(|post| !req.exclude.contains(&post.video_id));
+            (!posts.is_empty()).then_some(posts)
+        }
+    }
+
+    /// Whether the request is authenticated as `user_id`
+    async fn requested_by(user_id: Principal) -> bool {
+        extract_signed_jar()
+            .await
+            .ok()
+            .and_then(|jar| principal_from_refresh_cookie(&jar))
+            == Some(user_id)
+    }
+
+    pub async fn cached_recommendations(req: FeedRequest) -> Result<Vec<PostItem>, FeedError> {
+        let source = feed_source();
+        let Some(cache) = FeedCache::from_context() else {
+            return source.recommendations(req).await;
+        };
+        let is_owner = requested_by(req.user_id).await;
+
+        // keeps running in the background if we answer from the cache,
+        // so the cache is still refreshed with the eventual response
+        let mut fetch = tokio::spawn({
+            let cache = cache.clone();
+            let req = req.clone();
+            async move {
+                let res = source.recommendations(req.clone()).await;
+                if let Ok(posts) = &res {
+                    cache.store(&req, posts, is_owner).await;
+                }
+                res
+            }
+        });
+
+        let res = match tokio::time::timeout(SLOW_FEED_THRESHOLD, &mut fetch).await {
+            Ok(joined) => joined.map_err(|e| FeedError::ServerFn(e.to_string()))?,
+            Err(_) => {
+                if let Some(posts) = cache.fallback(&req, is_owner).await {
+                    leptos::logging::warn!("feed source is slow, serving cached feed");
+                    return Ok(posts);
+                }
+                fetch
+                    .await
+                    .map_err(|e| FeedError::ServerFn(e.to_string()))?
+            }
+        };
+
+        match res {
+            Ok(posts) if !posts.is_empty() => Ok(posts),
+            res => match cache.fallback(&req, is_owner).await {
+                Some(posts) => Ok(posts),
+                None => res,
+            },
+        }
+    }
+
+    /// Posts for the first paint of the root page
+    ///
+    /// sampled from a short lived pool, so that visitors don't all start on
+    /// the same posts. A missing pool is fetched with `fetch_pool`, the
+    /// global feed is sampled instead while that is slow or failing
+    pub async fn root_feed_posts(
+        content: ContentFilter,
+        count: usize,
+        seed: u64,
+        fetch_pool: impl Future<Output = Result<Vec<PostItem>, FeedError>> + Send + 'static,
+    ) -> Result<Vec<PostItem>, FeedError> {
+        let Some(cache) = FeedCache::from_context() else {
+            return Ok(sample_posts(fetch_pool.await?, count, seed));
+        };
+        if let Some(pool) = cache.root_feed_pool(content).await {
+            return Ok(sample_posts(pool, count, seed));
+        }
+
+        // keeps running in the background if we answer from the global feed,
+        // so the pool is still refreshed with the eventual response
+        let mut fetch = tokio::spawn({
+            let cache = cache.clone();
+            async move {
+                let res = fetch_pool.await;
+                match &res {
+                    Ok(posts) if !posts.is_empty() => {
+                        cache.store_root_feed_pool(content, posts).await;
+                        cache.store_global_feed(content, posts).await;
+                    }
+                    _ => {}
+                }
+                res
+            }
+        });
+
+        let res = match tokio::time::timeout(SLOW_FEED_THRESHOLD, &mut fetch).await {
+            Ok(joined) => joined.map_err(|e| FeedError::ServerFn(e.to_string()))?,
+            Err(_) => {
+                if let Some(posts) = cache.global_feed(content).await {
+                    leptos::logging::warn!("feed source is slow, serving the global feed");
+                    return Ok(sample_posts(posts, count, seed));
+                }
+                fetch
+                    .await
+                    .map_err(|e| FeedError::ServerFn(e.to_string()))?
+            }
+        };
+
+        match res {
+            Ok(posts) if !posts.is_empty() => Ok(sample_posts(posts, count, seed)),
+            res => match cache.global_feed(content).await {
+                Some(posts) => Ok(sample_posts(posts, count, seed)),
+                None => res,
+            },
+        }
+    }
+
+    /// Picks `count` posts of `pool` at random, so that visitors served from
+    /// the same pool don't all start with the same posts
+    pub fn sample_posts(mut pool: Vec<PostItem>, count: usize, seed: u64) -> Vec<PostItem> {
+        use rand::{rngs::SmallRng, RngCore, SeedableRng};
+
+        let mut rng = SmallRng::seed_from_u64(seed);
+        let count = count.min(pool.len());
+        // partial fisher-yates, the first `count` posts are the sample
+        for i in 0..count {
+            let j = i + (rng.next_u64() % (pool.len() - i) as u64) as usize;
+            pool.swap(i, j);
+        }
+        pool.truncate(count);
+        pool
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use candid::Principal;
+
+        use super::*;
+
+        fn pool(len: usize) -> Vec<PostItem> {
+            (0..len)
+                .map(|i| PostItem {
+                    video_id: i.to_string(),
+                    canister_id: Principal::anonymous(),
+                    post_id: i.to_string(),
+                    publisher_user_id: Principal::anonymous(),
+                    views: 0,
+                    nsfw_probability: Some(0.0),
+                })
+                .collect()
+        }
+
+        #[test]
+        fn samples_are_distinct_and_vary_per_seed() {
+            let ids = |posts: Vec<PostItem>| -> Vec<String> {
+                posts.into_iter().map(|post| post.video_id).collect()
+            };
+            let a = ids(sample_posts(pool(60), 15, 1));
+            let b = ids(sample_posts(pool(60), 15, 2));
+
+            assert_eq!(a.len(), 15);
+            let unique: std::collections::HashSet<_> = a.iter().collect();
+            assert_eq!(unique.len(), 15);
+            assert_ne!(a, b);
+            assert_eq!(sample_posts(pool(3), 15, 1).len(), 3);
+        }
+    }
+}
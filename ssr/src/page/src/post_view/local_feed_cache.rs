@@ -0,0 +1,100 @@
+//! Recent recommendations kept in local storage, served when the feed
+//! server can't be reached at all
+#[cfg(feature = "hydrate")]
+use consts::LOCAL_FEED_CACHE_STORE;
+use indexmap::IndexSet;
+use utils::ml_feed::ContentFilter;
+
+use super::MlPostItem;
+
+/// Max number of posts remembered per content filter
+const LOCAL_FEED_CACHE_CAPACITY: usize = 100;
+
+#[cfg(feature = "hydrate")]
+fn storage_key(content: ContentFilter) -> String {
+    format!("{LOCAL_FEED_CACHE_STORE}-{}", content.as_str())
+}
+
+/// Cached posts for `content`, newest first
+/// always empty on the server
+fn load(content: ContentFilter) -> Vec<MlPostItem> {
+    #[cfg(feature = "hydrate")]
+    {
+        leptos::prelude::window()
+            .local_storage()
+            .ok()
+            .flatten()
+            .and_then(|store| store.get_item(&storage_key(content)).ok().flatten())
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default()
+    }
+    #[cfg(not(feature = "hydrate"))]
+    {
+        _ = content;
+        vec![]
+    }
+}
+
+/// Newest posts first, without duplicates, capped to the capacity
+fn merge(fresh: &[MlPostItem], cached: Vec<MlPostItem>) -> Vec<MlPostItem> {
+    let mut posts: IndexSet<MlPostItem> = fresh.iter().cloned().collect();
+    posts.extend(cached);
+    posts.into_iter().take(LOCAL_FEED_CACHE_CAPACITY).collect()
+}
+
+/// Remember a successful response
+pub fn store(content: ContentFilter, fresh: &[MlPostItem]) {
+    if fresh.is_empty() {
+        return;
+    }
+    let posts = merge(fresh, load(content));
+    #[cfg(feature = "hydrate")]
+    {
+        let Ok(Some(store)) = leptos::prelude::window().local_storage() else {
+            return;
+        };
+        let Ok(raw) = serde_json::to_string(&posts) else {
+            return;
+        };
+        if let Err(e) = store.set_item(&storage_key(content), &raw) {
+            leptos::logging::warn!("failed to persist local feed cache: {e:?}");
+        }
+    }
+    #[cfg(not(feature = "hydrate"))]
+    _ = posts;
+}
+
+/// Cached posts that aren't in `exclude`
+pub fn fallback(content: ContentFilter, exclude: &[String]) -> Vec<MlPostItem> {
+    let mut posts = load(content);
+    posts.retain(|post| !exclude.contains(&post.video_uid));
+    posts
+}
+
+#[cfg(test)]
+mod tests {
+    use candid::Principal;
+
+    use super::*;
+
+    fn post(id: usize) -> MlPostItem {
+        MlPostItem {
+            canister_id: Principal::anonymous(),
+            post_id: id.to_string(),
+            video_uid: format!("uid-{id}"),
+            publisher_user_id: Principal::anonymous(),
+            nsfw_probability: Some(0.0),
+        }
+    }
+
+    #[test]
+    fn fresh_posts_come_first_and_the_cache_is_capped() {
+        let cached = (0..LOCAL_FEED_CACHE_CAPACITY).map(post).collect();
+        let fresh = [post(1000), post(0)];
+        let merged = merge(&fresh, cached);
+
+        assert_eq!(merged.len(), LOCAL_FEED_CACHE_CAPACITY);
+        assert_eq!(merged[..3], [post(1000), post(0), post(1)]);
+        assert!(!merged.contains(&post(LOCAL_FEED_CACHE_CAPACITY - 1)));
+    }
+}
@@ -1,30 +1,40 @@
 pub mod error;
+pub mod feed_cache;
+mod local_feed_cache;
 pub mod overlay;
 pub mod single_post;
 pub mod video_iter;
 pub mod video_loader;
 use crate::scrolling_post_view::{PostDetailResolver, ScrollingPostView};
+use component::share_meta::PostSharePreview;
 use component::spinner::FullScreenSpinner;
-use consts::{MAX_VIDEO_ELEMENTS_FOR_FEED, NSFW_ENABLED_COOKIE};
+use consts::{
+    FEED_HISTORY_DEPTH, MAX_PENDING_FEED_POSTS, MAX_VIDEO_ELEMENTS_FOR_FEED, NSFW_ENABLED_COOKIE,
+};
+use error::FeedErrorOverlay;
 use global_constants::{DEFAULT_BET_COIN_FOR_LOGGED_IN, DEFAULT_BET_COIN_FOR_LOGGED_OUT};
 use indexmap::IndexSet;
 use priority_queue::DoublePriorityQueue;
 use serde::{Deserialize, Serialize};
 use state::canisters::{auth_state, unauth_canisters};
-use std::{cmp::Reverse, collections::HashMap};
-use utils::ml_feed::{PostItem, QuickPostDetails};
+use std::{
+    cmp::Reverse,
+    collections::{HashMap, HashSet},
+};
+use utils::ml_feed::{FeedError, PostItem, QuickPostDetails};
 
 use candid::Principal;
 use codee::string::FromToStringCodec;
 use futures::StreamExt;
-use leptos::prelude::*;
+use leptos::{ev, prelude::*};
 use leptos_router::{hooks::use_params, params::Params};
-use leptos_use::{use_cookie_with_options, UseCookieOptions};
+use leptos_use::{use_cookie_with_options, use_event_listener, use_window, UseCookieOptions};
 use utils::{
     mixpanel::mixpanel_events::*,
     posts::{FeedPostCtx, FetchCursor},
     route::failure_redirect,
-    send_wrap, try_or_redirect,
+    seen_posts::{SeenPosts, SEEN_POSTS_EXCLUDE_LIMIT},
+    send_wrap,
     types::PostId,
 };
 
@@ -40,16 +50,27 @@ struct PostParams {
 #[derive(Clone, Default)]
 pub struct PostViewCtx {
     fetch_cursor: RwSignal<FetchCursor>,
-    // TODO: this is a dead simple with no GC
-    // We're using virtual lists for DOM, so this doesn't consume much memory
-    // as uids only occupy 32 bytes each
-    // but ideally this should be cleaned up
+    /// Bounded window of the feed, posts more than `history_depth` behind
+    /// `current_idx` are evicted by [`PostViewCtx::collect_garbage`]
     pub video_queue: RwSignal<IndexSet<MlPostItem>>,
+    /// number of posts evicted from the front of `video_queue`
+    /// feed index `i` lives at `video_queue[i - queue_offset]`
+    queue_offset: RwSignal<usize>,
+    /// posts evicted from `video_queue`, so that they never re-enter the feed
+    evicted: StoredValue<HashSet<PostId>>,
+    history_depth: usize,
+    /// DOM slots of the posts in `video_queue`, slot `i` holds feed index
+    /// `i + queue_offset`. Evicted slots are recycled at the end
     video_queue_for_feed: RwSignal<Vec<FeedPostCtx<MlPostItem>>>,
     current_idx: RwSignal<usize>,
     queue_end: RwSignal<bool>,
-    priority_q: RwSignal<DoublePriorityQueue<MlPostItem, (usize, Reverse<usize>)>>, // we are using DoublePriorityQueue for GC in the future through pop_min
+    // DoublePriorityQueue so that stale recommendations can be dropped through pop_min
+    priority_q: RwSignal<DoublePriorityQueue<MlPostItem, (usize, Reverse<usize>)>>,
     batch_cnt: RwSignal<usize>,
+    /// why the last fetch didn't yield any posts, if it failed
+    feed_error: RwSignal<Option<FeedError>>,
+    /// videos already shown to the current user, persisted across reloads
+    seen_posts: StoredValue<SeenPosts>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -58,7 +79,8 @@ pub struct MlPostItem {
     post_id: String,
     video_uid: String,
     publisher_user_id: Principal,
-    nsfw_probability: f32,
+    /// `None` if the post hasn't been scored yet
+    nsfw_probability: Option<f32>,
 }
 
 // manually implementing PartialEq using the canister_id and post_id as it is
@@ -85,7 +107,7 @@ impl From<PostItem> for MlPostItem {
             canister_id: value.canister_id,
             post_id: value.post_id,
             video_uid: value.video_id,
-            nsfw_probability: 0.0, // New API doesn't provide nsfw_probability
+            nsfw_probability: value.nsfw_probability,
             publisher_user_id: value.publisher_user_id,
         }
     }
@@ -107,7 +129,7 @@ impl PostDetailResolver for MlPostItem {
         let post_details = send_wrap(canisters.get_post_details_with_nsfw_info(
             self.canister_id,
             self.post_id.clone(),
-            Some(self.nsfw_probability),
+            self.nsfw_probability,
         ))
         .await?;
         let post_details = post_details.ok_or_else(|| {
@@ -123,18 +145,159 @@ impl PostDetailResolver for MlPostItem {
 
 impl PostViewCtx {
     pub fn new() -> Self {
-        let mut video_queue_for_feed = Vec::new();
-        for i in 0..MAX_VIDEO_ELEMENTS_FOR_FEED {
-            video_queue_for_feed.push(FeedPostCtx {
-                key: i,
+        Self {
+            video_queue_for_feed: RwSignal::new(Self::feed_slots(0..MAX_VIDEO_ELEMENTS_FOR_FEED)),
+            history_depth: FEED_HISTORY_DEPTH,
+            ..Default::default()
+        }
+    }
+
+    /// Number of posts to keep behind the current post for back-scroll
+    pub fn with_history_depth(mut self, history_depth: usize) -> Self {
+        self.history_depth = history_depth;
+        self
+    }
+
+    fn feed_slots(indices: std::ops::Range<usize>) -> Vec<FeedPostCtx<MlPostItem>> {
+        indices
+            .map(|key| FeedPostCtx {
+                key,
                 value: RwSignal::new(None),
-            });
+            })
+            .collect()
+    }
+
+    /// Empties the feed, e.g to start over from server rendered posts
+    fn reset(&self, posts: Vec<MlPostItem>) {
+        self.queue_offset.set(0);
+        self.evicted.set_value(HashSet::new());
+        let slots = Self::feed_slots(0..MAX_VIDEO_ELEMENTS_FOR_FEED);
+        for (slot, post) in slots.iter().zip(&posts) {
+            slot.value.set(Some(post.clone()));
         }
+        self.video_queue
+            .update_untracked(|q| *q = posts.into_iter().collect());
+        self.video_queue_for_feed.set(slots);
+    }
 
-        Self {
-            video_queue_for_feed: RwSignal::new(video_queue_for_feed),
-            ..Default::default()
+    /// Post at feed index `idx`, `None` if it was evicted or not fetched yet
+    pub fn post_at(&self, idx: usize) -> Option<MlPostItem> {
+        let offset = self.queue_offset.get_untracked();
+        self.video_queue
+            .with(|q| q.get_index(idx.checked_sub(offset)?).cloned())
+    }
+
+    /// Total number of posts added to the feed, including evicted ones
+    pub fn feed_len(&self) -> usize {
+        self.queue_offset.get_untracked() + self.video_queue.with_untracked(|q| q.len())
+    }
+
+    /// Appends `post` to the feed and its DOM slot
+    ///
+    /// returns false if the post is already part of the feed (or was evicted
+    /// from it), or there are no free slots until older posts are evicted
+    pub fn push_post(&self, post: MlPostItem) -> bool {
+        let id = (post.canister_id, post.post_id.clone());
+        if self.evicted.with_value(|evicted| evicted.contains(&id))
+            || self.video_queue.with_untracked(|q| q.contains(&post))
+        {
+            return false;
         }
+        let slot_idx = self.video_queue.with_untracked(|q| q.len());
+        let Some(slot) = self
+            .video_queue_for_feed
+            .with_untracked(|vqf| vqf.get(slot_idx).map(|slot| slot.value))
+        else {
+            return false;
+        };
+        self.video_queue.update(|q| {
+            q.insert(post.clone());
+        });
+        slot.set(Some(post));
+        true
+    }
+
+    /// Evicts posts far behind `current_idx` along with their cached details
+    /// and trims the pending recommendations
+    ///
+    /// Feed indices are left untouched, the slots of evicted posts are moved
+    /// to the end of the feed for the posts to come
+    pub fn collect_garbage(&self, post_details: &PostDetailsCacheCtx) {
+        let keep_from = self
+            .current_idx
+            .get_untracked()
+            .saturating_sub(self.history_depth);
+        let offset = self.queue_offset.get_untracked();
+        if keep_from > offset {
+            let evicted = self
+                .video_queue
+                .try_update_untracked(|q| {
+                    let cnt = (keep_from - offset).min(q.len());
+                    q.drain(..cnt).collect::<Vec<_>>()
+                })
+                .unwrap_or_default();
+            self.video_queue_for_feed.update(|vqf| {
+                let next_key = vqf.last().map_or(0, |slot| slot.key + 1);
+                vqf.drain(..evicted.len().min(vqf.len()));
+                vqf.extend(Self::feed_slots(next_key..next_key + evicted.len()));
+            });
+            self.queue_offset.update_untracked(|o| *o += evicted.len());
+            post_details.post_details.update_value(|details| {
+                self.evicted.update_value(|evicted_ids| {
+                    for post in evicted {
+                        let id = (post.canister_id, post.post_id);
+                        details.remove(&id);
+                        evicted_ids.insert(id);
+                    }
+                });
+            });
+        }
+
+        self.priority_q.update_untracked(|pq| {
+            while pq.len() > MAX_PENDING_FEED_POSTS {
+                pq.pop_min();
+            }
+        });
+    }
+
+    /// Whether `post` was already shown to `user` in this or a previous session
+    pub fn is_seen(&self, user: Option<Principal>, post: &MlPostItem) -> bool {
+        let Some(user) = user else {
+            return false;
+        };
+        self.seen_posts
+            .try_update_value(|seen| {
+                seen.ensure_owner(user);
+                seen.contains(&post.video_uid)
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn mark_seen(&self, user: Principal, post: &MlPostItem) {
+        self.seen_posts.update_value(|seen| {
+            seen.ensure_owner(user);
+            seen.insert(post.video_uid.clone());
+            if seen.persist_due() {
+                seen.persist();
+            }
+        });
+    }
+
+    /// Writes the seen videos that weren't persisted yet
+    pub fn flush_seen(&self) {
+        self.seen_posts.try_update_value(|seen| seen.persist());
+    }
+
+    pub fn recently_seen(&self, user: Option<Principal>) -> Vec<String> {
+        let Some(user) = user else {
+            return vec![];
+        };
+        self.seen_posts
+            .try_update_value(|seen| {
+                seen.ensure_owner(user);
+                seen.recent(SEEN_POSTS_EXCLUDE_LIMIT)
+            })
+            .unwrap_or_default()
     }
 }
 
@@ -149,14 +312,19 @@ pub fn CommonPostViewWithUpdates(
     fetch_video_action: Action<(), ()>,
     threshold_trigger_fetch: usize,
 ) -> impl IntoView {
+    let ctx: PostViewCtx = expect_context();
     let PostViewCtx {
         fetch_cursor,
         video_queue,
         current_idx,
         queue_end,
         video_queue_for_feed,
+        queue_offset,
+        feed_error,
         ..
-    } = expect_context();
+    } = ctx.clone();
+    let auth = auth_state();
+    let post_details_cache: PostDetailsCacheCtx = expect_context();
 
     let recovering_state = RwSignal::new(false);
     fetch_cursor.update_untracked(|f| {
@@ -169,19 +337,8 @@ pub fn CommonPostViewWithUpdates(
         // ngl, not sure what this does
         f.start = 1;
     });
-    if !initial_posts.is_empty() {
-        video_queue.update_untracked(|v| {
-            if v.len() > 1 {
-                return;
-            }
-            *v = IndexSet::new();
-            v.extend(initial_posts.clone());
-            video_queue_for_feed.update(|vq| {
-                for (idx, post) in initial_posts.into_iter().enumerate() {
-                    vq[idx].value.set(Some(post));
-                }
-            });
-        })
+    if !initial_posts.is_empty() && video_queue.with_untracked(|v| v.len() <= 1) {
+        ctx.reset(initial_posts);
     }
 
     let current_post_params: RwSignal<Option<utils::types::PostParams>> = expect_context();
@@ -198,18 +355,35 @@ pub fn CommonPostViewWithUpdates(
         }
     };
 
+    let ctx_for_post = ctx.clone();
     let current_post_base = Memo::new(move |_| {
-        video_queue.with(|q| {
-            let cur_idx = current_idx();
-            let details = q.get_index(cur_idx)?;
-            Some((details.canister_id, details.post_id.clone()))
-        })
+        let details = ctx_for_post.post_at(current_idx())?;
+        Some((details.canister_id, details.post_id))
     });
 
+    let ctx_for_gc = ctx.clone();
+    Effect::new(move || {
+        current_idx.track();
+        ctx_for_gc.collect_garbage(&post_details_cache);
+    });
+
+    // seen videos are persisted in batches, flush the rest when leaving
+    let ctx_for_flush = ctx.clone();
+    _ = use_event_listener(use_window(), ev::pagehide, move |_| {
+        ctx_for_flush.flush_seen()
+    });
+    let ctx_for_flush = ctx.clone();
+    on_cleanup(move || ctx_for_flush.flush_seen());
+
     Effect::new(move || {
         let Some((canister_id, post_id)) = current_post_base() else {
             return;
         };
+        let current_post = untrack(|| ctx.post_at(current_idx.get_untracked()));
+        if let (Some(user), Some(post)) = (auth.user_principal_if_available(), current_post) {
+            ctx.mark_seen(user, &post);
+        }
+
         current_post_params.set(Some(utils::types::PostParams {
             canister_id,
             post_id: post_id.clone(),
@@ -224,14 +398,17 @@ pub fn CommonPostViewWithUpdates(
     });
 
     let hard_refresh_target = RwSignal::new("/".to_string());
+    let retry = Callback::new(move |_| next_videos());
 
     view! {
         <ScrollingPostView
             video_queue
             video_queue_for_feed
             current_idx
+            queue_offset
             recovering_state
             fetch_next_videos=next_videos
+            overlay=move || view! { <FeedErrorOverlay error=feed_error retry /> }
             queue_end
             threshold_trigger_fetch
             _hard_refresh_target=hard_refresh_target
@@ -242,16 +419,16 @@ pub fn CommonPostViewWithUpdates(
 
 #[component]
 pub fn PostViewWithUpdatesMLFeed(initial_posts: Vec<MlPostItem>) -> impl IntoView {
+    let ctx: PostViewCtx = expect_context();
     let PostViewCtx {
         fetch_cursor,
-        video_queue,
         queue_end,
         priority_q,
         batch_cnt,
         current_idx,
-        video_queue_for_feed,
+        feed_error,
         ..
-    } = expect_context();
+    } = ctx.clone();
 
     let auth = auth_state();
 
@@ -264,6 +441,8 @@ pub fn PostViewWithUpdatesMLFeed(initial_posts: Vec<MlPostItem>) -> impl IntoVie
     ));
 
     let fetch_video_action = Action::new(move |_| {
+        #[cfg(feature = "hydrate")]
+        let ctx = ctx.clone();
         let (nsfw_enabled, _) = use_cookie_with_options::<bool, FromToStringCodec>(
             NSFW_ENABLED_COOKIE,
             UseCookieOptions::default()
@@ -282,19 +461,9 @@ pub fn PostViewWithUpdatesMLFeed(initial_posts: Vec<MlPostItem>) -> impl IntoVie
                 let mut prio_q = priority_q.write();
                 let mut cnt = 0;
                 while let Some((next, _)) = prio_q.pop_max() {
-                    video_queue.update(|vq| {
-                        if vq.insert(next.clone()) {
-                            let len_vq = vq.len();
-                            if len_vq >= video_queue_for_feed.with_untracked(|vqf| vqf.len()) {
-                                return;
-                            }
-
-                            video_queue_for_feed.update(|vqf| {
-                                vqf[len_vq - 1].value.set(Some(next.clone()));
-                            });
-                            cnt += 1;
-                        }
-                    });
+                    if ctx.push_post(next) {
+                        cnt += 1;
+                    }
                     if cnt >= 10 {
                         break;
                     }
@@ -317,42 +486,50 @@ pub fn PostViewWithUpdatesMLFeed(initial_posts: Vec<MlPostItem>) -> impl IntoVie
                 let cans_false: Canisters<false> = Default::default();
                 let cans_true = auth.auth_cans_if_available();
 
-                let video_queue_len = video_queue.get_untracked().len();
+                let user = auth.user_principal_if_available();
+                let exclude = ctx.recently_seen(user);
+                let video_queue_len = ctx.feed_len();
                 let chunks = if let Some(cans_true) = cans_true.as_ref() {
                     let mut fetch_stream = new_video_fetch_stream_auth(cans_true, auth, cursor);
                     fetch_stream
-                        .fetch_post_uids_hybrid(nsfw_enabled.unwrap_or(false), video_queue_len)
+                        .fetch_post_uids_hybrid(
+                            nsfw_enabled.unwrap_or(false),
+                            video_queue_len,
+                            exclude,
+                        )
                         .await
                 } else {
                     let mut fetch_stream = new_video_fetch_stream(&cans_false, auth, cursor);
                     fetch_stream
-                        .fetch_post_uids_hybrid(nsfw_enabled.unwrap_or(false), video_queue_len)
+                        .fetch_post_uids_hybrid(
+                            nsfw_enabled.unwrap_or(false),
+                            video_queue_len,
+                            exclude,
+                        )
                         .await
                 };
 
-                let res = try_or_redirect!(chunks);
+                let res = match chunks {
+                    Ok(res) => {
+                        feed_error.set(None);
+                        res
+                    }
+                    Err(e) => {
+                        leptos::logging::warn!("failed to fetch feed: {e}");
+                        feed_error.set(Some(e));
+                        return;
+                    }
+                };
                 let mut chunks = res.posts_stream;
                 let mut cnt = 0usize;
                 while let Some(chunk) = chunks.next().await {
                     leptos::logging::log!("recv a chunk");
                     for post_detail in chunk {
-                        if video_queue
-                            .with_untracked(|vq| vq.len())
-                            .saturating_sub(current_idx.get_untracked())
-                            <= 10
-                        {
-                            video_queue.update(|vq| {
-                                if vq.insert(post_detail.clone()) {
-                                    let len_vq = vq.len();
-                                    if len_vq > video_queue_for_feed.with_untracked(|vqf| vqf.len())
-                                    {
-                                        return;
-                                    }
-                                    video_queue_for_feed.update(|vqf| {
-                                        vqf[len_vq - 1].value.set(Some(post_detail.clone()));
-                                    });
-                                }
-                            });
+                        if ctx.is_seen(user, &post_detail) {
+                            continue;
+                        }
+                        if ctx.feed_len().saturating_sub(current_idx.get_untracked()) <= 10 {
+                            ctx.push_post(post_detail);
                         } else {
                             priority_q.update(|pq| {
                                 pq.push(post_detail, (batch_cnt_val, Reverse(cnt)));
@@ -420,6 +597,7 @@ pub fn PostView() -> impl IntoView {
     let PostViewCtx {
         video_queue,
         current_idx,
+        queue_offset,
         ..
     } = expect_context();
 
@@ -434,19 +612,27 @@ pub fn PostView() -> impl IntoView {
     let canisters = unauth_canisters();
     let post_details_cache: PostDetailsCacheCtx = expect_context();
 
-    let fetch_first_video_uid = Resource::new(initial_canister_and_post, move |params| {
+    // blocking, the share preview of the post is rendered from its details.
+    // posts already in the feed come without details, they're only reached
+    // by navigating within the app, where previews don't matter
+    let fetch_first_video_uid = Resource::new_blocking(initial_canister_and_post, move |params| {
         let canisters = canisters.clone();
         async move {
             let Some(params) = params else {
                 return Err(());
             };
             let cached_post = video_queue
-                .with_untracked(|q| q.get_index(current_idx.get_untracked()).cloned())
+                .with_untracked(|q| {
+                    let idx = current_idx
+                        .get_untracked()
+                        .checked_sub(queue_offset.get_untracked())?;
+                    q.get_index(idx).cloned()
+                })
                 .filter(|post| {
                     post.canister_id == params.canister_id && post.post_id == params.post_id
                 });
             if let Some(post) = cached_post {
-                return Ok(Some(post));
+                return Ok(Some((post, None)));
             }
 
             let post_nsfw_prob = post_details_cache
@@ -464,12 +650,15 @@ pub fn PostView() -> impl IntoView {
             ))
             .await
             {
-                Ok(post) => Ok(post.map(|post| MlPostItem {
-                    canister_id: post.canister_id,
-                    post_id: post.post_id,
-                    video_uid: post.uid,
-                    nsfw_probability: post.nsfw_probability,
-                    publisher_user_id: post.poster_principal,
+                Ok(post) => Ok(post.map(|post| {
+                    let item = MlPostItem {
+                        canister_id: post.canister_id,
+                        post_id: post.post_id.clone(),
+                        video_uid: post.uid.clone(),
+                        nsfw_probability: Some(post.nsfw_probability),
+                        publisher_user_id: post.poster_principal,
+                    };
+                    (item, Some(post))
                 })),
                 Err(e) => {
                     failure_redirect(e);
@@ -483,13 +672,96 @@ pub fn PostView() -> impl IntoView {
         <Suspense fallback=FullScreenSpinner>
             {move || Suspend::new(async move {
                 let initial_post = fetch_first_video_uid.await.ok()?;
-                let initial_posts = match initial_post {
-                    Some(post) => vec![post],
-                    None => vec![],
+                let (initial_posts, share_preview) = match initial_post {
+                    Some((post, details)) => {
+                        (vec![post], details.map(|post| view! { <PostSharePreview post /> }))
+                    }
+                    None => (vec![], None),
                 };
-                { Some(view! { <PostViewWithUpdatesMLFeed initial_posts /> }.into_any()) }
+                Some(
+                    view! {
+                        {share_preview}
+                        <PostViewWithUpdatesMLFeed initial_posts />
+                    }
+                        .into_any(),
+                )
             })}
         </Suspense>
     }
     .into_any()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn post(id: usize) -> MlPostItem {
+        MlPostItem {
+            canister_id: Principal::anonymous(),
+            post_id: id.to_string(),
+            video_uid: format!("uid-{id}"),
+            publisher_user_id: Principal::anonymous(),
+            nsfw_probability: Some(0.0),
+        }
+    }
+
+    #[test]
+    fn garbage_collection_clears_slots_and_blocks_reentry() {
+        let ctx = PostViewCtx::new().with_history_depth(2);
+        let details = PostDetailsCacheCtx::default();
+        for i in 0..10 {
+            assert!(ctx.push_post(post(i)));
+        }
+        assert!(!ctx.push_post(post(3)));
+
+        ctx.current_idx.set(6);
+        ctx.collect_garbage(&details);
+
+        // feed indices are stable, evicted slots move to the end
+        assert_eq!(ctx.feed_len(), 10);
+        assert_eq!(ctx.post_at(3), None);
+        assert_eq!(ctx.post_at(4), Some(post(4)));
+        let (first, last, len) = ctx.video_queue_for_feed.with_untracked(|vqf| {
+            let first = &vqf[0];
+            assert_eq!(first.value.get_untracked(), Some(post(4)));
+            (first.key, vqf.last().unwrap().key, vqf.len())
+        });
+        assert_eq!(
+            (first, last, len),
+            (
+                4,
+                MAX_VIDEO_ELEMENTS_FOR_FEED + 3,
+                MAX_VIDEO_ELEMENTS_FOR_FEED
+            )
+        );
+
+        // evicted posts never come back
+        assert!(!ctx.push_post(post(0)));
+        assert!(ctx.push_post(post(10)));
+        assert_eq!(ctx.post_at(10), Some(post(10)));
+    }
+
+    #[test]
+    fn evicted_slots_are_reused() {
+        let ctx = PostViewCtx::new().with_history_depth(2);
+        let details = PostDetailsCacheCtx::default();
+        for i in 0..MAX_VIDEO_ELEMENTS_FOR_FEED {
+            assert!(ctx.push_post(post(i)));
+        }
+        assert!(!ctx.push_post(post(MAX_VIDEO_ELEMENTS_FOR_FEED)));
+
+        // a long session keeps going past the slot count
+        let session = 3 * MAX_VIDEO_ELEMENTS_FOR_FEED;
+        for i in MAX_VIDEO_ELEMENTS_FOR_FEED..session {
+            ctx.current_idx.set(i - 1);
+            ctx.collect_garbage(&details);
+            assert!(ctx.push_post(post(i)));
+        }
+        assert_eq!(ctx.feed_len(), session);
+        assert_eq!(ctx.post_at(session - 1), Some(post(session - 1)));
+        assert_eq!(
+            ctx.video_queue_for_feed.with_untracked(|vqf| vqf.len()),
+            MAX_VIDEO_ELEMENTS_FOR_FEED
+        );
+    }
+}
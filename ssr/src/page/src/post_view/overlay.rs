@@ -12,9 +12,9 @@ use leptos_icons::*;
 use leptos_router::hooks::use_location;
 use leptos_use::use_window;
 use leptos_use::{use_cookie_with_options, UseCookieOptions};
+use state::app_state::AppState;
 use state::audio_state::AudioState;
 use state::canisters::auth_state;
-use utils::host::show_nsfw_content;
 
 use utils::mixpanel::mixpanel_events::*;
 use yral_canisters_common::utils::posts::PostDetails;
@@ -82,7 +82,7 @@ pub fn VideoDetailsOverlay(
         let video_id = post_clone.uid.clone();
         let post_clone = post_clone.clone();
         async move {
-            if show_nsfw_content() {
+            if AppState::current().show_nsfw_content() {
                 return;
             }
 
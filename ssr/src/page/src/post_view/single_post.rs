@@ -4,13 +4,15 @@ use serde::{Deserialize, Serialize};
 
 use super::{overlay::VideoDetailsOverlay, video_loader::VideoView};
 use crate::scrolling_post_view::MuteUnmuteOverlay;
-use component::{back_btn::go_back_or_fallback, spinner::FullScreenSpinner};
+use component::{
+    back_btn::go_back_or_fallback, share_meta::PostSharePreview, spinner::FullScreenSpinner,
+};
 use leptos_router::{components::Redirect, hooks::use_params, params::Params};
 use state::{
     audio_state::AudioState,
     canisters::{auth_state, unauth_canisters},
 };
-use utils::{bg_url, send_wrap};
+use utils::{bg_url, send_wrap, t};
 use yral_canisters_common::utils::posts::PostDetails;
 #[derive(Params, PartialEq, Clone)]
 struct PostParams {
@@ -52,12 +54,12 @@ fn SinglePostViewInner(post: PostDetails) -> impl IntoView {
 fn UnavailablePost() -> impl IntoView {
     view! {
         <div class="flex flex-col gap-2 justify-center items-center bg-black h-dvh w-dvw">
-            <span class="text-lg text-white md:text-xl lg:text-2xl">Post is unavailable</span>
+            <span class="text-lg text-white md:text-xl lg:text-2xl">{t!("post-unavailable")}</span>
             <button
                 on:click=|_| go_back_or_fallback("/")
                 class="py-2 px-4 text-center text-white rounded-full bg-primary-600"
             >
-                Go back
+                {t!("go-back")}
             </button>
         </div>
     }
@@ -69,7 +71,8 @@ pub fn SinglePost() -> impl IntoView {
 
     let auth = auth_state();
 
-    let fetch_post = Resource::new(params, move |params| {
+    // blocking, the share preview of the post is rendered from it
+    let fetch_post = Resource::new_blocking(params, move |params| {
         send_wrap(async move {
             let params = params.map_err(|_| PostFetchError::Invalid)?;
             let unauth_cans = unauth_canisters();
@@ -95,7 +98,13 @@ pub fn SinglePost() -> impl IntoView {
                 fetch_post
                     .get()
                     .map(|post| match post {
-                        Ok(post) => view! { <SinglePostViewInner post /> }.into_any(),
+                        Ok(post) => {
+                            view! {
+                                <PostSharePreview post=post.clone() />
+                                <SinglePostViewInner post />
+                            }
+                                .into_any()
+                        }
                         Err(PostFetchError::Invalid) => view! { <Redirect path="/" /> }.into_any(),
                         Err(PostFetchError::Unavailable) => view! { <UnavailablePost /> }.into_any(),
                         Err(PostFetchError::GetUid(e)) => {
@@ -4,18 +4,17 @@ use candid::Principal;
 use futures::Stream;
 use leptos::prelude::*;
 
-use state::canisters::AuthState;
+use state::{app_state::AppState, canisters::AuthState};
 use utils::{
-    host::show_nsfw_content,
     ml_feed::{
         get_ml_feed_clean, get_ml_feed_coldstart_clean, get_ml_feed_coldstart_nsfw,
-        get_ml_feed_nsfw,
+        get_ml_feed_nsfw, ContentFilter, FeedError,
     },
     posts::FetchCursor,
 };
 use yral_canisters_common::Canisters;
 
-use crate::post_view::MlPostItem;
+use crate::post_view::{feed_cache::CachedFeedSource, local_feed_cache, MlPostItem};
 
 type PostsStream<'a> = Pin<Box<dyn Stream<Item = Vec<MlPostItem>> + 'a>>;
 
@@ -109,24 +108,54 @@ impl<
         (self.user_principal)(self.canisters, &self.auth).await
     }
 
+    fn content_filter(allow_nsfw: bool) -> ContentFilter {
+        if allow_nsfw || AppState::current().show_nsfw_content() {
+            ContentFilter::Nsfw
+        } else {
+            ContentFilter::Clean
+        }
+    }
+
+    async fn feed_user_principal(&self) -> Result<Principal, FeedError> {
+        self.user_principal()
+            .await
+            .map_err(|e| FeedError::ServerFn(e.to_string()))
+    }
+
     pub async fn fetch_post_uids_ml_feed_chunked(
         &self,
         allow_nsfw: bool,
-    ) -> Result<FetchVideosRes<'a>, ServerFnError> {
-        let user_principal_id = self.user_principal().await?;
-
-        let show_nsfw = allow_nsfw || show_nsfw_content();
-        let top_posts = if show_nsfw {
-            get_ml_feed_nsfw(user_principal_id, self.cursor.limit as u32)
-                .await
-                .map_err(|e| ServerFnError::new(format!("Error fetching ml feed: {e:?}")))?
+        exclude: Vec<String>,
+    ) -> Result<FetchVideosRes<'a>, FeedError> {
+        let user_principal_id = self.feed_user_principal().await?;
+
+        let app_state = AppState::current();
+        let content = Self::content_filter(allow_nsfw);
+        let top_posts = if content == ContentFilter::Nsfw {
+            get_ml_feed_nsfw(
+                &CachedFeedSource,
+                user_principal_id,
+                self.cursor.limit as u32,
+                exclude,
+                app_state.default_rec_type,
+            )
+            .await?
         } else {
-            get_ml_feed_clean(user_principal_id, self.cursor.limit as u32)
-                .await
-                .map_err(|e| ServerFnError::new(format!("Error fetching ml feed: {e:?}")))?
+            get_ml_feed_clean(
+                &CachedFeedSource,
+                user_principal_id,
+                self.cursor.limit as u32,
+                exclude,
+                app_state.default_rec_type,
+            )
+            .await?
         };
 
-        let top_posts = top_posts.into_iter().map(Into::into).collect();
+        if top_posts.is_empty() {
+            return Err(FeedError::Empty);
+        }
+        let top_posts: Vec<MlPostItem> = top_posts.into_iter().map(Into::into).collect();
+        local_feed_cache::store(content, &top_posts);
 
         let end = false;
 
@@ -140,21 +169,34 @@ impl<
     pub async fn fetch_post_uids_mlfeed_cache_chunked(
         &self,
         allow_nsfw: bool,
-    ) -> Result<FetchVideosRes<'a>, ServerFnError> {
-        let user_principal_id = self.user_principal().await?;
-
-        let show_nsfw = allow_nsfw || show_nsfw_content();
-        let top_posts = if show_nsfw {
-            get_ml_feed_coldstart_nsfw(user_principal_id, self.cursor.limit as u32)
-                .await
-                .map_err(|e| ServerFnError::new(format!("Error fetching ml feed: {e:?}")))?
+        exclude: Vec<String>,
+    ) -> Result<FetchVideosRes<'a>, FeedError> {
+        let user_principal_id = self.feed_user_principal().await?;
+
+        let content = Self::content_filter(allow_nsfw);
+        let top_posts = if content == ContentFilter::Nsfw {
+            get_ml_feed_coldstart_nsfw(
+                &CachedFeedSource,
+                user_principal_id,
+                self.cursor.limit as u32,
+                exclude,
+            )
+            .await?
         } else {
-            get_ml_feed_coldstart_clean(user_principal_id, self.cursor.limit as u32)
-                .await
-                .map_err(|e| ServerFnError::new(format!("Error fetching ml feed: {e:?}")))?
+            get_ml_feed_coldstart_clean(
+                &CachedFeedSource,
+                user_principal_id,
+                self.cursor.limit as u32,
+                exclude,
+            )
+            .await?
         };
 
-        let top_posts = top_posts.into_iter().map(Into::into).collect();
+        if top_posts.is_empty() {
+            return Err(FeedError::Empty);
+        }
+        let top_posts: Vec<MlPostItem> = top_posts.into_iter().map(Into::into).collect();
+        local_feed_cache::store(content, &top_posts);
 
         let end = false;
 
@@ -165,24 +207,39 @@ impl<
         })
     }
 
+    /// `exclude` holds video ids the user has already seen
+    ///
+    /// Falls back to the posts cached on this device if the feed server
+    /// fails, as any other request to it would likely fail too
     pub async fn fetch_post_uids_hybrid(
         &mut self,
         allow_nsfw: bool,
         video_queue_len: usize,
-    ) -> Result<FetchVideosRes<'a>, ServerFnError> {
-        if video_queue_len < 5 {
+        exclude: Vec<String>,
+    ) -> Result<FetchVideosRes<'a>, FeedError> {
+        let res = if video_queue_len < 5 {
             self.cursor.set_limit(30);
-            self.fetch_post_uids_mlfeed_cache_chunked(allow_nsfw).await
+            self.fetch_post_uids_mlfeed_cache_chunked(allow_nsfw, exclude.clone())
+                .await
         } else {
-            let res = self.fetch_post_uids_ml_feed_chunked(allow_nsfw).await;
-
-            match res {
-                Ok(res) => Ok(res),
-                Err(_) => {
-                    self.cursor.set_limit(50);
-                    self.fetch_post_uids_mlfeed_cache_chunked(allow_nsfw).await
-                }
-            }
+            self.fetch_post_uids_ml_feed_chunked(allow_nsfw, exclude.clone())
+                .await
+        };
+        let err = match res {
+            Ok(res) => return Ok(res),
+            Err(e) => e,
+        };
+
+        let cached = local_feed_cache::fallback(Self::content_filter(allow_nsfw), &exclude);
+        if cached.is_empty() {
+            return Err(err);
         }
+        leptos::logging::warn!("ML feed unavailable ({err}), using posts cached locally");
+
+        Ok(FetchVideosRes {
+            posts_stream: Box::pin(futures::stream::once(async move { cached })),
+            end: false,
+            res_type: FeedResultType::PostCache,
+        })
     }
 }
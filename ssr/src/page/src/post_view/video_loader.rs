@@ -1,4 +1,3 @@
-use indexmap::IndexSet;
 use leptos::logging;
 use leptos::{html::Video, prelude::*};
 use state::canisters::auth_state;
@@ -10,6 +9,7 @@ use component::video_player::VideoPlayer;
 use futures::FutureExt;
 use gloo::timers::future::TimeoutFuture;
 use utils::ml_feed::QuickPostDetails;
+use utils::video_prefetch::{PrefetchState, VideoPrefetchCtx};
 use utils::{bg_url, mp4_url, send_wrap};
 
 /// Maximum PostDetails, time in milliseconds to waitay promise to resolve
@@ -22,19 +22,14 @@ use super::overlay::VideoDetailsOverlay;
 
 #[component]
 pub fn BgView<DetailResolver>(
-    video_queue: RwSignal<IndexSet<DetailResolver>>,
+    post: RwSignal<Option<DetailResolver>>,
     idx: usize,
     children: Children,
 ) -> impl IntoView
 where
     DetailResolver: PostDetailResolver + Clone + PartialEq + Sync + Send + 'static,
 {
-    let post_with_prev = Memo::new(move |_| {
-        video_queue.with(|q| {
-            let cur_post = q.get_index(idx).cloned();
-            cur_post
-        })
-    });
+    let post_with_prev = Memo::new(move |_| post.get());
 
     let PostDetailsCacheCtx {
         post_details: post_details_cache,
@@ -110,6 +105,7 @@ pub fn VideoView(
     volume: RwSignal<f64>,
     #[prop(optional, into)] is_current: Option<Signal<bool>>,
     #[prop(optional, into)] high_priority: bool,
+    #[prop(optional, into)] prefetched: Signal<bool>,
 ) -> impl IntoView {
     let post_for_uid = post;
     let uid = Memo::new(move |_| {
@@ -176,6 +172,7 @@ pub fn VideoView(
             view_bg_url=Signal::derive(view_bg_url)
             view_video_url=Signal::derive(view_video_url)
             high_priority
+            prefetched
         />
     }
     .into_any()
@@ -198,6 +195,18 @@ where
     let quick_post_details =
         Signal::derive(move || post.get().map(|post| post.get_quick_post_details()));
 
+    let prefetch = use_context::<VideoPrefetchCtx>();
+    let prefetch_state = Signal::derive(move || {
+        let Some(prefetch) = prefetch else {
+            return PrefetchState::Idle;
+        };
+        quick_post_details.with(|p| {
+            p.as_ref()
+                .map(|p| prefetch.state(&p.video_uid))
+                .unwrap_or_default()
+        })
+    });
+
     // Track if video is already playing to prevent multiple play attempts
     let is_playing = RwSignal::new(false);
 
@@ -237,7 +246,10 @@ where
                             }
                         }
                         _ = timeout_future => {
-                            logging::error!("video_log: Video play() did not resolve within 5 seconds");
+                            logging::error!(
+                                "video_log: Video play() did not resolve within 5 seconds (prefetch: {:?})",
+                                prefetch_state.get_untracked()
+                            );
                         }
                     }
                 });
@@ -259,6 +271,7 @@ where
             volume
             is_current=is_current_signal
             high_priority
+            prefetched=Signal::derive(move || prefetch_state.get() == PrefetchState::Ready)
         />
     }
     .into_any()
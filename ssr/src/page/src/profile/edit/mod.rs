@@ -1,12 +1,12 @@
 pub mod username;
 
 use component::{back_btn::BackButton, spinner::Spinner, title::TitleText};
-use leptos::{either::Either, html, prelude::*, task::spawn_local};
+use leptos::{either::Either, html, prelude::*, task::spawn_local, text_prop::TextProp};
 use leptos_icons::Icon;
 use leptos_meta::Title;
 use leptos_router::{components::Redirect, hooks::use_navigate};
 use state::{app_state::AppState, canisters::auth_state};
-use utils::send_wrap;
+use utils::{i18n::use_i18n, send_wrap, t};
 use wasm_bindgen::closure::Closure;
 use wasm_bindgen::JsCast;
 use yral_canisters_client::local::USER_INFO_SERVICE_ID;
@@ -15,19 +15,19 @@ use yral_canisters_common::utils::profile::ProfileDetails;
 
 #[component]
 pub fn ProfileEdit() -> impl IntoView {
-    let app_state = use_context::<AppState>();
-    let page_title = app_state.unwrap().name.to_owned() + " - Edit Profile";
+    let name = use_context::<AppState>().unwrap().name;
+    let page_title = t!("edit-profile-title", name = name);
 
     let auth = auth_state();
 
     view! {
-        <Title text=page_title.clone() />
+        <Title text=page_title />
 
         <div class="flex flex-col items-center pt-2 pb-12 bg-black min-w-dvw min-h-dvh">
             <TitleText justify_center=false>
                 <div class="flex flex-row justify-between">
                     <BackButton fallback="/profile/posts".to_string() />
-                    <span class="text-lg font-bold text-white">Edit Profile</span>
+                    <span class="text-lg font-bold text-white">{t!("edit-profile")}</span>
                     <div></div>
                 </div>
             </TitleText>
@@ -55,8 +55,8 @@ pub fn ProfileEdit() -> impl IntoView {
 
 #[component]
 fn InputField(
-    #[prop(into)] label: String,
-    #[prop(into)] placeholder: String,
+    #[prop(into)] label: TextProp,
+    #[prop(into)] placeholder: TextProp,
     value: RwSignal<String>,
     #[prop(optional)] is_required: bool,
     #[prop(optional)] prefix: Option<String>,
@@ -67,7 +67,7 @@ fn InputField(
         <div class="w-full flex flex-col gap-[10px]">
             <div class="flex gap-2 items-center">
                 <span class="text-[14px] font-medium text-neutral-400 font-['Kumbh_Sans']">
-                    {label}
+                    {move || label.get()}
                     {is_required.then_some("*")}
                 </span>
             </div>
@@ -79,7 +79,7 @@ fn InputField(
                     view! {
                         <textarea
                             class="w-full bg-transparent text-[14px] font-medium text-neutral-50 font-['Kumbh_Sans'] placeholder-neutral-400 outline-none resize-none"
-                            placeholder=placeholder
+                            placeholder=move || placeholder.get()
                             rows=2
                             bind:value=value
                         />
@@ -90,7 +90,7 @@ fn InputField(
                         <input
                             type=field_type
                             class="w-full bg-transparent text-[14px] font-medium text-neutral-50 font-['Kumbh_Sans'] placeholder-neutral-400 outline-none"
-                            placeholder=placeholder
+                            placeholder=move || placeholder.get()
                             bind:value=value
                         />
                     }.into_any()
@@ -137,6 +137,7 @@ fn ProfileEditInner(
     let save_error = RwSignal::new(Option::<String>::None);
     let success_message = RwSignal::new(Option::<String>::None);
     let nav = use_navigate();
+    let i18n = use_i18n();
 
     // Username validation state
     let username_input_ref = NodeRef::<html::Input>::new();
@@ -154,16 +155,13 @@ fn ProfileEditInner(
 
         #[cfg(feature = "hydrate")]
         if input.validity().pattern_mismatch() {
-            return Some(
-                "Username must be 3-15 characters long and can only contain letters and numbers."
-                    .to_string(),
-            );
+            return Some(i18n.tr("username-invalid", &[]));
         }
 
         Some(
             input
                 .validation_message()
-                .unwrap_or_else(|_| "Invalid input".to_string()),
+                .unwrap_or_else(|_| i18n.tr("invalid-input", &[])),
         )
     };
 
@@ -220,8 +218,8 @@ fn ProfileEditInner(
             // Validate and format URL
             let formatted_website = match validate_and_format_url(website_val.clone()) {
                 Ok(url) => url,
-                Err(e) => {
-                    save_error.set(Some(e));
+                Err(_) => {
+                    save_error.set(Some(i18n.tr_untracked("invalid-url", &[])));
                     saving.set(false);
                     return;
                 }
@@ -233,7 +231,7 @@ fn ProfileEditInner(
             website.set(formatted_website.clone());
 
             let Ok(mut canisters) = auth.auth_cans().await else {
-                save_error.set(Some("Not authenticated".to_string()));
+                save_error.set(Some(i18n.tr_untracked("not-authenticated", &[])));
                 saving.set(false);
                 username_changing.set(false);
                 return;
@@ -242,9 +240,7 @@ fn ProfileEditInner(
             // Only support service canister users
             if canisters.user_canister() != USER_INFO_SERVICE_ID {
                 log::error!("Profile update not supported for individual canister users");
-                save_error.set(Some(
-                    "Profile update not available for this account type".to_string(),
-                ));
+                save_error.set(Some(i18n.tr_untracked("profile-update-unavailable", &[])));
                 saving.set(false);
                 username_changing.set(false);
                 return;
@@ -262,10 +258,7 @@ fn ProfileEditInner(
                     && username_val.len() <= 15
                     && username_val.chars().all(|c| c.is_ascii_alphanumeric());
                 if !is_valid {
-                    save_error.set(Some(
-                        "Username must be 3-15 characters and contain only letters and numbers"
-                            .to_string(),
-                    ));
+                    save_error.set(Some(i18n.tr_untracked("username-invalid", &[])));
                     saving.set(false);
                     username_changing.set(false);
                     return;
@@ -323,7 +316,9 @@ fn ProfileEditInner(
                         website.set(orig_website.clone());
 
                         log::warn!("Error updating profile: {e}");
-                        save_error.set(Some(format!("Update failed: {e}")));
+                        save_error.set(Some(
+                            i18n.tr_untracked("update-failed", &[("error", e.to_string())]),
+                        ));
                         saving.set(false);
                         username_changing.set(false);
                         return;
@@ -335,7 +330,7 @@ fn ProfileEditInner(
                         website.set(orig_website.clone());
 
                         log::warn!("Network error updating profile: {e:?}");
-                        save_error.set(Some("Network error. Please try again.".to_string()));
+                        save_error.set(Some(i18n.tr_untracked("network-error", &[])));
                         saving.set(false);
                         username_changing.set(false);
                         return;
@@ -363,11 +358,12 @@ fn ProfileEditInner(
                         username.set(orig_username.clone());
 
                         // Set custom validity on the input element
+                        let taken = i18n.tr_untracked("username-taken", &[]);
                         if let Some(input) = username_input_ref.get_untracked() {
-                            input.set_custom_validity("This username is not available");
+                            input.set_custom_validity(&taken);
                             username_validity_trigger.notify();
                         }
-                        save_error.set(Some("This username is not available".to_string()));
+                        save_error.set(Some(taken));
                         saving.set(false);
                         username_changing.set(false);
                         return;
@@ -377,7 +373,8 @@ fn ProfileEditInner(
                         username.set(orig_username.clone());
 
                         log::error!("Error updating username: {e}");
-                        save_error.set(Some(format!("Failed to update username: {e}")));
+                        let args = [("error", e.to_string())];
+                        save_error.set(Some(i18n.tr_untracked("username-update-failed", &args)));
                         saving.set(false);
                         username_changing.set(false);
                         return;
@@ -386,18 +383,14 @@ fn ProfileEditInner(
             }
 
             // Show success message if anything was updated
-            if username_update_success || profile_update_success {
-                // Generate success message
-                let mut updated_items = Vec::new();
-                if username_update_success {
-                    updated_items.push("Username");
-                }
-                if profile_update_success {
-                    updated_items.push("Profile");
-                }
-
-                let success_msg = format!("{} updated successfully!", updated_items.join(" and "));
-                success_message.set(Some(success_msg));
+            let success_key = match (username_update_success, profile_update_success) {
+                (true, true) => Some("username-and-profile-updated"),
+                (true, false) => Some("username-updated"),
+                (false, true) => Some("profile-updated"),
+                (false, false) => None,
+            };
+            if let Some(key) = success_key {
+                success_message.set(Some(i18n.tr_untracked(key, &[])));
             }
 
             saving.set(false);
@@ -440,7 +433,7 @@ fn ProfileEditInner(
                 <div class="w-full flex flex-col gap-[10px] group">
                     <div class="flex gap-2 items-center">
                         <span class="text-[14px] font-medium text-neutral-400 font-['Kumbh_Sans']">
-                            "User Name"
+                            {t!("user-name")}
                         </span>
                     </div>
                     <form
@@ -455,7 +448,7 @@ fn ProfileEditInner(
                             on:input=move |_| on_username_input()
                             bind:value=username
                             class="w-full bg-transparent text-[14px] font-medium text-neutral-50 font-['Kumbh_Sans'] placeholder-neutral-400 outline-none peer"
-                            placeholder="Enter username"
+                            placeholder=t!("username-placeholder")
                         />
                         // Visual feedback indicators
                         <Show when=username_changing>
@@ -497,22 +490,22 @@ fn ProfileEditInner(
                         {move || username_error_message()}
                     </p>
                     <span class="text-[12px] text-neutral-500 font-['Kumbh_Sans']">
-                        "Username must be 3-15 characters. Letters and numbers only."
+                        {t!("username-rules")}
                     </span>
                 </div>
 
                 // Bio Field
                 <InputField
-                    label="Bio"
-                    placeholder="Tell us about yourself"
+                    label=t!("bio")
+                    placeholder=t!("bio-placeholder")
                     value=bio
                     multiline=true
                 />
 
                 // Website Field
                 <InputField
-                    label="Website/URL"
-                    placeholder="Your website URL"
+                    label=t!("website")
+                    placeholder=t!("website-placeholder")
                     value=website
                     input_type="url"
                 />
@@ -552,12 +545,12 @@ fn ProfileEditInner(
                         when=move || !saving.get()
                         fallback=|| view! {
                             <span class="text-[16px] font-bold text-[#f6b0d6] font-['Kumbh_Sans']">
-                                "Saving..."
+                                {t!("saving")}
                             </span>
                         }
                     >
                         <span class="text-[16px] font-bold text-[#f6b0d6] font-['Kumbh_Sans']">
-                            "Save"
+                            {t!("save")}
                         </span>
                     </Show>
                 </button>
@@ -794,6 +787,7 @@ fn ProfileImageEditor(
     let file_input_ref = NodeRef::<html::Input>::new();
     let is_uploading = RwSignal::new(false);
     let file_error = RwSignal::new(Option::<String>::None);
+    let i18n = use_i18n();
 
     // Maximum file size: 5MB
     const MAX_FILE_SIZE: f64 = 5.0 * 1024.0 * 1024.0;
@@ -808,24 +802,21 @@ fn ProfileImageEditor(
                 let file_size = file.size();
                 if file_size > MAX_FILE_SIZE {
                     let size_mb = (file_size / (1024.0 * 1024.0)).round();
-                    file_error.set(Some(format!("File too large: {size_mb}MB (max 5MB)")));
+                    let args = [("size", size_mb.to_string())];
+                    file_error.set(Some(i18n.tr_untracked("image-too-large", &args)));
                     return;
                 }
 
                 // Check file type
                 let file_type = file.type_();
                 if !file_type.starts_with("image/") {
-                    file_error.set(Some(
-                        "Please select an image file (JPEG, PNG, WebP)".to_string(),
-                    ));
+                    file_error.set(Some(i18n.tr_untracked("image-wrong-type", &[])));
                     return;
                 }
 
                 let accepted_formats = ["image/jpeg", "image/png", "image/webp"];
                 if !accepted_formats.iter().any(|&fmt| file_type == fmt) {
-                    file_error.set(Some(
-                        "Unsupported format. Please use JPEG, PNG, or WebP".to_string(),
-                    ));
+                    file_error.set(Some(i18n.tr_untracked("image-unsupported-format", &[])));
                     return;
                 }
 
@@ -906,7 +897,7 @@ fn ProfileImageEditor(
         <component::overlay::ShadowOverlay show>
             <div class="flex flex-col justify-around items-center py-4 w-[90vw] md:w-[80vw] lg:w-[70vw] max-w-5xl rounded-md cursor-auto px-6 bg-neutral-900">
                 <div class="flex justify-between items-center w-full mb-4">
-                    <h2 class="text-xl font-bold text-white">"Edit Profile Picture"</h2>
+                    <h2 class="text-xl font-bold text-white">{t!("edit-profile-picture")}</h2>
                     <button
                         on:click=move |_| show.set(false)
                         class="p-1 text-lg text-center text-white rounded-full md:text-xl bg-neutral-600"
@@ -979,7 +970,7 @@ fn ProfileImageEditor(
                                     }
                                 >
                                     <Icon icon=icondata::BiImageAddRegular attr:class="text-6xl md:text-8xl text-neutral-400 mb-4" />
-                                    <p class="text-base md:text-lg text-neutral-400 font-medium">"Click to upload an image"</p>
+                                    <p class="text-base md:text-lg text-neutral-400 font-medium">{t!("click-to-upload-image")}</p>
                                     <p class="text-xs md:text-sm text-neutral-500 mt-2">"or drag and drop"</p>
                                 </div>
                             }.into_any()
@@ -1044,7 +1035,7 @@ fn ProfileImageEditor(
                                     }
                                     class="flex-1 px-4 py-2 bg-neutral-700 hover:bg-neutral-600 rounded-lg text-white font-medium"
                                 >
-                                    "Change Image"
+                                    {t!("change-image")}
                                 </button>
                             }.into_any()
                         } else {
@@ -1059,7 +1050,7 @@ fn ProfileImageEditor(
                         }
                         class="flex-1 px-4 py-2 bg-neutral-700 hover:bg-neutral-600 rounded-lg text-white font-medium"
                     >
-                        "Cancel"
+                        {t!("cancel")}
                     </button>
 
                     <button
@@ -1092,14 +1083,14 @@ fn ProfileImageEditor(
                                                     }
                                                     Err(e) => {
                                                         leptos::logging::error!("Failed to upload image: {}", e);
-                                                        file_error.set(Some(format!("Upload failed: {e}")));
+                                                        file_error.set(Some(i18n.tr_untracked("upload-failed", &[("error", e.to_string())])));
                                                         is_uploading.set(false);
                                                     }
                                                 }
                                             }
                                             Err(e) => {
                                                 leptos::logging::error!("Failed to process image: {}", e);
-                                                file_error.set(Some(format!("Processing failed: {e}")));
+                                                file_error.set(Some(i18n.tr_untracked("processing-failed", &[("error", e.to_string())])));
                                                 is_uploading.set(false);
                                             }
                                         }
@@ -1110,7 +1101,7 @@ fn ProfileImageEditor(
                         disabled=move || uploaded_image.get().is_none() || is_uploading.get()
                         class="flex-1 px-4 py-2 bg-gradient-to-r from-[#e2017b] to-[#e2017b] hover:opacity-90 disabled:opacity-50 disabled:cursor-not-allowed rounded-lg text-white font-medium"
                     >
-                        {move || if is_uploading.get() { "Saving..." } else { "Save" }}
+                        {move || i18n.tr(if is_uploading.get() { "saving" } else { "save" }, &[])}
                     </button>
                 </div>
                 </div>
@@ -6,7 +6,9 @@ mod profile_iter;
 pub mod profile_post;
 
 use candid::Principal;
-use component::{connect::ConnectLogin, spinner::FullScreenSpinner};
+use component::{
+    connect::ConnectLogin, share_meta::ProfileSharePreview, spinner::FullScreenSpinner,
+};
 use consts::MAX_VIDEO_ELEMENTS_FOR_FEED;
 use indexmap::IndexSet;
 use leptos::{html, prelude::*};
@@ -22,7 +24,10 @@ use serde::{Deserialize, Serialize};
 use state::{app_state::AppState, canisters::auth_state};
 
 use component::{infinite_scroller::InfiniteScroller, overlay::ShadowOverlay};
-use utils::{mixpanel::mixpanel_events::*, posts::FeedPostCtx, send_wrap, UsernameOrPrincipal};
+use utils::{
+    i18n::use_i18n, mixpanel::mixpanel_events::*, posts::FeedPostCtx, send_wrap, t,
+    UsernameOrPrincipal,
+};
 use yral_canisters_client::user_info_service::{Result1, Result2};
 use yral_canisters_common::{
     cursored_data::{CursoredDataProvider, KeyedData, PageEntry},
@@ -89,15 +94,16 @@ struct SmileyGameStats {
 // Unlock Profile component for non-logged-in users
 #[component]
 fn UnlockProfile() -> impl IntoView {
+    let name = expect_context::<AppState>().name;
     view! {
         <div class="flex flex-col gap-[30px] items-center w-full py-12">
             <div class="flex flex-col gap-[10px] items-center text-center">
                 <p class="font-semibold text-base text-neutral-50">
-                    "Unlock Profile"
+                    {t!("unlock-profile")}
                 </p>
                 <div class="font-normal text-sm text-neutral-400">
-                    <p class="mb-0">"Log in to claim rewards, build your profile,"</p>
-                    <p>"and join the YRAL community."</p>
+                    <p class="mb-0">{t!("unlock-profile-rewards")}</p>
+                    <p>{t!("unlock-profile-community", name = name)}</p>
                 </div>
             </div>
             <div class="w-full max-w-[270px]">
@@ -554,16 +560,18 @@ fn FollowButton(
     user_follows_caller: Option<bool>,
     on_click: impl Fn() + 'static,
 ) -> impl IntoView {
+    let i18n = use_i18n();
     let button_text = move || {
-        if is_loading.get() {
-            "Loading..."
+        let key = if is_loading.get() {
+            "loading"
         } else if is_following.get().unwrap_or(false) {
-            "Unfollow"
+            "unfollow"
         } else if user_follows_caller.unwrap_or(false) {
-            "Follow Back"
+            "follow-back"
         } else {
-            "Follow"
-        }
+            "follow"
+        };
+        i18n.tr(key, &[])
     };
 
     let button_class = move || {
@@ -832,7 +840,7 @@ fn FollowersFollowingPopup(
                                     empty_content=move || view! {
                                         <div class="flex flex-col items-center justify-center py-12 px-4">
                                             <p class="text-neutral-400 text-center">
-                                                "Not following anyone yet"
+                                                {t!("not-following-anyone")}
                                             </p>
                                         </div>
                                     }
@@ -859,7 +867,7 @@ fn FollowersFollowingPopup(
                             empty_content=move || view! {
                                 <div class="flex flex-col items-center justify-center py-12 px-4">
                                     <p class="text-neutral-400 text-center">
-                                        "No followers yet"
+                                        {t!("no-followers")}
                                     </p>
                                 </div>
                             }
@@ -1150,7 +1158,7 @@ fn ProfileViewInner(user: ProfileDetails) -> impl IntoView {
                                                 class="w-full bg-[#212121] border border-neutral-700 rounded-lg px-5 py-2.5 flex items-center justify-center"
                                             >
                                                 <span class="font-semibold text-sm text-neutral-50">
-                                                    "Edit Profile"
+                                                    {t!("edit-profile")}
                                                 </span>
                                             </button>
                                         </Show>
@@ -1288,8 +1296,13 @@ pub fn ProfileView() -> impl IntoView {
         },
     );
 
+    let share_preview = untrack(param_id).map(|id| {
+        view! { <ProfileSharePreview id=id.to_string() /> }
+    });
+
     view! {
         <ProfilePageTitle />
+        {share_preview}
         <Suspense fallback=FullScreenSpinner>
             {move || Suspend::new(async move {
                 match user_details.await {
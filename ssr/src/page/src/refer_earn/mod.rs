@@ -3,6 +3,7 @@ use global_constants::{NEW_USER_SIGNUP_REWARD_SATS, REFERRAL_REWARD_SATS};
 use gloo::timers::callback::Timeout;
 use leptos::either::Either;
 use leptos::prelude::*;
+use leptos::text_prop::TextProp;
 use leptos_icons::*;
 use leptos_meta::*;
 use leptos_router::components::Redirect;
@@ -13,17 +14,19 @@ use component::{back_btn::BackButton, buttons::HighlightedButton, title::TitleTe
 use state::app_state::AppState;
 use state::canisters::auth_state;
 use utils::event_streaming::events::{Refer, ReferShareLink};
+use utils::i18n::use_i18n;
 use utils::mixpanel::mixpanel_events::*;
+use utils::t;
 use utils::web::copy_to_clipboard;
 
 #[component]
-fn WorkButton(#[prop(into)] text: String, #[prop(into)] head: String) -> impl IntoView {
+fn WorkButton(#[prop(into)] text: TextProp, #[prop(into)] head: TextProp) -> impl IntoView {
     view! {
         <div class="basis-1/3 flex flex-col gap-3 justify-center items-center
                     py-4 px-2 text-xs rounded-md bg-neutral-900
                     lg:flex-row lg:py-5 lg:px-4 lg:text-sm">
-            <div class="font-bold text-neutral-50 lg:whitespace-nowrap">{head}</div>
-            <span class="text-neutral-400 text-center lg:whitespace-nowrap">{text}</span>
+            <div class="font-bold text-neutral-50 lg:whitespace-nowrap">{move || head.get()}</div>
+            <span class="text-neutral-400 text-center lg:whitespace-nowrap">{move || text.get()}</span>
         </div>
     }
 }
@@ -86,8 +89,15 @@ fn ReferLoaded(user_principal: Principal) -> impl IntoView {
         }
     });
     let refer_link_share = refer_link.clone();
+    let i18n = use_i18n();
     let handle_share = move || {
-        let text = format!("Join YRAL—the world's 1st social platform on BITCOIN\nGet FREE {NEW_USER_SIGNUP_REWARD_SATS} YRAL Instantly\nAdditional {REFERRAL_REWARD_SATS} YRAL when you log in using the link.");
+        let text = i18n.tr_untracked(
+            "refer-share-text",
+            &[
+                ("signup_sats", NEW_USER_SIGNUP_REWARD_SATS.to_string()),
+                ("referral_sats", REFERRAL_REWARD_SATS.to_string()),
+            ],
+        );
         let global = MixpanelGlobalProps::from_ev_ctx(ev_ctx);
         if let Some(global) = global {
             MixPanelEvent::track_share_invites_clicked(global, REFERRAL_REWARD_SATS);
@@ -119,14 +129,14 @@ fn ReferLoaded(user_principal: Principal) -> impl IntoView {
                 disabled=false
                 on_click=move || { handle_share() }
             >
-                Share
+                {t!("share")}
             </HighlightedButton>
         </div>
 
         <Show when=show_copied_popup>
             <div class="flex absolute flex-col justify-center items-center z-4">
                 <span class="flex absolute top-28 flex-row justify-center items-center w-28 h-10 text-center rounded-md shadow-lg bg-white/90">
-                    <p class="text-black">Link Copied!</p>
+                    <p class="text-black">{t!("link-copied")}</p>
                 </span>
             </div>
         </Show>
@@ -185,7 +195,7 @@ fn ReferView() -> impl IntoView {
 
             <div class="relative z-10 flex flex-col gap-4 items-center w-full text-center">
                 <span class="text-xl font-bold md:text-2xl">
-                    {"Invite & get "} {REFERRAL_REWARD_SATS} {" YRAL"}
+                    {t!("invite-and-get", amount = REFERRAL_REWARD_SATS)}
                 </span>
             </div>
 
@@ -196,13 +206,16 @@ fn ReferView() -> impl IntoView {
             </div>
 
             <div class="relative z-10 flex flex-col gap-6 items-center pb-5 mt-2 w-full">
-                <span class="font-semibold font-xl">{"How it works?"}</span>
+                <span class="font-semibold font-xl">{t!("how-it-works")}</span>
 
                 <div class="flex w-full max-w-4xl flex-row flex-nowrap items-stretch justify-center gap-3
                             sm:gap-4">
-                    <WorkButton head="STEP 1" text="Share your link with a friend" />
-                    <WorkButton head="STEP 2" text="Your friend logs in from the link" />
-                    <WorkButton head="STEP 3" text=format!("You both earn {} YRAL", REFERRAL_REWARD_SATS) />
+                    <WorkButton head=t!("refer-step", step = 1) text=t!("refer-step-share") />
+                    <WorkButton head=t!("refer-step", step = 2) text=t!("refer-step-login") />
+                    <WorkButton
+                        head=t!("refer-step", step = 3)
+                        text=t!("refer-step-earn", amount = REFERRAL_REWARD_SATS)
+                    />
                 </div>
             </div>
         </div>
@@ -211,16 +224,16 @@ fn ReferView() -> impl IntoView {
 
 #[component]
 pub fn ReferEarn() -> impl IntoView {
-    let app_state = use_context::<AppState>();
-    let page_title = app_state.unwrap().name.to_owned() + " - Refer & Earn";
+    let name = use_context::<AppState>().unwrap().name;
+    let page_title = t!("refer-earn-title", name = name);
     view! {
-        <Title text=page_title.clone() />
+        <Title text=page_title />
         <div class="flex flex-col mb-8 min-w-dvw min-h-screen  bg-black text-white">
             <div class="flex-none pt-2 pb-4 px-2">
                 <TitleText justify_center=false>
                     <div class="flex flex-row justify-between bg-transparent">
                         <BackButton fallback="/menu".to_string() />
-                        <span class="text-lg font-bold text-white">Refer & Earn</span>
+                        <span class="text-lg font-bold text-white">{t!("refer-earn")}</span>
                         <div></div>
                     </div>
                 </TitleText>
@@ -6,11 +6,8 @@ use leptos::prelude::*;
 use leptos_meta::*;
 use leptos_router::hooks::use_query_map;
 use leptos_use::{use_cookie_with_options, UseCookieOptions};
-use utils::host::show_nsfw_content;
-use utils::ml_feed::{
-    get_ml_feed_clean, get_ml_feed_coldstart_clean, get_ml_feed_coldstart_nsfw, get_ml_feed_nsfw,
-    PostItem,
-};
+use state::app_state::AppState;
+use utils::ml_feed::PostItem;
 use utils::try_or_redirect_opt;
 
 use crate::post_view::{PostViewCtx, PostViewWithUpdatesMLFeed};
@@ -48,56 +45,111 @@ fn generate_random_principal() -> Principal {
     Principal::from_slice(&bytes)
 }
 
+/// Posts shown on the root page before the feed takes over
+#[cfg(feature = "ssr")]
+const ROOT_FEED_POSTS: usize = 15;
+/// Posts fetched for the pool the root page posts are sampled from
+#[cfg(feature = "ssr")]
+const ROOT_FEED_POOL_SIZE: u32 = 60;
+
+#[cfg(feature = "ssr")]
+fn sample_seed() -> u64 {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos() as u64
+}
+
 #[server]
 #[tracing::instrument]
 async fn get_top_post_ids_global_clean_feed() -> Result<Vec<PostItem>, ServerFnError> {
-    let random_principal = generate_random_principal();
-    let posts = get_ml_feed_coldstart_clean(random_principal, 15)
-        .await
-        .map_err(|e| {
-            leptos::logging::error!("Error getting top post id global clean feed: {e:?}");
-            ServerFnError::new(e.to_string())
-        })?;
-
-    if posts.is_empty() {
+    use crate::post_view::feed_cache::server::root_feed_posts;
+    use utils::ml_feed::{
+        feed_source, get_ml_feed_clean, get_ml_feed_coldstart_clean, ContentFilter,
+    };
+
+    let source = feed_source();
+    let rec_type = AppState::current().default_rec_type;
+    let fetch_pool = async move {
+        let posts = get_ml_feed_coldstart_clean(
+            &source,
+            generate_random_principal(),
+            ROOT_FEED_POOL_SIZE,
+            vec![],
+        )
+        .await?;
+        if !posts.is_empty() {
+            return Ok(posts);
+        }
         leptos::logging::warn!("Coldstart clean feed returned 0 results, falling back to ML feed");
-        let fallback_principal = generate_random_principal();
-        let posts = get_ml_feed_clean(fallback_principal, 15)
-            .await
-            .map_err(|e| {
-                leptos::logging::error!("Error getting ML feed clean fallback: {e:?}");
-                ServerFnError::new(e.to_string())
-            })?;
-        Ok(posts)
-    } else {
-        Ok(posts)
-    }
+        get_ml_feed_clean(
+            &source,
+            generate_random_principal(),
+            ROOT_FEED_POOL_SIZE,
+            vec![],
+            rec_type,
+        )
+        .await
+    };
+
+    root_feed_posts(
+        ContentFilter::Clean,
+        ROOT_FEED_POSTS,
+        sample_seed(),
+        fetch_pool,
+    )
+    .await
+    .map_err(|e| {
+        leptos::logging::error!("Error getting top post id global clean feed: {e:?}");
+        ServerFnError::new(e.to_string())
+    })
 }
 
 #[server]
 #[tracing::instrument]
 async fn get_top_post_ids_global_nsfw_feed() -> Result<Vec<PostItem>, ServerFnError> {
-    let random_principal = generate_random_principal();
-    let posts = get_ml_feed_coldstart_nsfw(random_principal, 15)
-        .await
-        .map_err(|e| {
-            leptos::logging::error!("Error getting top post id global nsfw feed: {e:?}");
-            ServerFnError::new(e.to_string())
-        })?;
-
-    if posts.is_empty() {
+    use crate::post_view::feed_cache::server::root_feed_posts;
+    use utils::ml_feed::{
+        feed_source, get_ml_feed_coldstart_nsfw, get_ml_feed_nsfw, ContentFilter,
+    };
+
+    let source = feed_source();
+    let rec_type = AppState::current().default_rec_type;
+    let fetch_pool = async move {
+        let posts = get_ml_feed_coldstart_nsfw(
+            &source,
+            generate_random_principal(),
+            ROOT_FEED_POOL_SIZE,
+            vec![],
+        )
+        .await?;
+        if !posts.is_empty() {
+            return Ok(posts);
+        }
         leptos::logging::warn!("Coldstart nsfw feed returned 0 results, falling back to ML feed");
-        let fallback_principal = generate_random_principal();
-        let posts = get_ml_feed_nsfw(fallback_principal, 15)
-            .await
-            .map_err(|e| {
-                leptos::logging::error!("Error getting ML feed nsfw fallback: {e:?}");
-                ServerFnError::new(e.to_string())
-            })?;
-        Ok(posts)
-    } else {
-        Ok(posts)
-    }
+        get_ml_feed_nsfw(
+            &source,
+            generate_random_principal(),
+            ROOT_FEED_POOL_SIZE,
+            vec![],
+            rec_type,
+        )
+        .await
+    };
+
+    root_feed_posts(
+        ContentFilter::Nsfw,
+        ROOT_FEED_POSTS,
+        sample_seed(),
+        fetch_pool,
+    )
+    .await
+    .map_err(|e| {
+        leptos::logging::error!("Error getting top post id global nsfw feed: {e:?}");
+        ServerFnError::new(e.to_string())
+    })
 }
 
 #[component]
@@ -113,6 +165,7 @@ pub fn YralRootPage() -> impl IntoView {
     );
 
     let PostViewCtx { video_queue, .. } = expect_context();
+    let tenant_shows_nsfw = expect_context::<AppState>().show_nsfw_content();
 
     let initial_posts = Resource::new(params, move |params_map| {
         async move {
@@ -121,11 +174,11 @@ pub fn YralRootPage() -> impl IntoView {
                 return Ok(Default::default());
             }
 
-            // Check query param first, then cookie, then show_nsfw_content
+            // Check query param first, then cookie, then the tenant's policy
             let nsfw_from_query = params_map.get("nsfw").map(|s| s == "true").unwrap_or(false);
             let nsfw_enabled = nsfw_from_query
                 || nsfw_cookie_enabled.get_untracked().unwrap_or(false)
-                || show_nsfw_content();
+                || tenant_shows_nsfw;
             leptos::logging::log!(
                 "NSFW enabled: {nsfw_enabled} (query: {nsfw_from_query}, cookie: {:?})",
                 nsfw_cookie_enabled.get_untracked()
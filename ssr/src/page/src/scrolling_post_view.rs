@@ -6,7 +6,11 @@ use leptos_icons::*;
 use leptos_use::{use_intersection_observer_with_options, UseIntersectionObserverOptions};
 
 use state::audio_state::AudioState;
-use utils::{ml_feed::QuickPostDetails, posts::FeedPostCtx};
+use utils::{
+    ml_feed::QuickPostDetails,
+    posts::FeedPostCtx,
+    video_prefetch::{VideoPrefetchCtx, VIDEO_PREFETCH_AHEAD},
+};
 use yral_canisters_common::utils::posts::PostDetails;
 
 /// A trait that requires some post details to be accessible instantly while others may be suspended
@@ -25,7 +29,7 @@ impl PostDetailResolver for PostDetails {
             canister_id: self.canister_id,
             post_id: self.post_id.clone(),
             publisher_user_id: self.poster_principal,
-            nsfw_probability: self.nsfw_probability,
+            nsfw_probability: Some(self.nsfw_probability),
         }
     }
 
@@ -67,6 +71,9 @@ pub fn ScrollingPostView<
     video_queue: RwSignal<IndexSet<DetailResolver>>,
     video_queue_for_feed: RwSignal<Vec<FeedPostCtx<DetailResolver>>>,
     current_idx: RwSignal<usize>,
+    /// number of posts evicted from the front of `video_queue`
+    #[prop(optional)]
+    queue_offset: Option<RwSignal<usize>>,
     #[prop(optional)] fetch_next_videos: Option<F>,
     recovering_state: RwSignal<bool>,
     queue_end: RwSignal<bool>,
@@ -81,7 +88,8 @@ pub fn ScrollingPostView<
     // Monitor current_idx and trigger hard refresh when reaching the end
     Effect::new(move |_| {
         let current = current_idx.get();
-        let queue_len = video_queue_for_feed.with(|vqf| vqf.len());
+        // slots of evicted posts are recycled, so the last key is the feed length
+        let queue_len = video_queue_for_feed.with(|vqf| vqf.last().map_or(0, |slot| slot.key + 1));
 
         // Check if we're at the last video (or second to last to be safe)
         if queue_len > 0 && current >= queue_len.saturating_sub(2) {
@@ -100,6 +108,22 @@ pub fn ScrollingPostView<
         }
     });
 
+    // Warm the next few videos into the service worker cache
+    if let Some(prefetch) = use_context::<VideoPrefetchCtx>() {
+        Effect::new(move |_| {
+            let current = current_idx.get();
+            let upcoming = video_queue_for_feed.with_untracked(|vqf| {
+                vqf.iter()
+                    .skip_while(|p| p.key <= current)
+                    .take(VIDEO_PREFETCH_AHEAD)
+                    .filter_map(|p| p.value.get())
+                    .map(|p| p.get_quick_post_details())
+                    .collect::<Vec<_>>()
+            });
+            prefetch.prefetch(upcoming);
+        });
+    }
+
     let var_name = view! {
         <div class="overflow-hidden overflow-y-auto w-full h-full">
             <div
@@ -144,7 +168,8 @@ pub fn ScrollingPostView<
                                 current_idx.set(queue_idx);
 
                                 // Trigger fetch if needed (without recursive calls)
-                                let queue_len = video_queue.with_untracked(|q| q.len());
+                                let queue_len = video_queue.with_untracked(|q| q.len())
+                                    + queue_offset.map(|o| o.get_untracked()).unwrap_or_default();
                                 let remaining = queue_len.saturating_sub(queue_idx);
 
                                 if remaining <= threshold_trigger_fetch {
@@ -178,7 +203,7 @@ pub fn ScrollingPostView<
                         view! {
                             <div node_ref=container_ref class="w-full h-full snap-always snap-end" class:hidden=move || post.get().is_none()>
                                 <Show when=show_video>
-                                    <BgView video_queue idx=queue_idx>
+                                    <BgView post idx=queue_idx>
                                         <VideoViewForQueue
                                             post
                                             current_idx
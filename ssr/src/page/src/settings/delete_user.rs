@@ -15,7 +15,8 @@ pub async fn initiate_delete_user(identity: DelegatedIdentityWire) -> Result<(),
     let response = client.delete(url).json(&body).send().await?;
 
     if response.status().is_success() {
-        Ok(())
+        // the account is gone, its keys and logins must not outlive it
+        auth::delete_user_data().await
     } else {
         Err(ServerFnError::ServerError(format!(
             "Delete user failed with status: {}",
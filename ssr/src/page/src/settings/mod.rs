@@ -12,11 +12,14 @@ use leptos_router::components::Redirect;
 use leptos_router::hooks::use_navigate;
 use leptos_router::{hooks::use_params, params::Params};
 use state::canisters::auth_state;
+use utils::i18n::{use_i18n, Locale};
 use utils::mixpanel::mixpanel_events::*;
 use utils::send_wrap;
+use utils::t;
 use yral_canisters_common::utils::profile::ProfileDetails;
 
 mod delete_user;
+mod sessions;
 
 #[derive(Params, PartialEq, Clone)]
 struct SettingsParams {
@@ -46,7 +49,7 @@ fn MenuItem(
 fn MenuFooter() -> impl IntoView {
     view! {
         <div class="flex flex-col gap-4 items-center pt-10 pb-8 w-full">
-            <span class="text-sm text-white/50">Follow us on</span>
+            <span class="text-sm text-white/50">{t!("follow-us-on")}</span>
             <div class="flex flex-row gap-4">
                 <Telegram />
                 <Discord />
@@ -104,7 +107,7 @@ fn ProfileLoaded(user_details: ProfileDetails) -> impl IntoView {
                 {user_details.display_name_or_fallback()}
             </span>
             <a class="text-primary-600 text-md" href="/profile/posts">
-                View Profile
+                {t!("view-profile")}
             </a>
         </div>
     }
@@ -139,6 +142,39 @@ fn EnableNotifications() -> impl IntoView {
     }
 }
 
+#[component]
+fn LanguageSelect() -> impl IntoView {
+    let i18n = use_i18n();
+
+    view! {
+        <div class="flex items-center justify-between w-full">
+            <div class="flex flex-row gap-4 items-center flex-1">
+                <Icon icon=icondata::AiGlobalOutlined attr:class="text-2xl flex-shrink-0" />
+                <span class="text-wrap">{t!("language")}</span>
+            </div>
+            <select
+                class="py-1 px-2 text-base text-white rounded-md bg-neutral-800"
+                on:change=move |ev| {
+                    if let Ok(locale) = event_target_value(&ev).parse::<Locale>() {
+                        i18n.set_locale(locale);
+                    }
+                }
+            >
+                {Locale::ALL
+                    .into_iter()
+                    .map(|locale| {
+                        view! {
+                            <option value=locale.code() selected=move || i18n.locale() == locale>
+                                {locale.native_name()}
+                            </option>
+                        }
+                    })
+                    .collect_view()}
+            </select>
+        </div>
+    }
+}
+
 #[component]
 fn DeleteAccountPopup(show_delete_popup: RwSignal<bool>) -> impl IntoView {
     let auth = auth_state();
@@ -189,14 +225,14 @@ fn DeleteAccountPopup(show_delete_popup: RwSignal<bool>) -> impl IntoView {
                         <Icon attr::class="w-4 h-4" icon=icondata::ChCross />
                     </button>
 
-                    <h2 class="text-lg font-bold mb-4 text-center">"Delete your account"</h2>
+                    <h2 class="text-lg font-bold mb-4 text-center">{t!("delete-your-account")}</h2>
 
                     <p class="text-sm text-neutral-300 mb-6 text-center">
-                        <span class="font-medium">Are you sure you want to delete your account?</span>
+                        <span class="font-medium">{t!("delete-account-confirm")}</span>
                         <br/><br/>
-                        This action is permanent and cannot be undone.
+                        {t!("delete-account-permanent")}
                         <br/><br/>
-                        "All your data — including your Bitcoin and token balances — will be permanently removed from the platform."
+                        {t!("delete-account-data-removed")}
                     </p>
 
                     <div class="flex justify-center gap-4">
@@ -205,7 +241,7 @@ fn DeleteAccountPopup(show_delete_popup: RwSignal<bool>) -> impl IntoView {
                             on:click=move |_| show_delete_popup.set(false)
                             disabled=move || is_deleting.get()
                         >
-                            "No, take me back"
+                            {t!("delete-account-cancel")}
                         </button>
                         <button
                             class="flex-1 px-4 py-2 rounded-md bg-red-600 hover:bg-red-700 text-white text-sm font-semibold disabled:opacity-50 flex items-center justify-center gap-2"
@@ -217,10 +253,10 @@ fn DeleteAccountPopup(show_delete_popup: RwSignal<bool>) -> impl IntoView {
                         >
                             <Show
                                 when=move || is_deleting.get()
-                                fallback=|| "Yes, Delete"
+                                fallback=t!("delete-account-yes")
                             >
                                 <div class="w-4 h-4 rounded-full border-2 border-white border-solid animate-spin border-t-transparent"></div>
-                                "Deleting..."
+                                {t!("deleting")}
                             </Show>
                         </button>
                     </div>
@@ -242,7 +278,7 @@ fn DeleteAccount(show_popup: RwSignal<bool>) -> impl IntoView {
         >
             <div class="flex flex-row gap-4 items-center flex-1">
                 <Icon icon=icondata::RiDeleteBinSystemLine attr:class="text-2xl flex-shrink-0" />
-                <span class="text-wrap">Delete account</span>
+                <span class="text-wrap">{t!("delete-account")}</span>
             </div>
             <Icon attr:class="text-2xl flex-shrink-0 hover:text-primary-600 transition-colors cursor-pointer" icon=icondata::AiRightOutlined />
         </button>
@@ -309,13 +345,17 @@ pub fn Settings() -> impl IntoView {
                             <TitleText justify_center=false>
                                 <div class="flex flex-row justify-between">
                                     <BackButton fallback="/menu".to_string() />
-                                    <span class="text-2xl font-bold">Settings</span>
+                                    <span class="text-2xl font-bold">{t!("settings")}</span>
                                     <div></div>
                                 </div>
                             </TitleText>
                         </div>
                         <div class="flex flex-col gap-8 py-12 px-8 w-full text-lg">
                             <EnableNotifications />
+                            <LanguageSelect />
+                            <Show when=move || is_auth>
+                                <sessions::ActiveSessions />
+                            </Show>
                             <DeleteAccount show_popup />
                         </div>
                         <MenuFooter />
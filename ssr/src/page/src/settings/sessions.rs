@@ -0,0 +1,117 @@
+use auth::{list_sessions, revoke_other_sessions, revoke_session, SessionInfo};
+use leptos::prelude::*;
+use leptos_icons::*;
+use utils::{
+    i18n::{use_i18n, I18n},
+    send_wrap, t,
+    time::get_day_month,
+};
+use web_time::Duration;
+use yral_canisters_common::utils::time::current_epoch;
+
+fn last_seen_label(i18n: I18n, last_seen_ms: u64) -> String {
+    let last_seen = Duration::from_millis(last_seen_ms);
+    if current_epoch().saturating_sub(last_seen) < Duration::from_secs(10 * 60) {
+        i18n.tr("active-now", &[])
+    } else {
+        let date = get_day_month(last_seen.as_secs(), i18n.locale());
+        i18n.tr("last-active", &[("date", date)])
+    }
+}
+
+#[component]
+fn SessionRow(
+    session: SessionInfo,
+    revoke: Action<String, Result<(), ServerFnError>>,
+) -> impl IntoView {
+    let SessionInfo {
+        id,
+        device,
+        ip,
+        last_seen_ms,
+        current,
+        ..
+    } = session;
+    let i18n = use_i18n();
+    let details = move || {
+        [Some(last_seen_label(i18n, last_seen_ms)), ip.clone()]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(" · ")
+    };
+
+    view! {
+        <div class="flex flex-row gap-4 justify-between items-center w-full">
+            <div class="flex flex-col">
+                <span class="text-base">
+                    {device}
+                    <Show when=move || current>
+                        <span class="text-sm text-primary-600">" " {t!("this-device")}</span>
+                    </Show>
+                </span>
+                <span class="text-sm text-white/50">{details}</span>
+            </div>
+            <Show when=move || !current>
+                <button
+                    class="text-sm text-red-500 hover:text-red-400 disabled:opacity-50"
+                    disabled=move || revoke.pending().get()
+                    on:click={
+                        let id = id.clone();
+                        move |_| {
+                            revoke.dispatch(id.clone());
+                        }
+                    }
+                >
+                    {t!("log-out")}
+                </button>
+            </Show>
+        </div>
+    }
+}
+
+/// Devices the user is logged in on, each of which can be logged out remotely
+#[component]
+pub fn ActiveSessions() -> impl IntoView {
+    let revoke =
+        Action::new(move |session_id: &String| send_wrap(revoke_session(session_id.clone())));
+    let revoke_others = Action::new(move |&()| send_wrap(revoke_other_sessions()));
+    let sessions = Resource::new(
+        move || (revoke.version().get(), revoke_others.version().get()),
+        |_| send_wrap(list_sessions()),
+    );
+
+    view! {
+        <div class="flex flex-col gap-4 w-full">
+            <div class="flex flex-row gap-4 items-center">
+                <Icon icon=icondata::AiLaptopOutlined attr:class="text-2xl flex-shrink-0" />
+                <span class="text-wrap">{t!("active-sessions")}</span>
+            </div>
+            <Suspense>
+                {move || Suspend::new(async move {
+                    let sessions = sessions.await.unwrap_or_default();
+                    let has_others = sessions.iter().any(|session| !session.current);
+                    view! {
+                        <div class="flex flex-col gap-3 pl-10">
+                            {sessions
+                                .into_iter()
+                                .map(|session| view! { <SessionRow session revoke /> })
+                                .collect_view()}
+                            <Show when=move || has_others>
+                                <button
+                                    class="self-start text-sm text-red-500 hover:text-red-400 disabled:opacity-50"
+                                    disabled=move || revoke_others.pending().get()
+                                    on:click=move |_| {
+                                        revoke_others.dispatch(());
+                                    }
+                                >
+                                    {t!("log-out-other-sessions")}
+                                </button>
+                            </Show>
+                        </div>
+                    }
+                })}
+            </Suspense>
+        </div>
+    }
+}
@@ -8,6 +8,7 @@ use leptos_router::components::Redirect;
 use leptos_router::hooks::use_params;
 use leptos_router::params::Params;
 use state::canisters::auth_state;
+use utils::i18n::use_i18n;
 use utils::send_wrap;
 use utils::web::copy_to_clipboard;
 use utils::UsernameOrPrincipal;
@@ -72,6 +73,7 @@ fn TokenInfoInner(
     key_principal: Option<Principal>,
     is_user_principal: bool,
 ) -> impl IntoView {
+    let i18n = use_i18n();
     let meta_c1 = meta.clone();
     let meta_c = meta.clone();
     let detail_toggle = RwSignal::new(false);
@@ -135,9 +137,10 @@ fn TokenInfoInner(
                                         .balance
                                         .clone()
                                         .map(|balance| {
+                                            let balance = balance.humanize_float_truncate_to_dp(8);
                                             view! {
                                                 <span class="font-bold">
-                                                    {format!("{} ", balance.humanize_float_truncate_to_dp(8))}
+                                                    {move || format!("{} ", i18n.locale().format_decimal(&balance))}
                                                 </span>
                                                 <span>{meta_c1.symbol.clone()}</span>
                                             }
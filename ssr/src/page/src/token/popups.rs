@@ -1,6 +1,7 @@
 use component::{buttons::GradientLinkButton, overlay::ActionTrackerPopup};
 use leptos::{either::Either, prelude::*};
 use leptos_icons::*;
+use utils::i18n::{format::LocalizedTokenBalance, use_i18n};
 use yral_canisters_common::utils::token::balance::TokenBalance;
 
 #[component]
@@ -61,16 +62,22 @@ fn TokenTransferSuccessPopup(
     #[prop(into)] token_name: String,
     amount: TokenBalance,
 ) -> impl IntoView {
-    let amount_str = amount.humanize_float();
+    let i18n = use_i18n();
     view! {
         <SuccessPopup
             img=|| view! { <img src="/img/hotornot/tick.webp" class="max-w-44" /> }
             text=move || {
-                format!("You’ve successfully sent {amount_str} {token_name} to your wallet.")
+                i18n.tr(
+                    "token-sent",
+                    &[
+                        ("amount", amount.humanize_float_localized(i18n.locale())),
+                        ("token", token_name.clone()),
+                    ],
+                )
             }
 
             previous_link="/wallet"
-            previous_text="Back to wallet"
+            previous_text=i18n.tr_untracked("back-to-wallet", &[])
         />
     }
 }
@@ -93,7 +100,7 @@ fn TokenTransferErrorPopup(
             }
 
             previous_link="/wallet"
-            previous_text="Back to wallet"
+            previous_text=use_i18n().tr_untracked("back-to-wallet", &[])
             close_popup
         />
     }
@@ -11,6 +11,7 @@ use leptos_router::components::Redirect;
 use leptos_router::hooks::use_params;
 use server_fn::codec::Json;
 use state::canisters::{auth_state, unauth_canisters};
+use utils::i18n::use_i18n;
 use utils::mixpanel::mixpanel_events::*;
 use utils::send_wrap;
 use utils::{event_streaming::events::TokensTransferred, web::paste_from_clipboard};
@@ -229,7 +230,9 @@ fn TokenTransferInner(
     } else {
         "Enter destination principal"
     };
-    let formatted_balance = balance.humanize_float_truncate_to_dp(if is_btc { 5 } else { 2 });
+    let i18n = use_i18n();
+    let balance = balance.humanize_float_truncate_to_dp(if is_btc { 5 } else { 2 });
+    let formatted_balance = move || i18n.locale().format_decimal(&balance);
 
     Either::Right(view! {
         <div class="flex flex-col gap-4 w-dvw min-h-dvh bg-neutral-950">
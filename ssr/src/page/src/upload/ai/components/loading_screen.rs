@@ -1,6 +1,7 @@
 use component::back_btn::BackButton;
 use leptos::prelude::*;
 use leptos_icons::*;
+use utils::{i18n::use_i18n, t};
 use videogen_common::ProviderInfo;
 
 #[component]
@@ -9,6 +10,7 @@ pub fn VideoGenerationLoadingScreen(
     provider: ProviderInfo,
     loading_state: String,
 ) -> impl IntoView {
+    let i18n = use_i18n();
     let loading_state_1 = loading_state.clone();
     let loading_state_2 = loading_state;
     view! {
@@ -18,7 +20,7 @@ pub fn VideoGenerationLoadingScreen(
                 <div class="text-white">
                     <BackButton fallback="/upload-options".to_string() />
                 </div>
-                <h1 class="text-lg font-bold text-white">"Generate Video"</h1>
+                <h1 class="text-lg font-bold text-white">{t!("generate-video")}</h1>
                 <div class="w-6"></div> // Spacer for centering
             </div>
 
@@ -100,18 +102,20 @@ pub fn VideoGenerationLoadingScreen(
                     <div class="text-center">
                         <h2 class="text-xl font-bold text-white mb-2">
                             {move || {
-                                match loading_state_1.as_str() {
-                                    "uploading" => "Uploading video",
-                                    _ => "Generating video"
-                                }
+                                let key = match loading_state_1.as_str() {
+                                    "uploading" => "uploading-video",
+                                    _ => "generating-video"
+                                };
+                                i18n.tr(key, &[])
                             }}
                         </h2>
                         <p class="text-sm text-neutral-400">
                             {move || {
-                                match loading_state_2.as_str() {
-                                    "uploading" => "Almost there...",
-                                    _ => "This may take a few minutes..."
-                                }
+                                let key = match loading_state_2.as_str() {
+                                    "uploading" => "almost-there",
+                                    _ => "may-take-minutes"
+                                };
+                                i18n.tr(key, &[])
                             }}
                         </p>
                     </div>
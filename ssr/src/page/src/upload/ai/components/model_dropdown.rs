@@ -1,7 +1,11 @@
 use leptos::prelude::*;
 use leptos_icons::*;
 use state::canisters::auth_state;
-use utils::mixpanel::mixpanel_events::{MixPanelEvent, MixpanelGlobalProps};
+use utils::{
+    i18n::{use_i18n, I18n},
+    mixpanel::mixpanel_events::{MixPanelEvent, MixpanelGlobalProps},
+    t,
+};
 use videogen_common::ProviderInfo;
 
 #[component]
@@ -12,6 +16,7 @@ pub fn ModelDropdown(
 ) -> impl IntoView {
     let auth = auth_state();
     let ev_ctx = auth.event_ctx();
+    let i18n = use_i18n();
 
     let providers = StoredValue::new(providers);
 
@@ -20,20 +25,20 @@ pub fn ModelDropdown(
         selected_provider
             .get()
             .map(|p| p.name.clone())
-            .unwrap_or_else(|| "Select Model".to_string())
+            .unwrap_or_else(|| i18n.tr("select-model", &[]))
     });
     let provider_description = Signal::derive(move || {
         selected_provider
             .get()
             .map(|p| p.description.clone())
-            .unwrap_or_else(|| "Choose a model to generate video".to_string())
+            .unwrap_or_else(|| i18n.tr("choose-model", &[]))
     });
     let provider_icon =
         Signal::derive(move || selected_provider.get().and_then(|p| p.model_icon.clone()));
 
     view! {
         <div class="relative w-full">
-            <label class="block text-sm font-medium text-white mb-2">Model</label>
+            <label class="block text-sm font-medium text-white mb-2">{t!("model")}</label>
 
             // Selected model display
             <div
@@ -79,7 +84,7 @@ pub fn ModelDropdown(
                             let provider_id = provider.id.clone();
                             let provider_name = provider.name.clone();
                             let provider_description = provider.description.clone();
-                            let provider_duration = format_duration(provider.default_duration);
+                            let provider_duration = format_duration(i18n, provider.default_duration);
                             let _provider_cost_usd_cents = provider.cost.usd_cents;
                             let provider_icon = provider.model_icon.clone();
                             let is_available = provider.is_available;
@@ -160,7 +165,7 @@ pub fn ModelDropdown(
                                                         view! {
                                                             // Coming Soon badge
                                                             <div class="flex items-center gap-1 px-2 py-1 rounded bg-neutral-700/50">
-                                                                <span class="text-neutral-500 text-xs font-medium">"Coming Soon"</span>
+                                                                <span class="text-neutral-500 text-xs font-medium">{t!("coming-soon")}</span>
                                                             </div>
                                                         }.into_any()
                                                     }
@@ -179,15 +184,15 @@ pub fn ModelDropdown(
 }
 
 // Helper function to format duration display
-fn format_duration(duration_seconds: Option<u8>) -> String {
+fn format_duration(i18n: I18n, duration_seconds: Option<u8>) -> String {
     match duration_seconds {
         Some(seconds) => {
             if seconds < 60 {
-                format!("{seconds} Sec")
+                i18n.tr("duration-seconds", &[("count", seconds.to_string())])
             } else {
-                format!("{} Min", seconds / 60)
+                i18n.tr("duration-minutes", &[("count", (seconds / 60).to_string())])
             }
         }
-        None => "Variable".to_string(), // For providers where duration depends on input
+        None => i18n.tr("duration-variable", &[]), // For providers where duration depends on input
     }
 }
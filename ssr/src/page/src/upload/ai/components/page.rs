@@ -6,14 +6,16 @@ use crate::upload::ai::helpers::{
 use crate::upload::ai::server::upload_ai_video_from_url;
 use crate::upload::ai::types::VideoGenerationParams;
 use crate::upload::ai::types::{UploadActionParams, AI_VIDEO_PARAMS_STORE};
-use auth::delegate_short_lived_identity;
+use auth::{delegate_identity_with_policy, DelegationPolicy};
 use codee::string::JsonSerdeCodec;
 use component::notification_nudge::NotificationNudge;
 use leptos::prelude::*;
 use leptos_meta::Title;
 use leptos_use::storage::use_local_storage;
-use state::canisters::auth_state;
+use state::{app_state::AppState, canisters::auth_state};
 use utils::mixpanel::mixpanel_events::{MixPanelEvent, MixpanelGlobalProps};
+use utils::t;
+use videogen_common::TokenType;
 
 #[component]
 pub fn UploadAiPostPage() -> impl IntoView {
@@ -66,7 +68,12 @@ pub fn UploadAiPostPage() -> impl IntoView {
                     )
                     .map_err(|e| e.to_string())?;
 
-                    let delegated_identity = delegate_short_lived_identity(identity);
+                    // SATS are paid off-chain, DOLR through its ledger
+                    let ledger = (params.token_type == TokenType::Dolr)
+                        .then(|| consts::DOLR_AI_LEDGER_CANISTER.parse().unwrap());
+                    let policy =
+                        DelegationPolicy::video_generation(canisters.rate_limits().await.0, ledger);
+                    let delegated_identity = delegate_identity_with_policy(identity, &policy);
                     let result = execute_video_generation_with_identity_v2(
                         request,
                         delegated_identity,
@@ -136,8 +143,12 @@ pub fn UploadAiPostPage() -> impl IntoView {
                 // Get delegated identity within the Action
                 match auth.auth_cans().await {
                     Ok(canisters) => {
-                        let id = canisters.identity();
-                        let delegated_identity = delegate_short_lived_identity(id);
+                        let policy = DelegationPolicy::upload(
+                            canisters.user_canister(),
+                            canisters.user_post_service().await.0,
+                        );
+                        let delegated_identity =
+                            delegate_identity_with_policy(canisters.identity(), &policy);
 
                         // Call server function with delegated identity
                         match upload_ai_video_from_url(
@@ -200,8 +211,9 @@ pub fn UploadAiPostPage() -> impl IntoView {
         }
     });
 
+    let name = use_context::<AppState>().unwrap().name;
     view! {
-        <Title text="YRAL AI - Upload" />
+        <Title text=t!("ai-upload-title", name = name) />
         <NotificationNudge pop_up=notification_nudge />
         <div class="w-full h-full">
             <Show
@@ -1,6 +1,7 @@
 use component::buttons::HighlightedLinkButton;
 use leptos::prelude::*;
 use leptos_icons::*;
+use utils::t;
 
 #[component]
 pub fn PostUploadScreenAi(video_url: String) -> impl IntoView {
@@ -45,7 +46,7 @@ pub fn PostUploadScreenAi(video_url: String) -> impl IntoView {
                                 preload="metadata"
                                 src=video_url.clone()
                             >
-                                <p class="text-white p-4">"Your browser doesn't support video playback."</p>
+                                <p class="text-white p-4">{t!("video-unsupported")}</p>
                             </video>
                         </div>
                     </div>
@@ -54,10 +55,10 @@ pub fn PostUploadScreenAi(video_url: String) -> impl IntoView {
                     <div class="w-full max-w-[321px] flex flex-col items-center gap-[30px]">
                     <div class="flex flex-col items-center gap-2.5 text-center">
                         <h1 class="font-semibold text-[20px] text-neutral-50 font-['Kumbh_Sans']">
-                            "AI Video generated Successfully!"
+                            {t!("ai-video-generated")}
                         </h1>
                         <p class="text-[16px] text-neutral-400 font-['Kumbh_Sans'] font-normal w-[303px] leading-[1.4]">
-                            "Your video is being processed and will appear in \"Your Videos\" under your profile shortly. Happy scrolling!"
+                            {t!("ai-video-processing")}
                         </p>
                     </div>
 
@@ -68,7 +69,7 @@ pub fn PostUploadScreenAi(video_url: String) -> impl IntoView {
                             classes="w-full h-[45px] px-5 py-3".to_string()
                             href="/".to_string()
                         >
-                            "Done"
+                            {t!("done")}
                         </HighlightedLinkButton>
                     </div>
                 </div>
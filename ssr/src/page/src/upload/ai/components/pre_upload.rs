@@ -14,10 +14,12 @@ use leptos_router::hooks::use_location;
 use leptos_use::{use_cookie_with_options, UseCookieOptions};
 use state::canisters::auth_state;
 use utils::event_streaming::events::VideoUploadInitiated;
+use utils::i18n::use_i18n;
 use utils::mixpanel::mixpanel_events::{
     BottomNavigationCategory, MixPanelEvent, MixpanelGlobalProps,
 };
 use utils::send_wrap;
+use utils::t;
 use videogen_common::{ProviderInfo, TokenType};
 use wasm_bindgen::{prelude::*, JsCast};
 use yral_canisters_common::utils::token::balance::TokenBalance;
@@ -72,8 +74,8 @@ fn ImageUploadSection(
         <Show when=move || selected_provider.get().map(|p| p.supports_image).unwrap_or(false)>
             <div class="w-full">
                 <div class="flex items-center gap-2 mb-2">
-                    <label class="block text-sm font-medium text-white">Image</label>
-                    <span class="text-xs text-neutral-400">(Optional)</span>
+                    <label class="block text-sm font-medium text-white">{t!("image")}</label>
+                    <span class="text-xs text-neutral-400">{t!("optional")}</span>
                     <Icon icon=icondata::AiInfoCircleOutlined attr:class="text-neutral-400 text-sm" />
                 </div>
 
@@ -91,7 +93,7 @@ fn ImageUploadSection(
                             fallback=move || view! {
                                 <div class="flex flex-col items-center gap-3">
                                     <Icon icon=icondata::AiPictureOutlined attr:class="text-neutral-500 text-3xl" />
-                                    <span class="text-neutral-500 text-sm">"Click to upload an image"</span>
+                                    <span class="text-neutral-500 text-sm">{t!("click-to-upload-image")}</span>
                                 </div>
                             }
                         >
@@ -146,8 +148,8 @@ fn AudioUploadSection(
         <Show when=move || selected_provider.get().map(|p| p.supports_audio_input).unwrap_or(false)>
             <div class="w-full">
                 <div class="flex items-center gap-2 mb-2">
-                    <label class="block text-sm font-medium text-white">Audio</label>
-                    <span class="text-xs text-neutral-400">(Required for Talking Head)</span>
+                    <label class="block text-sm font-medium text-white">{t!("audio")}</label>
+                    <span class="text-xs text-neutral-400">{t!("audio-required")}</span>
                     <Icon icon=icondata::AiInfoCircleOutlined attr:class="text-neutral-400 text-sm" />
                 </div>
 
@@ -165,13 +167,13 @@ fn AudioUploadSection(
                             fallback=move || view! {
                                 <div class="flex flex-col items-center gap-3">
                                     <Icon icon=icondata::AiAudioOutlined attr:class="text-neutral-500 text-3xl" />
-                                    <span class="text-neutral-500 text-sm">"Click to upload audio"</span>
+                                    <span class="text-neutral-500 text-sm">{t!("click-to-upload-audio")}</span>
                                 </div>
                             }
                         >
                             <div class="flex flex-col items-center gap-2">
                                 <Icon icon=icondata::AiAudioFilled attr:class="text-green-500 text-3xl" />
-                                <span class="text-green-500 text-sm">"Audio uploaded"</span>
+                                <span class="text-green-500 text-sm">{t!("audio-uploaded")}</span>
                             </div>
                         </Show>
                     </div>
@@ -186,12 +188,12 @@ fn AudioUploadSection(
 fn PromptSection(prompt_text: RwSignal<String>, character_count: Signal<usize>) -> impl IntoView {
     view! {
         <div class="w-full">
-            <label class="block text-sm font-medium text-white mb-2">Prompt</label>
+            <label class="block text-sm font-medium text-white mb-2">{t!("prompt")}</label>
             <div class="relative">
                 <textarea
                     class="w-full p-4 bg-neutral-900 border border-neutral-800 rounded-lg text-white placeholder:text-neutral-500 resize-none focus:outline-none focus:border-pink-400 transition-colors"
                     rows=6
-                    placeholder="Enter the Prompt here..."
+                    placeholder=t!("prompt-placeholder")
                     on:input=move |ev| {
                         let value = event_target_value(&ev);
                         if value.len() <= 500 {
@@ -222,9 +224,10 @@ fn CreditsSection(
     has_sufficient_balance: RwSignal<bool>,
     locked_rate_limit_status: RwSignal<Option<bool>>,
 ) -> impl IntoView {
+    let i18n = use_i18n();
     view! {
         <div class="flex flex-col gap-2">
-            <span class="text-sm font-medium text-neutral-300">Credits Required</span>
+            <span class="text-sm font-medium text-neutral-300">{t!("credits-required")}</span>
             <div class="flex items-center justify-between px-2.5 py-2 bg-neutral-900 border border-neutral-800 rounded-lg">
                 <div class="text-base font-semibold text-neutral-300">
                     <Suspense fallback=move || view! { <span>"..."</span> }>
@@ -235,7 +238,7 @@ fn CreditsSection(
                             // If no provider selected yet, show loading
                             let provider = match provider_opt {
                                 Some(p) => p,
-                                None => return view! { <span>"Loading..."</span> }.into_any(),
+                                None => return view! { <span>{t!("loading")}</span> }.into_any(),
                             };
 
                             // Check if user can use free generation and lock in the status
@@ -268,6 +271,7 @@ fn CreditsSection(
                                 TokenType::Dolr => TokenBalance::new(cost.into(), 8).humanize_float_truncate_to_dp(2),
                                 _ => "0".to_string(),
                             };
+                            let humanized = i18n.locale().format_decimal(&humanized);
 
                             if can_use_free {
                                 // Show 0 with strikethrough original price
@@ -299,7 +303,7 @@ fn CreditsSection(
             <Suspense fallback=move || view! {
                 <div class="flex items-center gap-2 text-xs text-neutral-400">
                     <Icon icon=icondata::AiInfoCircleOutlined attr:class="text-neutral-400 text-sm" />
-                    <span>"Current balance: Loading..."</span>
+                    <span>{t!("current-balance-loading")}</span>
                 </div>
             }>
                 {move || Suspend::new(async move {
@@ -316,7 +320,7 @@ fn CreditsSection(
                                     return view! {
                                         <div class="flex items-center gap-2 text-xs text-neutral-400">
                                             <Icon icon=icondata::AiInfoCircleOutlined attr:class="text-neutral-400 text-sm" />
-                                            <span>"Loading providers..."</span>
+                                            <span>{t!("loading-providers")}</span>
                                         </div>
                                     }.into_any()
                                 }
@@ -354,21 +358,22 @@ fn CreditsSection(
                                 view! {
                                     <div class="flex items-center gap-2 text-xs" style="color: #1ec981;">
                                         <Icon icon=icondata::AiInfoCircleOutlined attr:class="text-sm" attr:style="color: #1ec981;" />
-                                        <span>"Enjoy 1 free AI video per day. Use credits for more."</span>
+                                        <span>{t!("free-ai-video")}</span>
                                     </div>
                                 }.into_any()
                             } else {
                                 // Show regular balance for paid generation
+                                let locale = i18n.locale();
                                 let balance_text = match token_type {
                                     TokenType::Sats => {
-                                        let formatted_balance = balance.humanize_float_truncate_to_dp(0);
-                                        format!("Current balance: {formatted_balance} YRAL")
+                                        let formatted_balance = locale.format_decimal(&balance.humanize_float_truncate_to_dp(0));
+                                        i18n.tr("current-balance", &[("amount", formatted_balance), ("token", "YRAL".into())])
                                     },
                                     TokenType::Dolr => {
-                                        let formatted_balance = balance.humanize_float_truncate_to_dp(2);
-                                        format!("Current balance: {formatted_balance} DOLR")
+                                        let formatted_balance = locale.format_decimal(&balance.humanize_float_truncate_to_dp(2));
+                                        i18n.tr("current-balance", &[("amount", formatted_balance), ("token", "DOLR".into())])
                                     },
-                                    _ => "Current balance: Unknown token type".to_string(),
+                                    _ => i18n.tr("current-balance-unknown-token", &[]),
                                 };
                                 view! {
                                     <div class="flex items-center gap-2 text-xs text-neutral-400">
@@ -383,7 +388,7 @@ fn CreditsSection(
                             view! {
                                 <div class="flex items-center gap-2 text-xs text-neutral-400">
                                     <Icon icon=icondata::AiInfoCircleOutlined attr:class="text-neutral-400 text-sm" />
-                                    <span>{"Current balance: Error loading".to_string()}</span>
+                                    <span>{t!("current-balance-error")}</span>
                                 </div>
                             }.into_any()
                         }
@@ -399,6 +404,7 @@ pub fn PreUploadAiView(
     generate_action: Action<VideoGenerationParams, Result<String, String>>,
     set_stored_params: WriteSignal<VideoGenerationParams>,
 ) -> impl IntoView {
+    let i18n = use_i18n();
     // Form state
     // Use Resource to fetch providers from API
     let providers_resource = LocalResource::new(move || async move {
@@ -548,7 +554,7 @@ pub fn PreUploadAiView(
                 <div class="text-white">
                     <BackButton fallback="/upload-options".to_string() />
                 </div>
-                <h1 class="text-lg font-bold text-white">"Create AI Video"</h1>
+                <h1 class="text-lg font-bold text-white">{t!("create-ai-video")}</h1>
                 <div class="w-6"></div> // Spacer for centering
             </div>
 
@@ -559,9 +565,9 @@ pub fn PreUploadAiView(
                     // Provider Selection Dropdown
                     <Suspense fallback=move || view! {
                         <div class="w-full">
-                            <label class="block text-sm font-medium text-white mb-2">Model</label>
+                            <label class="block text-sm font-medium text-white mb-2">{t!("model")}</label>
                             <div class="flex items-center justify-center p-4 bg-neutral-900 border border-neutral-800 rounded-lg">
-                                <span class="text-neutral-400">Loading models...</span>
+                                <span class="text-neutral-400">{t!("loading-models")}</span>
                             </div>
                         </div>
                     }>
@@ -626,7 +632,7 @@ pub fn PreUploadAiView(
                         <Suspense
                             fallback=move || view! {
                                 <div class="w-full h-12 rounded-lg font-bold bg-gradient-to-r from-pink-500 to-purple-500 flex items-center justify-center text-white opacity-50">
-                                    "Loading..."
+                                    {t!("loading")}
                                 </div>
                             }
                         >
@@ -731,11 +737,12 @@ pub fn PreUploadAiView(
                                         })
                                     >
                                         {move || {
-                                            if generate_action.pending().get() {
-                                                "Generating & Uploading..."
+                                            let key = if generate_action.pending().get() {
+                                                "generating-and-uploading"
                                             } else {
-                                                "Generate & Upload Video"
-                                            }
+                                                "generate-and-upload"
+                                            };
+                                            i18n.tr(key, &[])
                                         }}
                                     </GradientButton>
                                 }
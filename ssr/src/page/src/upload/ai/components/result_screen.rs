@@ -1,6 +1,7 @@
 use component::{back_btn::BackButton, buttons::GradientButton};
 use leptos::prelude::*;
 use leptos_icons::*;
+use utils::t;
 
 #[component]
 pub fn VideoResultScreen(
@@ -15,7 +16,7 @@ pub fn VideoResultScreen(
                 <div class="text-white">
                     <BackButton fallback="/upload-options".to_string() />
                 </div>
-                <h1 class="text-lg font-bold text-white">"Generate Video"</h1>
+                <h1 class="text-lg font-bold text-white">{t!("generate-video")}</h1>
                 <div class="w-6"></div> // Spacer for centering
             </div>
 
@@ -32,14 +33,14 @@ pub fn VideoResultScreen(
                             preload="metadata"
                             src=video_url.clone()
                         >
-                            <p class="text-white p-4">"Your browser doesn't support video playback."</p>
+                            <p class="text-white p-4">{t!("video-unsupported")}</p>
                         </video>
                     </div>
 
                     // Status text
                     <div class="text-center">
-                        <h2 class="text-xl font-bold text-white mb-2">"Video generated successfully!"</h2>
-                        <p class="text-sm text-neutral-400">"Your AI video is ready. You can re-generate or upload it."</p>
+                        <h2 class="text-xl font-bold text-white mb-2">{t!("video-generated")}</h2>
+                        <p class="text-sm text-neutral-400">{t!("video-ready")}</p>
                     </div>
 
                     // Action buttons
@@ -53,7 +54,7 @@ pub fn VideoResultScreen(
                             }
                         >
                             <Icon icon=icondata::AiReloadOutlined attr:class="text-lg" />
-                            "Re-generate"
+                            {t!("regenerate")}
                         </button>
 
                         // Upload button (primary action)
@@ -66,7 +67,7 @@ pub fn VideoResultScreen(
                         >
                             <div class="flex items-center justify-center gap-2">
                                 <Icon icon=icondata::AiUploadOutlined attr:class="text-lg" />
-                                "Upload"
+                                {t!("upload")}
                             </div>
                         </GradientButton>
                     </div>
@@ -74,15 +75,15 @@ pub fn VideoResultScreen(
                     // Video info (optional)
                     <div class="mt-6 p-4 bg-neutral-900 rounded-lg">
                         <div class="flex items-center justify-between text-sm">
-                            <span class="text-neutral-400">"Duration:"</span>
-                            <span class="text-white">"Auto-detected"</span>
+                            <span class="text-neutral-400">{t!("duration")}</span>
+                            <span class="text-white">{t!("auto-detected")}</span>
                         </div>
                         <div class="flex items-center justify-between text-sm mt-2">
-                            <span class="text-neutral-400">"Format:"</span>
+                            <span class="text-neutral-400">{t!("video-format")}</span>
                             <span class="text-white">"MP4"</span>
                         </div>
                         <div class="flex items-center justify-between text-sm mt-2">
-                            <span class="text-neutral-400">"Quality:"</span>
+                            <span class="text-neutral-400">{t!("video-quality")}</span>
                             <span class="text-white">"HD"</span>
                         </div>
                     </div>
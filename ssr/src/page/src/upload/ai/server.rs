@@ -3,7 +3,7 @@ use consts::{OFF_CHAIN_AGENT_URL, UPLOAD_URL};
 use leptos::prelude::*;
 use leptos::server_fn::codec::Json;
 use serde_json::json;
-use utils::host::show_preview_component;
+use state::app_type::is_preview_deployment;
 use videogen_common::{ProviderInfo, VideoGenClient};
 use yral_types::delegated_identity::DelegatedIdentityWire;
 
@@ -150,7 +150,7 @@ pub async fn upload_ai_video_from_url(
 #[server(endpoint = "fetch_video_providers", input = Json, output = Json)]
 pub async fn fetch_video_providers() -> Result<Vec<ProviderInfo>, ServerFnError> {
     let client = VideoGenClient::new(OFF_CHAIN_AGENT_URL.clone());
-    let is_preview = show_preview_component();
+    let is_preview = is_preview_deployment();
 
     // Use get_providers_all for preview mode to include test models
     let providers_result = if is_preview {
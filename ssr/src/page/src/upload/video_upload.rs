@@ -1,5 +1,5 @@
 use super::UploadParams;
-use auth::delegate_short_lived_identity;
+use auth::{delegate_identity_with_policy, DelegationPolicy};
 use component::buttons::HighlightedLinkButton;
 use component::modal::Modal;
 use component::notification_nudge::NotificationNudge;
@@ -384,8 +384,11 @@ pub fn VideoUploader(
             let uid_value = uid.get_untracked()?;
 
             let canisters = auth.auth_cans().await.ok()?;
-            let id = canisters.identity();
-            let delegated_identity = delegate_short_lived_identity(id);
+            let policy = DelegationPolicy::upload(
+                canisters.user_canister(),
+                canisters.user_post_service().await.0,
+            );
+            let delegated_identity = delegate_identity_with_policy(canisters.identity(), &policy);
 
             let res: std::result::Result<reqwest::Response, ServerFnError> = {
                 let client = reqwest::Client::new();
@@ -22,7 +22,7 @@ use state::app_state::AppState;
 use state::canisters::{auth_state, unauth_canisters};
 use tokens::TokenList;
 use utils::notifications::get_device_registeration_token;
-use utils::{send_wrap, UsernameOrPrincipal};
+use utils::{send_wrap, t, UsernameOrPrincipal};
 use yral_canisters_common::utils::profile::ProfileDetails;
 use yral_metadata_client::MetadataClient;
 
@@ -59,7 +59,7 @@ fn ProfileCard(
             <Show when=move || !is_connected.get() && is_own_account>
                 <ConnectLogin
                     show_login
-                    login_text="Login to claim YRAL"
+                    login_text=t!("login-to-claim")
                     cta_location="wallet"
                     redirect_to="/wallet"
                 />
@@ -40,6 +40,7 @@ use leptos_icons::*;
 use leptos_router::hooks::use_navigate;
 use state::canisters::{auth_state, unauth_canisters};
 use utils::host::get_host;
+use utils::i18n::use_i18n;
 use utils::mixpanel::mixpanel_events::*;
 use utils::send_wrap;
 use yral_canisters_common::utils::token::balance::TokenBalance;
@@ -478,6 +479,7 @@ pub fn FastWalletCard(
 ) -> impl IntoView {
     let _ = user_canister;
     let _ = token_type;
+    let i18n = use_i18n();
 
     let TokenDisplayInfo {
         name,
@@ -545,7 +547,7 @@ pub fn FastWalletCard(
                                     // show error text if balance fails to load for whatever reason
                                     // error logs are captured by sentry
                                     <div class="text-lg font-medium" class=("text-red-500", err)>
-                                        {text}
+                                        {move || i18n.locale().format_decimal(&text)}
                                     </div>
                                 }
                             })}
@@ -592,10 +594,7 @@ pub fn FastWalletCard(
 }
 
 #[component]
-fn WalletCardOptions(
-    pop_up: WriteSignal<bool>,
-    share_link: WriteSignal<String>,
-) -> impl IntoView {
+fn WalletCardOptions(pop_up: WriteSignal<bool>, share_link: WriteSignal<String>) -> impl IntoView {
     let WalletCardOptionsContext {
         is_utility_token,
         root,
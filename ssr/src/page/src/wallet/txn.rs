@@ -3,7 +3,7 @@ use leptos::html;
 use leptos::prelude::*;
 use leptos_icons::Icon;
 use leptos_router::hooks::use_params;
-use utils::time::parse_ns_to_datetime;
+use utils::{i18n::use_i18n, time::parse_ns_to_datetime};
 use yral_canisters_common::utils::transaction::{TxnDirection, TxnInfoType, TxnInfoWallet};
 
 fn direction_to_icon(direction: TxnDirection) -> &'static icondata_core::IconData {
@@ -27,16 +27,21 @@ pub fn TxnView(
     symbol: String,
 ) -> impl IntoView {
     let params = use_params::<TokenKeyParam>();
+    let i18n = use_i18n();
     let direction = TxnDirection::from(info.tag);
-    let bal_res = format!(
-        "{}{}",
-        match direction {
-            TxnDirection::Added => "+",
-            TxnDirection::Deducted => "-",
-            TxnDirection::Transaction => "",
-        },
-        info.amount.humanize_float_truncate_to_dp(8)
-    );
+    let amount = info.amount.humanize_float_truncate_to_dp(8);
+    let bal_res = move || {
+        format!(
+            "{}{}",
+            match direction {
+                TxnDirection::Added => "+",
+                TxnDirection::Deducted => "-",
+                TxnDirection::Transaction => "",
+            },
+            i18n.locale().format_decimal(&amount)
+        )
+    };
+    let timestamp = info.timestamp;
 
     view! {
         <div
@@ -150,9 +155,9 @@ pub fn TxnView(
                         TxnDirection::Added => "text-green-600 font-semibold",
                         _ => "text-white font-semibold",
                     }
-                }>{format!("{bal_res} {symbol}")}</span>
+                }>{move || format!("{} {symbol}", bal_res())}</span>
                 <span class="text-sm text-white/50 md:text-md">
-                    {parse_ns_to_datetime(info.timestamp).ok()}
+                    {move || parse_ns_to_datetime(timestamp, i18n.locale()).ok()}
                 </span>
             </div>
         </div>
@@ -1,7 +1,7 @@
 use component::auth_providers::yral::YralAuthMessage;
 use component::loading::Loading;
 use leptos::prelude::*;
-use leptos_router::hooks::use_query;
+use leptos_router::hooks::{use_params_map, use_query};
 use leptos_router::params::Params;
 
 use openidconnect::CsrfToken;
@@ -10,14 +10,21 @@ use server_fn::codec::Json;
 use utils::route::go_to_root;
 use utils::types::NewIdentity;
 
+/// Provider used by the redirect route registered before per-provider routes existed
+const LEGACY_REDIRECT_PROVIDER: &str = "yral";
+
 #[server(input = Json, output = Json)]
-async fn perform_yral_oauth(oauth: OAuthQuery) -> Result<NewIdentity, ServerFnError> {
-    use auth::server_impl::yral::perform_yral_auth_impl;
-    use auth::server_impl::yral::YralOAuthClient;
+async fn perform_oauth(
+    provider_id: String,
+    oauth: OAuthQuery,
+) -> Result<NewIdentity, ServerFnError> {
+    use auth::server_impl::provider::{IdentityProvider, IdentityProviders};
 
-    let oauth2: YralOAuthClient = expect_context();
-    let (id, fallback_username, email) =
-        perform_yral_auth_impl(oauth.state, oauth.code, oauth2).await?;
+    let providers: IdentityProviders = expect_context();
+    let (id, fallback_username, email) = providers
+        .get(&provider_id)?
+        .perform_auth(oauth.state, oauth.code)
+        .await?;
     Ok(NewIdentity {
         id_wire: id,
         fallback_username,
@@ -59,8 +66,8 @@ pub fn IdentitySender(identity_res: YralAuthMessage) -> impl IntoView {
     }
 }
 
-async fn handle_oauth_query(oauth_query: OAuthQuery) -> YralAuthMessage {
-    let delegated = perform_yral_oauth(oauth_query)
+async fn handle_oauth_query(provider_id: String, oauth_query: OAuthQuery) -> YralAuthMessage {
+    let delegated = perform_oauth(provider_id, oauth_query)
         .await
         .map_err(|e| e.to_string())?;
     Ok(delegated)
@@ -90,16 +97,27 @@ struct OAuthState {
     pub client_redirect_uri: Option<String>,
 }
 
+/// Handles `/auth/{provider}/redirect`, and the legacy `/auth/google_redirect` for Yral Auth
 #[component]
-pub fn YralAuthRedirectHandler() -> impl IntoView {
+pub fn AuthRedirectHandler() -> impl IntoView {
     let query = use_query::<OAuthQuery>();
-    let identity_resource = Resource::new_blocking(query, |query_res| async move {
-        let Ok(oauth_query) = query_res else {
-            return Err("Invalid query".to_string());
-        };
+    let params = use_params_map();
+    let provider_id = move || {
+        params.with(|p| {
+            p.get("provider")
+                .unwrap_or_else(|| LEGACY_REDIRECT_PROVIDER.to_string())
+        })
+    };
+    let identity_resource = Resource::new_blocking(
+        move || (provider_id(), query()),
+        |(provider_id, query_res)| async move {
+            let Ok(oauth_query) = query_res else {
+                return Err("Invalid query".to_string());
+            };
 
-        handle_oauth_query(oauth_query).await
-    });
+            handle_oauth_query(provider_id, oauth_query).await
+        },
+    );
 
     view! {
         <Loading text="Logging out...".to_string()>
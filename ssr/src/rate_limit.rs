@@ -0,0 +1,138 @@
+//! Token bucket rate limits for expensive server functions
+//!
+//! buckets live in the KV store, so limits hold across replicas
+use std::{net::SocketAddr, time::Duration};
+
+use auth::server_impl::{
+    principal_from_refresh_cookie,
+    store::{KVStore, TokenBucket},
+};
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use http::{header, StatusCode};
+use state::server::AppState;
+use utils::client_ip::client_ip;
+
+const RATE_LIMIT_PREFIX: &str = "rate-limit";
+
+/// Who a limit is counted against
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum LimitKey {
+    /// The caller's principal, or their IP if they have no identity yet
+    Principal,
+    Ip,
+}
+
+struct EndpointPolicy {
+    /// Server function endpoint, without the `/api/` prefix or hash suffix
+    endpoint: &'static str,
+    key: LimitKey,
+    bucket: TokenBucket,
+}
+
+const fn per(capacity: u32, period: Duration) -> TokenBucket {
+    TokenBucket { capacity, period }
+}
+
+const MINUTE: Duration = Duration::from_secs(60);
+const HOUR: Duration = Duration::from_secs(60 * 60);
+
+const POLICIES: &[EndpointPolicy] = &[
+    EndpointPolicy {
+        // many users can share an IP, so this is kept generous
+        endpoint: "generate_anonymous_identity_if_required",
+        key: LimitKey::Ip,
+        bucket: per(30, Duration::from_secs(10 * 60)),
+    },
+    EndpointPolicy {
+        endpoint: "save_deferred_link",
+        key: LimitKey::Ip,
+        bucket: per(20, HOUR),
+    },
+    EndpointPolicy {
+        endpoint: "upload_ai_video_from_url",
+        key: LimitKey::Principal,
+        bucket: per(10, HOUR),
+    },
+    EndpointPolicy {
+        endpoint: "transfer_token_to_user_principal",
+        key: LimitKey::Principal,
+        bucket: per(10, MINUTE),
+    },
+    EndpointPolicy {
+        endpoint: "clear_sats",
+        key: LimitKey::Principal,
+        bucket: per(5, MINUTE),
+    },
+];
+
+fn policy_for(path: &str) -> Option<&'static EndpointPolicy> {
+    let fn_name = path.strip_prefix("/api/")?;
+    POLICIES
+        .iter()
+        .find(|policy| fn_name.starts_with(policy.endpoint))
+}
+
+fn limit_subject(app_state: &AppState, key: LimitKey, req: &Request) -> Option<String> {
+    if key == LimitKey::Principal {
+        let (jar, _) = app_state.cookie_keyring.signed_jar(req.headers());
+        if let Some(principal) = principal_from_refresh_cookie(&jar) {
+            return Some(principal.to_text());
+        }
+    }
+
+    let peer = req
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| *addr);
+    client_ip(req.headers(), peer).map(|ip| format!("ip:{ip}"))
+}
+
+fn too_many_requests(retry_after: Duration) -> Response {
+    let retry_after_secs = retry_after.as_millis().div_ceil(1000);
+    (
+        StatusCode::TOO_MANY_REQUESTS,
+        [(header::RETRY_AFTER, retry_after_secs.to_string())],
+        "too many requests, try again later",
+    )
+        .into_response()
+}
+
+/// Middleware applying [`POLICIES`] to server function calls
+pub async fn rate_limit(State(app_state): State<AppState>, req: Request, next: Next) -> Response {
+    let Some(policy) = policy_for(req.uri().path()) else {
+        return next.run(req).await;
+    };
+    let Some(subject) = limit_subject(&app_state, policy.key, &req) else {
+        return next.run(req).await;
+    };
+
+    let key = format!("{RATE_LIMIT_PREFIX}:{}:{subject}", policy.endpoint);
+    match app_state.kv.take_token(key, policy.bucket).await {
+        Ok(None) => next.run(req).await,
+        Ok(Some(retry_after)) => too_many_requests(retry_after),
+        Err(e) => {
+            // an unavailable store shouldn't take the endpoints down with it
+            tracing::warn!("rate limit check failed for {}: {e}", policy.endpoint);
+            next.run(req).await
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn policies_match_hashed_endpoints() {
+        let policy = policy_for("/api/clear_sats17029374923").unwrap();
+        assert_eq!(policy.endpoint, "clear_sats");
+        assert_eq!(policy.key, LimitKey::Principal);
+
+        assert!(policy_for("/api/get_user_profile").is_none());
+        assert!(policy_for("/clear_sats").is_none());
+    }
+}
@@ -0,0 +1,421 @@
+//! `robots.txt` and sitemaps of public profiles and popular posts
+//!
+//! every tenant gets its own sitemap, of the posts its feed shows, with urls
+//! built for its canonical host. The listed pages are cached in the KV store
+//! per tenant and rebuilt by one request at a time per instance
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Write,
+    sync::{Arc, LazyLock, Mutex},
+    time::Duration,
+};
+
+use auth::server_impl::store::KVStore;
+use axum::{
+    extract::{Path, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+};
+use candid::Principal;
+use futures::{
+    future::{BoxFuture, Shared},
+    stream, FutureExt, StreamExt,
+};
+use serde::{Deserialize, Serialize};
+use state::{
+    app_state,
+    app_type::{is_preview_host, AppType},
+    server::AppState,
+};
+use utils::ml_feed::{ContentFilter, FeedRequest, FeedSource, PostItem};
+
+const SITEMAP_CACHE_PREFIX: &str = "sitemap:pages";
+const SITEMAP_CACHE_TTL: Duration = Duration::from_secs(6 * 60 * 60);
+/// An empty sitemap is likely a feed outage, it's rebuilt sooner
+const EMPTY_SITEMAP_CACHE_TTL: Duration = Duration::from_secs(5 * 60);
+/// Posts requested from the feed at a time
+const FEED_BATCH: u32 = 100;
+const MAX_POSTS: usize = 1000;
+/// Urls per sitemap shard, sitemaps are limited to 50k
+const SHARD_SIZE: usize = 500;
+const PROFILE_FETCH_CONCURRENCY: usize = 10;
+
+/// Paths of the pages listed in the sitemaps
+#[derive(Serialize, Deserialize, Default)]
+struct SitemapPages {
+    posts: Vec<String>,
+    profiles: Vec<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum SitemapKind {
+    Posts,
+    Profiles,
+}
+
+impl SitemapKind {
+    fn from_path(kind: &str) -> Option<Self> {
+        match kind {
+            "posts" => Some(Self::Posts),
+            "profiles" => Some(Self::Profiles),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Posts => "posts",
+            Self::Profiles => "profiles",
+        }
+    }
+
+    fn pages(self, pages: &SitemapPages) -> &[String] {
+        match self {
+            Self::Posts => &pages.posts,
+            Self::Profiles => &pages.profiles,
+        }
+    }
+}
+
+type Rebuild = Shared<BoxFuture<'static, Arc<SitemapPages>>>;
+
+/// Rebuilds of the pages running on this instance by tenant id, awaited by
+/// every request that misses the cache meanwhile
+static REBUILDS: LazyLock<Mutex<HashMap<&'static str, Rebuild>>> = LazyLock::new(Default::default);
+
+fn sitemap_cache_key(tenant: &app_state::AppState) -> String {
+    format!("{SITEMAP_CACHE_PREFIX}:{}", tenant.id)
+}
+
+/// Tenant the request is for
+fn request_tenant(headers: &HeaderMap) -> app_state::AppState {
+    let host = headers
+        .get(header::HOST)
+        .and_then(|host| host.to_str().ok())
+        .unwrap_or_default();
+    app_state::AppState::from_type(&AppType::from_host(host))
+}
+
+fn canonical_origin(tenant: &app_state::AppState) -> String {
+    format!("https://{}", tenant.app_type.canonical_host())
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn xml_response(body: String) -> Response {
+    (
+        [(header::CONTENT_TYPE, "application/xml; charset=utf-8")],
+        body,
+    )
+        .into_response()
+}
+
+/// Posts anonymous visitors of `tenant` see in its feed, fetched in batches
+async fn popular_posts(app_state: &AppState, tenant: &app_state::AppState) -> Vec<PostItem> {
+    let content = if tenant.show_nsfw_content() {
+        ContentFilter::Nsfw
+    } else {
+        ContentFilter::Clean
+    };
+    let mut posts = vec![];
+    let mut seen = vec![];
+    while posts.len() < MAX_POSTS {
+        let batch = match app_state
+            .feed_source
+            .recommendations(FeedRequest {
+                user_id: Principal::anonymous(),
+                count: FEED_BATCH,
+                rec_type: tenant.default_rec_type,
+                content,
+                exclude: seen.clone(),
+            })
+            .await
+        {
+            Ok(batch) => batch,
+            Err(e) => {
+                tracing::warn!("sitemap: failed to fetch posts: {e}");
+                break;
+            }
+        };
+        let batch: Vec<_> = batch
+            .into_iter()
+            .filter(|post| !seen.contains(&post.video_id))
+            .collect();
+        if batch.is_empty() {
+            break;
+        }
+        seen.extend(batch.iter().map(|post| post.video_id.clone()));
+        posts.extend(
+            batch
+                .into_iter()
+                .filter(|post| content.allows(post.nsfw_probability)),
+        );
+    }
+    posts.truncate(MAX_POSTS);
+    posts
+}
+
+async fn build_pages(app_state: &AppState, tenant: &app_state::AppState) -> SitemapPages {
+    let posts = popular_posts(app_state, tenant).await;
+
+    let mut seen_publishers = HashSet::new();
+    let publishers: Vec<_> = posts
+        .iter()
+        .map(|post| post.publisher_user_id)
+        .filter(|publisher| seen_publishers.insert(*publisher))
+        .collect();
+    // profiles are listed under their username if they have one, like the
+    // links shared from the app
+    let profiles = stream::iter(publishers)
+        .map(|publisher| async move {
+            match app_state
+                .canisters
+                .get_profile_details(publisher.to_text())
+                .await
+            {
+                Ok(Some(profile)) => Some(format!(
+                    "/profile/{}/posts",
+                    profile.username_or_principal()
+                )),
+                Ok(None) => None,
+                Err(e) => {
+                    tracing::warn!("sitemap: failed to fetch profile {publisher}: {e}");
+                    Some(format!("/profile/{publisher}/posts"))
+                }
+            }
+        })
+        .buffered(PROFILE_FETCH_CONCURRENCY)
+        .filter_map(|profile| async move { profile })
+        .collect()
+        .await;
+
+    SitemapPages {
+        posts: posts
+            .iter()
+            .map(|post| format!("/hot-or-not/{}/{}", post.canister_id, post.post_id))
+            .collect(),
+        profiles,
+    }
+}
+
+async fn rebuild_pages(app_state: AppState, tenant: app_state::AppState) -> Arc<SitemapPages> {
+    let pages = build_pages(&app_state, &tenant).await;
+    let ttl = if pages.posts.is_empty() {
+        EMPTY_SITEMAP_CACHE_TTL
+    } else {
+        SITEMAP_CACHE_TTL
+    };
+    let cached = serde_json::to_string(&pages).expect("sitemap pages are serializable");
+    if let Err(e) = app_state
+        .kv
+        .write_with_ttl(sitemap_cache_key(&tenant), cached, ttl)
+        .await
+    {
+        tracing::warn!("sitemap: failed to cache pages of {}: {e}", tenant.id);
+    }
+    REBUILDS.lock().unwrap().remove(tenant.id);
+    Arc::new(pages)
+}
+
+async fn sitemap_pages(app_state: &AppState, tenant: &app_state::AppState) -> Arc<SitemapPages> {
+    match app_state.kv.read(sitemap_cache_key(tenant)).await {
+        Ok(Some(cached)) => {
+            if let Ok(pages) = serde_json::from_str(&cached) {
+                return Arc::new(pages);
+            }
+        }
+        Ok(None) => {}
+        Err(e) => tracing::warn!("sitemap: failed to read cache: {e}"),
+    }
+
+    // the shared rebuild keeps running as long as a request awaits it
+    let rebuild = REBUILDS
+        .lock()
+        .unwrap()
+        .entry(tenant.id)
+        .or_insert_with(|| {
+            rebuild_pages(app_state.clone(), tenant.clone())
+                .boxed()
+                .shared()
+        })
+        .clone();
+    rebuild.await
+}
+
+fn shard_count(pages: usize) -> usize {
+    pages.div_ceil(SHARD_SIZE)
+}
+
+/// Preview deployments aren't indexed at all
+fn robots(host: &str) -> String {
+    let mut robots = String::from("User-agent: *\n");
+    if is_preview_host(host) {
+        robots.push_str("Disallow: /\n");
+    } else {
+        let tenant = app_state::AppState::from_type(&AppType::from_host(host));
+        for path in tenant.robots_disallow.iter().chain(tenant.disabled_routes) {
+            _ = writeln!(robots, "Disallow: {path}");
+        }
+        _ = writeln!(
+            robots,
+            "\nSitemap: {}/sitemap.xml",
+            canonical_origin(&tenant)
+        );
+    }
+    robots
+}
+
+pub async fn robots_txt(headers: HeaderMap) -> Response {
+    (
+        [(header::CONTENT_TYPE, "text/plain; charset=utf-8")],
+        robots(
+            headers
+                .get(header::HOST)
+                .and_then(|host| host.to_str().ok())
+                .unwrap_or_default(),
+        ),
+    )
+        .into_response()
+}
+
+/// Sitemap index, linking every shard
+pub async fn sitemap_index(State(app_state): State<AppState>, headers: HeaderMap) -> Response {
+    let tenant = request_tenant(&headers);
+    let origin = canonical_origin(&tenant);
+    let pages = sitemap_pages(&app_state, &tenant).await;
+
+    let mut xml = String::from(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+"#,
+    );
+    for kind in [SitemapKind::Posts, SitemapKind::Profiles] {
+        for shard in 0..shard_count(kind.pages(&pages).len()) {
+            let loc = xml_escape(&format!("{origin}/sitemap/{}/{shard}.xml", kind.as_str()));
+            _ = writeln!(xml, "  <sitemap><loc>{loc}</loc></sitemap>");
+        }
+    }
+    xml.push_str("</sitemapindex>\n");
+    xml_response(xml)
+}
+
+/// `(kind, shard)` of a shard's path, `/sitemap/<kind>/<shard>.xml`
+fn parse_shard(kind: &str, file: &str) -> Option<(SitemapKind, usize)> {
+    SitemapKind::from_path(kind).zip(
+        file.strip_suffix(".xml")
+            .and_then(|shard| shard.parse::<usize>().ok()),
+    )
+}
+
+/// None if the shard is past the last page
+fn shard_xml(
+    origin: &str,
+    pages: &SitemapPages,
+    kind: SitemapKind,
+    shard: usize,
+) -> Option<String> {
+    let shard = kind.pages(pages).chunks(SHARD_SIZE).nth(shard)?;
+
+    let mut xml = String::from(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+"#,
+    );
+    for path in shard {
+        let loc = xml_escape(&format!("{origin}{path}"));
+        _ = writeln!(xml, "  <url><loc>{loc}</loc></url>");
+    }
+    xml.push_str("</urlset>\n");
+    Some(xml)
+}
+
+/// A page of the urls of one kind, `/sitemap/<kind>/<shard>.xml`
+pub async fn sitemap_shard(
+    State(app_state): State<AppState>,
+    headers: HeaderMap,
+    Path((kind, file)): Path<(String, String)>,
+) -> Response {
+    let Some((kind, shard)) = parse_shard(&kind, &file) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let tenant = request_tenant(&headers);
+    let origin = canonical_origin(&tenant);
+    let pages = sitemap_pages(&app_state, &tenant).await;
+    match shard_xml(&origin, &pages, kind, shard) {
+        Some(xml) => xml_response(xml),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shards_cover_every_page() {
+        assert_eq!(shard_count(0), 0);
+        assert_eq!(shard_count(1), 1);
+        assert_eq!(shard_count(SHARD_SIZE), 1);
+        assert_eq!(shard_count(SHARD_SIZE + 1), 2);
+    }
+
+    #[test]
+    fn previews_are_not_indexed() {
+        for host in [
+            "pr-12-dolr-ai-hot-or-not-web-leptos-ssr.fly.dev",
+            "localhost:3000",
+        ] {
+            assert_eq!(robots(host), "User-agent: *\nDisallow: /\n", "{host}");
+        }
+    }
+
+    #[test]
+    fn robots_follow_the_tenant() {
+        let hotornot = robots("hotornot.wtf");
+        assert!(hotornot.contains("Disallow: /pnd\n"));
+        assert!(hotornot.ends_with("Sitemap: https://hotornot.wtf/sitemap.xml\n"));
+
+        // hosts that aren't canonical point crawlers to the tenant's own host
+        let yral = robots("yral.example.com");
+        assert!(!yral.contains("Disallow: /pnd\n"));
+        assert!(yral.contains("Disallow: /wallet\n"));
+        assert!(yral.ends_with("Sitemap: https://legacy.yral.com/sitemap.xml\n"));
+    }
+
+    #[test]
+    fn shards_past_the_end_are_missing() {
+        let pages = SitemapPages {
+            posts: vec!["/hot-or-not/aaaaa-aa/1".into()],
+            profiles: vec![],
+        };
+        let origin = "https://hotornot.wtf";
+        let xml = shard_xml(origin, &pages, SitemapKind::Posts, 0).unwrap();
+        assert!(xml.contains("<loc>https://hotornot.wtf/hot-or-not/aaaaa-aa/1</loc>"));
+        assert_eq!(shard_xml(origin, &pages, SitemapKind::Posts, 1), None);
+        assert_eq!(shard_xml(origin, &pages, SitemapKind::Profiles, 0), None);
+        assert_eq!(parse_shard("posts", "0.xml"), Some((SitemapKind::Posts, 0)));
+        assert_eq!(parse_shard("videos", "0.xml"), None);
+        assert_eq!(parse_shard("posts", "first.xml"), None);
+    }
+
+    #[test]
+    fn tenants_are_cached_apart() {
+        let keys: HashSet<_> = AppType::ALL
+            .iter()
+            .map(|app_type| sitemap_cache_key(&app_state::AppState::from_type(app_type)))
+            .collect();
+        assert_eq!(keys.len(), AppType::ALL.len());
+    }
+
+    #[test]
+    fn sitemap_urls_are_escaped() {
+        assert_eq!(xml_escape("/a?b=1&c=<2>"), "/a?b=1&amp;c=&lt;2&gt;");
+    }
+}
@@ -1,5 +1,105 @@
+use consts::FEED_HISTORY_DEPTH;
+use utils::ml_feed::RecType;
+
 use super::app_type::AppType;
 
+/// Who gets to see NSFW posts
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NsfwPolicy {
+    /// Only users who opted in
+    OptIn,
+    Everyone,
+}
+
+#[derive(Clone, Debug)]
+pub struct AnalyticsKeys {
+    /// Google Analytics property of the tenant, none if it has no property of its own
+    pub ga4_measurement_id: Option<&'static str>,
+}
+
+#[derive(Clone, Debug)]
+pub struct AndroidApp {
+    pub package_name: &'static str,
+    /// Fingerprints of the certificates the app is signed with
+    pub sha256_cert_fingerprints: &'static [&'static str],
+}
+
+impl AndroidApp {
+    /// Play Store listing, `referrer` reaches the app through the Play Install Referrer API
+    pub fn store_url(&self, referrer: &str) -> String {
+        format!(
+            "https://play.google.com/store/apps/details?id={}&referrer={}",
+            self.package_name,
+            urlencoding::encode(referrer)
+        )
+    }
+
+    /// Opens `host` + `path` in the app, or the store listing if it isn't installed
+    pub fn intent_url(&self, host: &str, path: &str, referrer: &str) -> String {
+        format!(
+            "intent://{host}{path}#Intent;scheme=https;package={};S.browser_fallback_url={};end",
+            self.package_name,
+            urlencoding::encode(&self.store_url(referrer))
+        )
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct IosApp {
+    /// `<team id>.<bundle id>`
+    pub app_id: &'static str,
+    pub app_store_id: &'static str,
+    /// Custom URL scheme the app registers
+    pub url_scheme: &'static str,
+}
+
+impl IosApp {
+    /// YRAL's iOS app, its ids are set at build time through
+    /// `YRAL_IOS_APP_ID` and `YRAL_IOS_APP_STORE_ID`
+    fn yral() -> Option<Self> {
+        Some(Self {
+            app_id: option_env!("YRAL_IOS_APP_ID")?,
+            app_store_id: option_env!("YRAL_IOS_APP_STORE_ID")?,
+            url_scheme: "yral",
+        })
+    }
+
+    pub fn store_url(&self) -> String {
+        format!("https://apps.apple.com/app/id{}", self.app_store_id)
+    }
+
+    /// Opens `path` in the app if it's installed, Safari does nothing otherwise
+    ///
+    /// universal links only open the app when navigating from another site, so
+    /// the banner needs the custom scheme
+    pub fn app_url(&self, path: &str) -> String {
+        format!("{}://{}", self.url_scheme, path.trim_start_matches('/'))
+    }
+}
+
+/// Native apps links to this tenant open in
+#[derive(Clone, Debug, Default)]
+pub struct NativeApps {
+    pub android: Option<AndroidApp>,
+    pub ios: Option<IosApp>,
+}
+
+/// Pages that are specific to the visitor, or not pages at all
+const PRIVATE_ROUTES: &[&str] = &[
+    "/api/",
+    "/auth/",
+    "/deferred-link/",
+    "/internal/",
+    "/logout",
+    "/menu",
+    "/profile/edit",
+    "/refer-earn",
+    "/token/transfer/",
+    "/upload",
+    "/wallet",
+];
+
+/// Branding and feature set of a tenant
 #[derive(Clone)]
 pub struct AppState {
     pub app_type: AppType,
@@ -8,6 +108,18 @@ pub struct AppState {
     pub description: &'static str,
     pub theme_color: &'static str,
     pub assets_dir: &'static str,
+    pub nsfw_policy: NsfwPolicy,
+    /// Domain the tenant's passkeys are bound to, its canonical host or a parent domain
+    pub passkey_rp_id: &'static str,
+    /// Path prefixes of the routes this tenant doesn't serve
+    pub disabled_routes: &'static [&'static str],
+    pub default_rec_type: RecType,
+    /// Posts kept behind the current one in the feed for back-scroll
+    pub feed_history_depth: usize,
+    pub analytics: AnalyticsKeys,
+    pub native_apps: NativeApps,
+    /// Path prefixes crawlers are asked not to index
+    pub robots_disallow: &'static [&'static str],
 }
 
 impl AppState {
@@ -20,11 +132,90 @@ impl AppState {
                 description: "The First App to Host Creative Short Video Challenges",
                 theme_color: "#E20479",
                 assets_dir: "yral",
+                nsfw_policy: NsfwPolicy::OptIn,
+                passkey_rp_id: "yral.com",
+                disabled_routes: &[],
+                default_rec_type: RecType::Mixed,
+                feed_history_depth: FEED_HISTORY_DEPTH,
+                analytics: AnalyticsKeys {
+                    ga4_measurement_id: Some("G-V2WDJLV7E5"),
+                },
+                native_apps: NativeApps {
+                    android: Some(AndroidApp {
+                        package_name: "com.yral.lite",
+                        sha256_cert_fingerprints: &[
+                            "FA:AC:91:6B:17:5E:08:2D:F9:20:10:2F:A3:EF:85:28:8A:66:87:74:1A:33:B6:AD:C9:EC:CC:E0:6D:B3:02:44",
+                        ],
+                    }),
+                    ios: IosApp::yral(),
+                },
+                robots_disallow: PRIVATE_ROUTES,
+            },
+            AppType::HotOrNot => Self {
+                app_type: AppType::HotOrNot,
+                id: "hotornot",
+                name: "Hot or Not",
+                description: "Vote on the hottest short videos and win SATS",
+                theme_color: "#E53335",
+                assets_dir: "hotornot",
+                nsfw_policy: NsfwPolicy::Everyone,
+                passkey_rp_id: "hotornot.wtf",
+                disabled_routes: &["/pnd"],
+                default_rec_type: RecType::Mixed,
+                feed_history_depth: FEED_HISTORY_DEPTH,
+                // kept out of YRAL's property until it gets its own
+                analytics: AnalyticsKeys {
+                    ga4_measurement_id: None,
+                },
+                native_apps: NativeApps::default(),
+                robots_disallow: PRIVATE_ROUTES,
             },
         }
     }
 
+    /// Tenant of the current request (or page, on the client)
+    pub fn current() -> Self {
+        Self::from_type(&AppType::select())
+    }
+
     pub fn asset_path(&self) -> String {
         format!("img/{}", self.assets_dir)
     }
+
+    /// Whether NSFW posts are shown without the user opting in
+    pub fn show_nsfw_content(&self) -> bool {
+        self.nsfw_policy == NsfwPolicy::Everyone
+    }
+
+    pub fn route_enabled(&self, path: &str) -> bool {
+        !self.disabled_routes.iter().any(|prefix| {
+            path.strip_prefix(prefix)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disabled_routes_match_whole_segments() {
+        let app_state = AppState::from_type(&AppType::HotOrNot);
+        assert!(!app_state.route_enabled("/pnd/withdraw"));
+        assert!(!app_state.route_enabled("/pnd"));
+        assert!(app_state.route_enabled("/pndx"));
+    }
+
+    #[test]
+    fn passkeys_cover_the_canonical_host() {
+        for app_type in AppType::ALL {
+            let rp_id = AppState::from_type(&app_type).passkey_rp_id;
+            let host = app_type.canonical_host();
+            assert!(
+                host == rp_id || host.ends_with(&format!(".{rp_id}")),
+                "{host} can't use passkeys bound to {rp_id}"
+            );
+        }
+    }
 }
@@ -1,10 +1,192 @@
-#[derive(Clone, Debug, PartialEq)]
+//! Tenants served by this app, resolved from the host of the request
+use utils::host::get_host;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum AppType {
     YRAL,
+    HotOrNot,
 }
 
 impl AppType {
+    pub const ALL: [AppType; 2] = [AppType::YRAL, AppType::HotOrNot];
+}
+
+enum HostPattern {
+    Exact(&'static str),
+    /// The host or any of its subdomains
+    Suffix(&'static str),
+    /// `pr-<number>-<suffix>`, the per pull request deployments
+    PrPreview(&'static str),
+    Localhost,
+}
+
+impl HostPattern {
+    fn matches(&self, host: &str) -> bool {
+        match self {
+            Self::Exact(exact) => host == *exact,
+            Self::Suffix(suffix) => host
+                .strip_suffix(suffix)
+                .is_some_and(|sub| sub.is_empty() || sub.ends_with('.')),
+            Self::PrPreview(suffix) => host
+                .strip_prefix("pr-")
+                .and_then(|rest| rest.strip_suffix(suffix))
+                .and_then(|rest| rest.strip_suffix('-'))
+                .is_some_and(|number| number.chars().all(|c| c.is_ascii_digit())),
+            Self::Localhost => host == "localhost" || host == "127.0.0.1",
+        }
+    }
+}
+
+struct HostRule {
+    pattern: HostPattern,
+    app_type: AppType,
+    /// Preview deployments expose unreleased features (e.g test models)
+    preview: bool,
+    /// Whether pages served from this host may call our APIs cross origin
+    cors: bool,
+    /// Whether this is the host the tenant's pages are indexed under
+    canonical: bool,
+}
+
+const PREVIEW_DOMAIN: &str = "dolr-ai-hot-or-not-web-leptos-ssr.fly.dev";
+
+/// First matching rule wins, unknown hosts are served as [`AppType::YRAL`]
+const HOSTS: &[HostRule] = &[
+    HostRule {
+        pattern: HostPattern::Exact("hotornot.wtf"),
+        app_type: AppType::HotOrNot,
+        preview: false,
+        cors: false,
+        canonical: true,
+    },
+    HostRule {
+        pattern: HostPattern::Exact("legacy.yral.com"),
+        app_type: AppType::YRAL,
+        preview: false,
+        cors: true,
+        canonical: true,
+    },
+    HostRule {
+        pattern: HostPattern::PrPreview(PREVIEW_DOMAIN),
+        app_type: AppType::YRAL,
+        preview: true,
+        cors: true,
+        canonical: false,
+    },
+    HostRule {
+        pattern: HostPattern::Suffix(PREVIEW_DOMAIN),
+        app_type: AppType::YRAL,
+        preview: true,
+        cors: false,
+        canonical: false,
+    },
+    HostRule {
+        pattern: HostPattern::Localhost,
+        app_type: AppType::YRAL,
+        preview: true,
+        cors: false,
+        canonical: false,
+    },
+];
+
+/// Strips the scheme, path and port, so origins and urls can be matched as well
+fn normalize_host(host: &str) -> &str {
+    let host = host
+        .strip_prefix("https://")
+        .or_else(|| host.strip_prefix("http://"))
+        .unwrap_or(host);
+    host.split(['/', ':']).next().unwrap_or_default()
+}
+
+fn host_rule(host: &str) -> Option<&'static HostRule> {
+    let host = normalize_host(host);
+    HOSTS.iter().find(|rule| rule.pattern.matches(host))
+}
+
+impl AppType {
+    pub fn from_host(host: &str) -> Self {
+        host_rule(host).map_or(AppType::YRAL, |rule| rule.app_type)
+    }
+
+    /// Tenant of the current request (or page, on the client)
     pub fn select() -> Self {
-        AppType::YRAL
+        Self::from_host(&get_host())
+    }
+
+    /// Host the tenant's pages are indexed under, whichever host they're served from
+    pub fn canonical_host(self) -> &'static str {
+        HOSTS
+            .iter()
+            .find_map(|rule| match rule.pattern {
+                HostPattern::Exact(host) if rule.canonical && rule.app_type == self => Some(host),
+                _ => None,
+            })
+            .expect("every tenant has a canonical host")
+    }
+}
+
+/// Whether `host` is a preview deployment
+pub fn is_preview_host(host: &str) -> bool {
+    host_rule(host).is_some_and(|rule| rule.preview)
+}
+
+/// Whether the current request (or page, on the client) is on a preview deployment
+pub fn is_preview_deployment() -> bool {
+    is_preview_host(&get_host())
+}
+
+/// Whether cross origin requests from `origin` are allowed
+pub fn is_cors_allowed_origin(origin: &str) -> bool {
+    host_rule(origin).is_some_and(|rule| rule.cors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tenants_are_resolved_from_host() {
+        assert_eq!(AppType::from_host("hotornot.wtf"), AppType::HotOrNot);
+        assert_eq!(AppType::from_host("yral.com"), AppType::YRAL);
+        assert_eq!(AppType::from_host("localhost:3000"), AppType::YRAL);
+        assert_eq!(AppType::from_host("hotornot.wtf.evil.com"), AppType::YRAL);
+    }
+
+    #[test]
+    fn tenants_have_a_canonical_host() {
+        assert_eq!(AppType::YRAL.canonical_host(), "legacy.yral.com");
+        assert_eq!(AppType::HotOrNot.canonical_host(), "hotornot.wtf");
+    }
+
+    #[test]
+    fn preview_origins_match() {
+        for origin in [
+            "https://pr-636-dolr-ai-hot-or-not-web-leptos-ssr.fly.dev",
+            "pr-636-dolr-ai-hot-or-not-web-leptos-ssr.fly.dev",
+            "https://legacy.yral.com",
+        ] {
+            assert!(is_cors_allowed_origin(origin), "{origin}");
+        }
+        for origin in [
+            "https://ramdom.com/pr-636-dolr-ai-hot-or-not-web-leptos-ssr.fly.dev",
+            "https://pr-x-dolr-ai-hot-or-not-web-leptos-ssr.fly.dev",
+            "https://yral.com",
+            "http://localhost:3000",
+        ] {
+            assert!(!is_cors_allowed_origin(origin), "{origin}");
+        }
+    }
+
+    #[test]
+    fn preview_hosts_match() {
+        assert!(is_preview_host("dolr-ai-hot-or-not-web-leptos-ssr.fly.dev"));
+        assert!(is_preview_host(
+            "pr-12-dolr-ai-hot-or-not-web-leptos-ssr.fly.dev"
+        ));
+        assert!(is_preview_host("localhost:3000"));
+        assert!(!is_preview_host("yral.com"));
+        assert!(!is_preview_host(
+            "evildolr-ai-hot-or-not-web-leptos-ssr.fly.dev"
+        ));
     }
 }
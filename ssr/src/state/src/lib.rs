@@ -15,9 +15,12 @@ pub mod server {
 #[cfg(feature = "ssr")]
 pub mod server {
 
-    use auth::server_impl::store::KVStoreImpl;
+    use auth::server_impl::{
+        cookie_keyring::CookieKeyring,
+        passkey::PasskeyConfigs,
+        store::{encrypted_kv::SecretKeyring, KVStoreImpl},
+    };
     use axum::extract::FromRef;
-    use axum_extra::extract::cookie::Key;
     use leptos::prelude::*;
     use leptos_axum::AxumRouteListing;
     use yral_canisters_common::Canisters;
@@ -34,16 +37,21 @@ pub mod server {
         #[cfg(feature = "cloudflare")]
         pub cloudflare: gob_cloudflare::CloudflareAuth,
         pub kv: KVStoreImpl,
+        pub secret_keyring: SecretKeyring,
         pub routes: Vec<AxumRouteListing>,
-        pub cookie_key: Key,
+        pub cookie_keyring: CookieKeyring,
+        pub passkey_configs: PasskeyConfigs,
         #[cfg(feature = "oauth-ssr")]
         pub yral_oauth_client: auth::server_impl::yral::YralOAuthClient,
         #[cfg(feature = "oauth-ssr")]
+        pub identity_providers: auth::server_impl::provider::IdentityProviders,
+        #[cfg(feature = "oauth-ssr")]
         pub yral_auth_migration_key: jsonwebtoken::EncodingKey,
         #[cfg(feature = "ga4")]
         pub grpc_offchain_channel: tonic::transport::Channel,
         #[cfg(feature = "qstash")]
         pub qstash: utils::qstash::QStashClient,
         pub hon_worker_jwt: HonWorkerJwt,
+        pub feed_source: utils::ml_feed::FeedSourceImpl,
     }
 }
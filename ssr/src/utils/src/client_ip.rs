@@ -1,18 +1,113 @@
+/// Reverse proxy in front of the app, whose forwarding headers are trusted
 #[cfg(feature = "ssr")]
-pub async fn get_client_ip() -> Option<String> {
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TrustedProxy {
+    /// Sets `Fly-Client-IP` itself
+    Fly,
+    /// Appends the address it was reached from to `X-Forwarded-For` (e.g Caddy)
+    XForwardedFor,
+}
+
+#[cfg(feature = "ssr")]
+impl TrustedProxy {
+    /// `TRUSTED_PROXY` is `fly` or `x-forwarded-for`, Fly is assumed when `FLY_APP_NAME` is set.
+    /// Without a proxy clients connect directly and forwarding headers are ignored
+    pub fn from_env() -> Option<Self> {
+        let proxy = std::env::var("TRUSTED_PROXY").unwrap_or_default();
+        match proxy.as_str() {
+            "fly" => Some(Self::Fly),
+            "x-forwarded-for" => Some(Self::XForwardedFor),
+            "" => std::env::var_os("FLY_APP_NAME").map(|_| Self::Fly),
+            other => panic!("Invalid `TRUSTED_PROXY` `{other}`"),
+        }
+    }
+}
+
+#[cfg(feature = "ssr")]
+static TRUSTED_PROXY: std::sync::LazyLock<Option<TrustedProxy>> =
+    std::sync::LazyLock::new(TrustedProxy::from_env);
+
+/// Proxy configured for this process, see [`TrustedProxy::from_env`]
+#[cfg(feature = "ssr")]
+pub fn trusted_proxy() -> Option<TrustedProxy> {
+    *TRUSTED_PROXY
+}
+
+/// Client IP of a request, `peer` is the address the connection came from
+///
+/// Behind a trusted proxy only the hop it added is used, everything before it
+/// comes from the client and can be spoofed
+#[cfg(feature = "ssr")]
+pub fn client_ip(
+    headers: &axum::http::HeaderMap,
+    peer: Option<std::net::SocketAddr>,
+) -> Option<String> {
+    resolve_client_ip(headers, peer, trusted_proxy())
+}
+
+#[cfg(feature = "ssr")]
+fn resolve_client_ip(
+    headers: &axum::http::HeaderMap,
+    peer: Option<std::net::SocketAddr>,
+    proxy: Option<TrustedProxy>,
+) -> Option<String> {
+    let header = |name: &str| headers.get(name).and_then(|val| val.to_str().ok());
+    let ip = match proxy {
+        Some(TrustedProxy::Fly) => header("fly-client-ip")?,
+        Some(TrustedProxy::XForwardedFor) => header("x-forwarded-for")?.rsplit(',').next()?,
+        None => return peer.map(|peer| peer.ip().to_string()),
+    };
+    ip.trim()
+        .parse::<std::net::IpAddr>()
+        .ok()
+        .map(|ip| ip.to_string())
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
     use axum::http::HeaderMap;
-    use leptos_axum::extract;
 
-    let result: Result<HeaderMap, _> = extract().await;
+    use super::*;
+
+    fn headers(entries: &[(&'static str, &str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in entries {
+            headers.insert(*name, value.parse().unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn only_the_proxy_hop_is_trusted() {
+        let peer = Some("4.4.4.4:443".parse().unwrap());
+        let spoofed = headers(&[("x-forwarded-for", "1.1.1.1, 2.2.2.2")]);
+        assert_eq!(
+            resolve_client_ip(&spoofed, peer, Some(TrustedProxy::XForwardedFor)).as_deref(),
+            Some("2.2.2.2")
+        );
+        // without a proxy the header comes from the client
+        assert_eq!(
+            resolve_client_ip(&spoofed, peer, None).as_deref(),
+            Some("4.4.4.4")
+        );
 
-    leptos::logging::log!("Extracted headers: {:?}", result);
+        let fly = headers(&[
+            ("x-forwarded-for", "1.1.1.1, 2.2.2.2"),
+            ("fly-client-ip", "3.3.3.3"),
+        ]);
+        assert_eq!(
+            resolve_client_ip(&fly, peer, Some(TrustedProxy::Fly)).as_deref(),
+            Some("3.3.3.3")
+        );
+        assert_eq!(
+            resolve_client_ip(&spoofed, peer, Some(TrustedProxy::Fly)),
+            None
+        );
 
-    match result {
-        Ok(headers) => headers
-            .get("x-forwarded-for")
-            .and_then(|val| val.to_str().ok())
-            .and_then(|s| s.split(',').next())
-            .map(|s| s.trim().to_string()),
-        Err(_) => None,
+        let garbage = headers(&[("x-forwarded-for", "1.1.1.1, not-an-ip")]);
+        assert_eq!(
+            resolve_client_ip(&garbage, peer, Some(TrustedProxy::XForwardedFor)),
+            None
+        );
     }
 }
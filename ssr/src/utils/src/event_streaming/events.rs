@@ -9,6 +9,7 @@ use sns_validation::pbs::sns_pb::SnsInitPayload;
 pub enum ProviderKind {
     #[cfg(any(feature = "oauth-ssr", feature = "oauth-hydrate"))]
     YralAuth,
+    Passkey,
 }
 
 use circular_buffer::CircularBuffer;
@@ -566,8 +567,7 @@ impl LoginMethodSelected {
                     "login_method": match prov {
                         #[cfg(any(feature = "oauth-ssr", feature = "oauth-hydrate"))]
                         ProviderKind::YralAuth => "yral",
-                        #[cfg(not(any(feature = "oauth-ssr", feature = "oauth-hydrate")))]
-                        _ => "local",
+                        ProviderKind::Passkey => "passkey",
                     },
                     "attempt_count": 1,
                 })
@@ -1,5 +1,3 @@
-use std::sync::LazyLock;
-
 pub fn get_host() -> String {
     #[cfg(feature = "hydrate")]
     {
@@ -30,55 +28,3 @@ pub fn get_host() -> String {
             .unwrap_or_default()
     }
 }
-
-#[cfg(feature = "ssr")]
-pub fn is_host_or_origin_from_preview_domain(uri: &str) -> bool {
-    use regex::Regex;
-
-    static PR_PREVIEW_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
-        Regex::new(r"^(https:\/\/)?pr-\d*-dolr-ai-hot-or-not-web-leptos-ssr\.fly\.dev$").unwrap()
-    });
-
-    PR_PREVIEW_PATTERN.is_match_at(uri, 0)
-}
-
-pub fn show_preview_component() -> bool {
-    let host = get_host();
-    host.contains("dolr-ai-hot-or-not-web-leptos-ssr.fly.dev") || host.contains("localhost")
-}
-
-// TODO: migrate to AppType
-pub fn show_nsfw_content() -> bool {
-    let host = get_host();
-
-    show_nsfw_condition(host)
-}
-
-pub fn show_nsfw_condition(host: String) -> bool {
-    host == "hotornot.wtf"
-    // || host.contains("dolr-ai-hot-or-not-web-leptos-ssr.fly.dev")
-}
-
-#[cfg(test)]
-mod tests {
-    use crate::host::is_host_or_origin_from_preview_domain;
-
-    #[test]
-    fn preview_origin_regex_matches() {
-        let preview_link_url = "https://pr-636-dolr-ai-hot-or-not-web-leptos-ssr.fly.dev";
-        assert!(is_host_or_origin_from_preview_domain(preview_link_url))
-    }
-
-    #[test]
-    fn preview_host_regex_matches() {
-        let preview_link_url = "pr-636-dolr-ai-hot-or-not-web-leptos-ssr.fly.dev";
-        assert!(is_host_or_origin_from_preview_domain(preview_link_url))
-    }
-
-    #[test]
-    fn preview_localhost_fails() {
-        let preview_link_url =
-            "https://ramdom.com/pr-636-dolr-ai-hot-or-not-web-leptos-ssr.fly.dev";
-        assert!(!is_host_or_origin_from_preview_domain(preview_link_url))
-    }
-}
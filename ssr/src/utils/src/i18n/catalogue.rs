@@ -0,0 +1,231 @@
+use std::{collections::HashMap, sync::LazyLock};
+
+use super::Locale;
+
+type Catalogue = HashMap<&'static str, String>;
+
+static CATALOGUES: LazyLock<HashMap<Locale, Catalogue>> = LazyLock::new(|| {
+    HashMap::from([
+        (Locale::En, parse(include_str!("locales/en.ftl"))),
+        (Locale::Hi, parse(include_str!("locales/hi.ftl"))),
+        (Locale::Es, parse(include_str!("locales/es.ftl"))),
+    ])
+});
+
+fn parse(source: &'static str) -> Catalogue {
+    let mut catalogue = Catalogue::new();
+    let mut current: Option<&'static str> = None;
+    for line in source.lines() {
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        // indented lines continue the previous message
+        if line.starts_with([' ', '\t']) {
+            if let Some(message) = current.and_then(|key| catalogue.get_mut(key)) {
+                if !message.is_empty() {
+                    message.push('\n');
+                }
+                message.push_str(line.trim());
+            }
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            current = None;
+            continue;
+        };
+        let key = key.trim();
+        catalogue.insert(key, value.trim().to_string());
+        current = Some(key);
+    }
+    catalogue
+}
+
+pub(super) fn message(locale: Locale, key: &str) -> Option<&'static str> {
+    CATALOGUES
+        .get(&locale)?
+        .get(key)
+        .map(|message| message.as_str())
+}
+
+/// Replaces the `{ $arg }` and `{ $arg -> [variant] ... }` placeables of `pattern`
+pub(super) fn format_pattern(locale: Locale, pattern: &str, args: &[(&str, String)]) -> String {
+    let mut formatted = String::with_capacity(pattern.len());
+    let mut rest = pattern;
+    while let Some(start) = rest.find('{') {
+        // an unclosed brace is literal text
+        let Some(len) = closing_brace(&rest[start..]) else {
+            break;
+        };
+        formatted.push_str(&rest[..start]);
+        let placeable = &rest[start + 1..start + len];
+        match resolve(locale, placeable, args) {
+            Some(value) => formatted.push_str(&value),
+            None => formatted.push_str(&rest[start..=start + len]),
+        }
+        rest = &rest[start + len + 1..];
+    }
+    formatted.push_str(rest);
+    formatted
+}
+
+struct Variant {
+    key: String,
+    pattern: String,
+    default: bool,
+}
+
+/// Offset of the `}` closing the brace `text` starts with
+fn closing_brace(text: &str) -> Option<usize> {
+    let mut depth = 0;
+    for (i, c) in text.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn arg<'a>(args: &'a [(&str, String)], placeable: &str) -> Option<&'a str> {
+    let name = placeable.trim().trim_start_matches('$');
+    args.iter()
+        .find(|(arg, _)| *arg == name)
+        .map(|(_, value)| value.as_str())
+}
+
+/// Value of a placeable, `None` when its argument is missing
+fn resolve(locale: Locale, placeable: &str, args: &[(&str, String)]) -> Option<String> {
+    let Some((selector, variants)) = placeable.split_once("->") else {
+        return arg(args, placeable).map(str::to_string);
+    };
+    let value = arg(args, selector)?;
+    let variants = variants_of(variants);
+    let category = value
+        .parse::<f64>()
+        .ok()
+        .map(|n| plural_category(locale, n));
+    let variant = variants
+        .iter()
+        .find(|variant| variant.key == value)
+        .or_else(|| {
+            variants
+                .iter()
+                .find(|variant| Some(variant.key.as_str()) == category)
+        })
+        .or_else(|| variants.iter().find(|variant| variant.default))?;
+    Some(format_pattern(locale, &variant.pattern, args))
+}
+
+/// Variants of a select, one per `[key] pattern` line
+fn variants_of(select: &str) -> Vec<Variant> {
+    let mut variants: Vec<Variant> = Vec::new();
+    for line in select
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+    {
+        let (default, variant) = match line.strip_prefix('*') {
+            Some(variant) => (true, variant),
+            None => (false, line),
+        };
+        let key_and_pattern = variant
+            .strip_prefix('[')
+            .and_then(|variant| variant.split_once(']'));
+        match (key_and_pattern, variants.last_mut()) {
+            (Some((key, pattern)), _) => variants.push(Variant {
+                key: key.trim().to_string(),
+                pattern: pattern.trim().to_string(),
+                default,
+            }),
+            // the previous variant's pattern continues on this line
+            (None, Some(previous)) => {
+                previous.pattern.push('\n');
+                previous.pattern.push_str(line);
+            }
+            (None, None) => {}
+        }
+    }
+    variants
+}
+
+/// CLDR plural category of `n`, limited to the ones our locales use
+fn plural_category(locale: Locale, n: f64) -> &'static str {
+    let one = match locale {
+        Locale::En | Locale::Es => n == 1.0,
+        // hi: i = 0 or n = 1
+        Locale::Hi => (0.0..=1.0).contains(&n),
+    };
+    if one {
+        "one"
+    } else {
+        "other"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn catalogues_are_complete() {
+        let english = &CATALOGUES[&Locale::En];
+        for locale in [Locale::Hi, Locale::Es] {
+            let catalogue = &CATALOGUES[&locale];
+            for key in english.keys() {
+                assert!(catalogue.contains_key(key), "{locale} is missing {key}");
+            }
+            for key in catalogue.keys() {
+                assert!(english.contains_key(key), "{locale} has unknown {key}");
+            }
+        }
+    }
+
+    #[test]
+    fn placeables_and_continuations() {
+        let catalogue = parse("greeting =\n    Hello { $name }\n    Bye\n# comment\nplain = x");
+        assert_eq!(catalogue["greeting"], "Hello { $name }\nBye");
+        assert_eq!(
+            format_pattern(
+                Locale::En,
+                &catalogue["greeting"],
+                &[("name", "YRAL".into())]
+            ),
+            "Hello YRAL\nBye"
+        );
+        assert_eq!(
+            format_pattern(Locale::En, "{ $missing } left", &[]),
+            "{ $missing } left"
+        );
+        assert_eq!(format_pattern(Locale::En, "a {b", &[]), "a {b");
+        assert_eq!(catalogue["plain"], "x");
+    }
+
+    #[test]
+    fn plural_selectors() {
+        let catalogue = parse(
+            "moved =\n    { $count ->\n        [0] Nothing moved\n        [one] { $count } post moved\n       *[other] { $count } posts moved\n    }",
+        );
+        let moved = |locale, count: usize| {
+            format_pattern(locale, &catalogue["moved"], &[("count", count.to_string())])
+        };
+        assert_eq!(moved(Locale::En, 0), "Nothing moved");
+        assert_eq!(moved(Locale::En, 1), "1 post moved");
+        assert_eq!(moved(Locale::En, 2), "2 posts moved");
+        assert_eq!(plural_category(Locale::Hi, 0.0), "one");
+        assert_eq!(plural_category(Locale::Es, 0.0), "other");
+        assert_eq!(
+            format_pattern(
+                Locale::En,
+                "{ $count -> [one] x }",
+                &[("count", "2".into())]
+            ),
+            "{ $count -> [one] x }"
+        );
+    }
+}
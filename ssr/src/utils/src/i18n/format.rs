@@ -0,0 +1,150 @@
+//! Locale aware number and date formatting
+use yral_canisters_common::utils::token::balance::TokenBalance;
+
+use super::Locale;
+
+impl Locale {
+    fn separators(&self) -> (char, char) {
+        match self {
+            Locale::En | Locale::Hi => (',', '.'),
+            Locale::Es => ('.', ','),
+        }
+    }
+
+    /// Groups the integer digits, `digits` has no sign or separators
+    fn group_digits(&self, digits: &str, group: char) -> String {
+        // Spanish leaves four digit numbers ungrouped
+        if *self == Locale::Es && digits.len() <= 4 {
+            return digits.to_string();
+        }
+        // Indian grouping: the last three digits, then pairs (12,34,567)
+        let (head, tail) = if *self == Locale::Hi && digits.len() > 3 {
+            digits.split_at(digits.len() - 3)
+        } else {
+            ("", digits)
+        };
+        let (head_size, tail_size) = match self {
+            Locale::Hi => (2, 3),
+            _ => (3, 3),
+        };
+        let mut groups = vec![];
+        for (part, size) in [(head, head_size), (tail, tail_size)] {
+            let first = part.len() % size;
+            if first > 0 {
+                groups.push(&part[..first]);
+            }
+            groups.extend(
+                part.as_bytes()[first..]
+                    .chunks(size)
+                    .map(|chunk| std::str::from_utf8(chunk).expect("digits are ascii")),
+            );
+        }
+        groups.join(&group.to_string())
+    }
+
+    /// Formats a plain decimal number (e.g `-1234567.25`) for the locale
+    ///
+    /// anything else (e.g `1.2K`) is returned unchanged
+    pub fn format_decimal(&self, decimal: &str) -> String {
+        let plain = decimal.replace(',', "");
+        let (sign, unsigned) = match plain.strip_prefix('-') {
+            Some(unsigned) => ("-", unsigned),
+            None => ("", plain.as_str()),
+        };
+        let (integer, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+        if integer.is_empty()
+            || !integer.bytes().all(|b| b.is_ascii_digit())
+            || !fraction.bytes().all(|b| b.is_ascii_digit())
+        {
+            return decimal.to_string();
+        }
+
+        let (group, point) = self.separators();
+        let mut formatted = format!("{sign}{}", self.group_digits(integer, group));
+        if !fraction.is_empty() {
+            formatted.push(point);
+            formatted.push_str(fraction);
+        }
+        formatted
+    }
+
+    /// Name of the month, `month` is 1 based
+    pub fn month_name(&self, month: u8) -> &'static str {
+        const EN: [&str; 12] = [
+            "January",
+            "February",
+            "March",
+            "April",
+            "May",
+            "June",
+            "July",
+            "August",
+            "September",
+            "October",
+            "November",
+            "December",
+        ];
+        const HI: [&str; 12] = [
+            "जनवरी",
+            "फ़रवरी",
+            "मार्च",
+            "अप्रैल",
+            "मई",
+            "जून",
+            "जुलाई",
+            "अगस्त",
+            "सितंबर",
+            "अक्टूबर",
+            "नवंबर",
+            "दिसंबर",
+        ];
+        const ES: [&str; 12] = [
+            "enero",
+            "febrero",
+            "marzo",
+            "abril",
+            "mayo",
+            "junio",
+            "julio",
+            "agosto",
+            "septiembre",
+            "octubre",
+            "noviembre",
+            "diciembre",
+        ];
+        let names = match self {
+            Locale::En => &EN,
+            Locale::Hi => &HI,
+            Locale::Es => &ES,
+        };
+        names[(month as usize).clamp(1, 12) - 1]
+    }
+}
+
+pub trait LocalizedTokenBalance {
+    /// [`TokenBalance::humanize_float`] with the locale's separators
+    fn humanize_float_localized(&self, locale: Locale) -> String;
+}
+
+impl LocalizedTokenBalance for TokenBalance {
+    fn humanize_float_localized(&self, locale: Locale) -> String {
+        locale.format_decimal(&self.humanize_float())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decimals_use_the_locale_separators() {
+        assert_eq!(Locale::En.format_decimal("1234567.25"), "1,234,567.25");
+        assert_eq!(Locale::Hi.format_decimal("1234567.25"), "12,34,567.25");
+        assert_eq!(Locale::Es.format_decimal("1234567.25"), "1.234.567,25");
+        assert_eq!(Locale::Es.format_decimal("1234.5"), "1234,5");
+        assert_eq!(Locale::Hi.format_decimal("-999"), "-999");
+        assert_eq!(Locale::Hi.format_decimal("1000"), "1,000");
+        assert_eq!(Locale::En.format_decimal("1,000"), "1,000");
+        assert_eq!(Locale::En.format_decimal("1.2K"), "1.2K");
+    }
+}
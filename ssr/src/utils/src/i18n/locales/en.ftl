@@ -0,0 +1,198 @@
+# English, the fallback for missing translations
+
+## Posts
+post-unavailable = Post is unavailable
+go-back = Go back
+
+## Sharing
+link-copied = Link Copied!
+share = Share
+share-this-app = Share this app
+back = Back
+refer-share-text =
+    Join YRAL—the world's 1st social platform on BITCOIN
+    Get FREE { $signup_sats } YRAL Instantly
+    Additional { $referral_sats } YRAL when you log in using the link.
+
+## Settings
+settings = Settings
+follow-us-on = Follow us on
+view-profile = View Profile
+delete-account = Delete account
+language = Language
+active-sessions = Active sessions
+this-device = (this device)
+log-out = Log out
+log-out-other-sessions = Log out of all other sessions
+active-now = Active now
+last-active = Last active { $date }
+menu = Menu
+account-ready = Your YRAL account is ready. Log in with Google to save your progress and continue seamlessly.
+show-nsfw-videos = Show NSFW videos
+enable-notifications = Enable Notifications
+legal = Legal
+terms-of-service = Terms of service
+privacy-policy = Privacy Policy
+about-us = About Us
+help = Help
+talk-to-the-team = Talk to the team
+delete-your-account = Delete your account
+delete-account-confirm = Are you sure you want to delete your account?
+delete-account-permanent = This action is permanent and cannot be undone.
+delete-account-data-removed = All your data — including your Bitcoin and token balances — will be permanently removed from the platform.
+delete-account-cancel = No, take me back
+delete-account-yes = Yes, Delete
+deleting = Deleting...
+
+## Account merge
+merge-welcome = Welcome back!
+merge-summary = We merged your guest activity into your account
+sats-moved =
+    { $count ->
+        [one] { $count } SAT moved to your account
+       *[other] { $count } SATS moved to your account
+    }
+follows-moved =
+    { $count ->
+        [one] { $count } follow moved to your account
+       *[other] { $count } follows moved to your account
+    }
+posts-moved =
+    { $count ->
+        [one] { $count } post moved to your account
+       *[other] { $count } posts moved to your account
+    }
+posts-not-moved =
+    { $count ->
+        [one] { $count } post couldn't be moved, it stays on your guest profile { $profile }
+       *[other] { $count } posts couldn't be moved, they stay on your guest profile { $profile }
+    }
+
+## App banner
+app-banner-tagline = Get the full experience in the app
+app-banner-clipboard-notice = Opening the app copies a link to this page to your clipboard
+open = Open
+
+## Wallet
+token-sent = You’ve successfully sent { $amount } { $token } to your wallet.
+back-to-wallet = Back to wallet
+current-balance = Current balance: { $amount } { $token }
+
+## Profile
+unlock-profile = Unlock Profile
+unlock-profile-rewards = Log in to claim rewards, build your profile,
+unlock-profile-community = and join the { $name } community.
+loading = Loading...
+follow = Follow
+unfollow = Unfollow
+follow-back = Follow Back
+not-following-anyone = Not following anyone yet
+no-followers = No followers yet
+edit-profile = Edit Profile
+
+## Edit profile
+edit-profile-title = { $name } - Edit Profile
+user-name = User Name
+username-placeholder = Enter username
+username-rules = Username must be 3-15 characters. Letters and numbers only.
+username-invalid = Username must be 3-15 characters long and can only contain letters and numbers.
+invalid-input = Invalid input
+username-taken = This username is not available
+bio = Bio
+bio-placeholder = Tell us about yourself
+website = Website/URL
+website-placeholder = Your website URL
+invalid-url = Invalid URL format
+not-authenticated = Not authenticated
+profile-update-unavailable = Profile update not available for this account type
+update-failed = Update failed: { $error }
+network-error = Network error. Please try again.
+username-update-failed = Failed to update username: { $error }
+profile-updated = Profile updated successfully!
+username-updated = Username updated successfully!
+username-and-profile-updated = Username and Profile updated successfully!
+save = Save
+saving = Saving...
+cancel = Cancel
+edit-profile-picture = Edit Profile Picture
+click-to-upload-image = Click to upload an image
+change-image = Change Image
+image-too-large = File too large: { $size }MB (max 5MB)
+image-wrong-type = Please select an image file (JPEG, PNG, WebP)
+image-unsupported-format = Unsupported format. Please use JPEG, PNG, or WebP
+upload-failed = Upload failed: { $error }
+processing-failed = Processing failed: { $error }
+
+## Refer & earn
+refer-earn = Refer & Earn
+refer-earn-title = { $name } - Refer & Earn
+invite-and-get = Invite & get { $amount } YRAL
+how-it-works = How it works?
+refer-step = STEP { $step }
+refer-step-share = Share your link with a friend
+refer-step-login = Your friend logs in from the link
+refer-step-earn = You both earn { $amount } YRAL
+
+## Login
+login = Login
+connecting = Connecting...
+login-to-claim = Login to claim YRAL
+unlock-higher-bets = Unlock Higher Bets
+login-nudge-one-step = You're just 1 step away from high-stake fun.
+login-nudge-unlock-bets = Log in to unlock 5 YRAL bets!
+why-log-in = Why log in?
+higher-bets-higher-rewards = Higher bets, higher rewards
+enable-withdrawals = Enable withdrawals
+login-now = Login Now
+
+## Feed
+feed-caught-up = You're all caught up! Check back later for new videos
+feed-offline = Looks like you're offline, check your connection
+feed-unavailable = We're having trouble finding new videos for you
+feed-failed = Something went wrong loading more videos
+try-again = Try again
+
+## AI video
+ai-upload-title = { $name } AI - Upload
+create-ai-video = Create AI Video
+generate-video = Generate Video
+model = Model
+loading-models = Loading models...
+select-model = Select Model
+choose-model = Choose a model to generate video
+coming-soon = Coming Soon
+duration-seconds = { $count } Sec
+duration-minutes = { $count } Min
+duration-variable = Variable
+image = Image
+optional = (Optional)
+audio = Audio
+audio-required = (Required for Talking Head)
+click-to-upload-audio = Click to upload audio
+audio-uploaded = Audio uploaded
+prompt = Prompt
+prompt-placeholder = Enter the Prompt here...
+credits-required = Credits Required
+current-balance-loading = Current balance: Loading...
+current-balance-unknown-token = Current balance: Unknown token type
+current-balance-error = Current balance: Error loading
+loading-providers = Loading providers...
+free-ai-video = Enjoy 1 free AI video per day. Use credits for more.
+generating-and-uploading = Generating & Uploading...
+generate-and-upload = Generate & Upload Video
+uploading-video = Uploading video
+generating-video = Generating video
+almost-there = Almost there...
+may-take-minutes = This may take a few minutes...
+video-generated = Video generated successfully!
+video-ready = Your AI video is ready. You can re-generate or upload it.
+regenerate = Re-generate
+upload = Upload
+duration = Duration:
+auto-detected = Auto-detected
+video-format = Format:
+video-quality = Quality:
+video-unsupported = Your browser doesn't support video playback.
+ai-video-generated = AI Video generated Successfully!
+ai-video-processing = Your video is being processed and will appear in "Your Videos" under your profile shortly. Happy scrolling!
+done = Done
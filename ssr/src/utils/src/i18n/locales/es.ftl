@@ -0,0 +1,198 @@
+# Spanish
+
+## Posts
+post-unavailable = La publicación no está disponible
+go-back = Volver
+
+## Sharing
+link-copied = ¡Enlace copiado!
+share = Compartir
+share-this-app = Comparte esta app
+back = Atrás
+refer-share-text =
+    Únete a YRAL, la primera plataforma social del mundo en BITCOIN
+    Consigue { $signup_sats } YRAL GRATIS al instante
+    { $referral_sats } YRAL adicionales al iniciar sesión con el enlace.
+
+## Settings
+settings = Ajustes
+follow-us-on = Síguenos en
+view-profile = Ver perfil
+delete-account = Eliminar cuenta
+language = Idioma
+active-sessions = Sesiones activas
+this-device = (este dispositivo)
+log-out = Cerrar sesión
+log-out-other-sessions = Cerrar todas las demás sesiones
+active-now = Activo ahora
+last-active = Última actividad { $date }
+menu = Menú
+account-ready = Tu cuenta de YRAL está lista. Inicia sesión con Google para guardar tu progreso y continuar sin interrupciones.
+show-nsfw-videos = Mostrar videos NSFW
+enable-notifications = Activar notificaciones
+legal = Legal
+terms-of-service = Términos del servicio
+privacy-policy = Política de privacidad
+about-us = Sobre nosotros
+help = Ayuda
+talk-to-the-team = Habla con el equipo
+delete-your-account = Elimina tu cuenta
+delete-account-confirm = ¿Seguro que quieres eliminar tu cuenta?
+delete-account-permanent = Esta acción es permanente y no se puede deshacer.
+delete-account-data-removed = Todos tus datos, incluidos tus saldos de Bitcoin y tokens, se eliminarán de la plataforma de forma permanente.
+delete-account-cancel = No, volver
+delete-account-yes = Sí, eliminar
+deleting = Eliminando...
+
+## Account merge
+merge-welcome = ¡Bienvenido de nuevo!
+merge-summary = Hemos unido tu actividad como invitado a tu cuenta
+sats-moved =
+    { $count ->
+        [one] { $count } SAT movido a tu cuenta
+       *[other] { $count } SATS movidos a tu cuenta
+    }
+follows-moved =
+    { $count ->
+        [one] { $count } seguimiento movido a tu cuenta
+       *[other] { $count } seguimientos movidos a tu cuenta
+    }
+posts-moved =
+    { $count ->
+        [one] { $count } publicación movida a tu cuenta
+       *[other] { $count } publicaciones movidas a tu cuenta
+    }
+posts-not-moved =
+    { $count ->
+        [one] { $count } publicación no se pudo mover, se queda en tu perfil de invitado { $profile }
+       *[other] { $count } publicaciones no se pudieron mover, se quedan en tu perfil de invitado { $profile }
+    }
+
+## App banner
+app-banner-tagline = Disfruta de la experiencia completa en la app
+app-banner-clipboard-notice = Al abrir la app se copiará un enlace a esta página en tu portapapeles
+open = Abrir
+
+## Wallet
+token-sent = Has enviado { $amount } { $token } a tu billetera correctamente.
+back-to-wallet = Volver a la billetera
+current-balance = Saldo actual: { $amount } { $token }
+
+## Profile
+unlock-profile = Desbloquea tu perfil
+unlock-profile-rewards = Inicia sesión para reclamar recompensas, crear tu perfil
+unlock-profile-community = y unirte a la comunidad de { $name }.
+loading = Cargando...
+follow = Seguir
+unfollow = Dejar de seguir
+follow-back = Seguir también
+not-following-anyone = Aún no sigues a nadie
+no-followers = Aún no hay seguidores
+edit-profile = Editar perfil
+
+## Edit profile
+edit-profile-title = { $name } - Editar perfil
+user-name = Nombre de usuario
+username-placeholder = Escribe un nombre de usuario
+username-rules = El nombre de usuario debe tener de 3 a 15 caracteres. Solo letras y números.
+username-invalid = El nombre de usuario debe tener de 3 a 15 caracteres y solo puede contener letras y números.
+invalid-input = Entrada no válida
+username-taken = Este nombre de usuario no está disponible
+bio = Biografía
+bio-placeholder = Cuéntanos sobre ti
+website = Sitio web/URL
+website-placeholder = La URL de tu sitio web
+invalid-url = Formato de URL no válido
+not-authenticated = No has iniciado sesión
+profile-update-unavailable = No se puede actualizar el perfil de este tipo de cuenta
+update-failed = No se pudo actualizar: { $error }
+network-error = Error de red. Inténtalo de nuevo.
+username-update-failed = No se pudo actualizar el nombre de usuario: { $error }
+profile-updated = ¡Perfil actualizado!
+username-updated = ¡Nombre de usuario actualizado!
+username-and-profile-updated = ¡Nombre de usuario y perfil actualizados!
+save = Guardar
+saving = Guardando...
+cancel = Cancelar
+edit-profile-picture = Editar foto de perfil
+click-to-upload-image = Haz clic para subir una imagen
+change-image = Cambiar imagen
+image-too-large = Archivo demasiado grande: { $size } MB (máx. 5 MB)
+image-wrong-type = Selecciona un archivo de imagen (JPEG, PNG, WebP)
+image-unsupported-format = Formato no compatible. Usa JPEG, PNG o WebP
+upload-failed = No se pudo subir: { $error }
+processing-failed = No se pudo procesar: { $error }
+
+## Refer & earn
+refer-earn = Invita y gana
+refer-earn-title = { $name } - Invita y gana
+invite-and-get = Invita y recibe { $amount } YRAL
+how-it-works = ¿Cómo funciona?
+refer-step = PASO { $step }
+refer-step-share = Comparte tu enlace con un amigo
+refer-step-login = Tu amigo inicia sesión desde el enlace
+refer-step-earn = Ambos ganan { $amount } YRAL
+
+## Login
+login = Iniciar sesión
+connecting = Conectando...
+login-to-claim = Inicia sesión para reclamar YRAL
+unlock-higher-bets = Desbloquea apuestas más altas
+login-nudge-one-step = Estás a solo un paso de la diversión de alto riesgo.
+login-nudge-unlock-bets = ¡Inicia sesión para desbloquear apuestas de 5 YRAL!
+why-log-in = ¿Por qué iniciar sesión?
+higher-bets-higher-rewards = Apuestas más altas, mayores recompensas
+enable-withdrawals = Habilita los retiros
+login-now = Iniciar sesión ahora
+
+## Feed
+feed-caught-up = ¡Estás al día! Vuelve más tarde para ver videos nuevos
+feed-offline = Parece que no tienes conexión, revisa tu conexión
+feed-unavailable = Tenemos problemas para encontrar videos nuevos para ti
+feed-failed = Algo salió mal al cargar más videos
+try-again = Reintentar
+
+## AI video
+ai-upload-title = { $name } AI - Subir
+create-ai-video = Crear video con IA
+generate-video = Generar video
+model = Modelo
+loading-models = Cargando modelos...
+select-model = Selecciona un modelo
+choose-model = Elige un modelo para generar el video
+coming-soon = Próximamente
+duration-seconds = { $count } s
+duration-minutes = { $count } min
+duration-variable = Variable
+image = Imagen
+optional = (Opcional)
+audio = Audio
+audio-required = (Obligatorio para Talking Head)
+click-to-upload-audio = Haz clic para subir un audio
+audio-uploaded = Audio subido
+prompt = Prompt
+prompt-placeholder = Escribe el prompt aquí...
+credits-required = Créditos necesarios
+current-balance-loading = Saldo actual: cargando...
+current-balance-unknown-token = Saldo actual: tipo de token desconocido
+current-balance-error = Saldo actual: error al cargar
+loading-providers = Cargando proveedores...
+free-ai-video = Disfruta de 1 video con IA gratis al día. Usa créditos para más.
+generating-and-uploading = Generando y subiendo...
+generate-and-upload = Generar y subir video
+uploading-video = Subiendo video
+generating-video = Generando video
+almost-there = Ya casi...
+may-take-minutes = Esto puede tardar unos minutos...
+video-generated = ¡Video generado!
+video-ready = Tu video con IA está listo. Puedes volver a generarlo o subirlo.
+regenerate = Volver a generar
+upload = Subir
+duration = Duración:
+auto-detected = Detectada automáticamente
+video-format = Formato:
+video-quality = Calidad:
+video-unsupported = Tu navegador no puede reproducir videos.
+ai-video-generated = ¡Video con IA generado!
+ai-video-processing = Tu video se está procesando y pronto aparecerá en "Tus videos" en tu perfil. ¡Feliz scroll!
+done = Listo
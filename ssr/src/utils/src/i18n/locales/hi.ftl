@@ -0,0 +1,198 @@
+# Hindi
+
+## Posts
+post-unavailable = यह पोस्ट उपलब्ध नहीं है
+go-back = वापस जाएँ
+
+## Sharing
+link-copied = लिंक कॉपी हो गया!
+share = शेयर करें
+share-this-app = यह ऐप शेयर करें
+back = वापस
+refer-share-text =
+    YRAL से जुड़ें—BITCOIN पर दुनिया का पहला सोशल प्लेटफ़ॉर्म
+    तुरंत { $signup_sats } YRAL मुफ़्त पाएँ
+    लिंक से लॉग इन करने पर { $referral_sats } YRAL अतिरिक्त।
+
+## Settings
+settings = सेटिंग्स
+follow-us-on = हमें फ़ॉलो करें
+view-profile = प्रोफ़ाइल देखें
+delete-account = अकाउंट हटाएँ
+language = भाषा
+active-sessions = सक्रिय सेशन
+this-device = (यह डिवाइस)
+log-out = लॉग आउट
+log-out-other-sessions = बाकी सभी सेशन से लॉग आउट करें
+active-now = अभी सक्रिय
+last-active = आखिरी बार सक्रिय { $date }
+menu = मेन्यू
+account-ready = आपका YRAL अकाउंट तैयार है। अपनी प्रगति सेव करने और बिना रुकावट जारी रखने के लिए Google से लॉग इन करें।
+show-nsfw-videos = NSFW वीडियो दिखाएँ
+enable-notifications = नोटिफ़िकेशन चालू करें
+legal = कानूनी
+terms-of-service = सेवा की शर्तें
+privacy-policy = गोपनीयता नीति
+about-us = हमारे बारे में
+help = मदद
+talk-to-the-team = टीम से बात करें
+delete-your-account = अपना अकाउंट डिलीट करें
+delete-account-confirm = क्या आप वाकई अपना अकाउंट डिलीट करना चाहते हैं?
+delete-account-permanent = यह कार्रवाई स्थायी है और इसे पहले जैसा नहीं किया जा सकता।
+delete-account-data-removed = आपका सारा डेटा — आपके Bitcoin और टोकन बैलेंस सहित — प्लेटफ़ॉर्म से हमेशा के लिए हटा दिया जाएगा।
+delete-account-cancel = नहीं, मुझे वापस ले चलें
+delete-account-yes = हाँ, डिलीट करें
+deleting = डिलीट हो रहा है...
+
+## Account merge
+merge-welcome = आपका फिर से स्वागत है!
+merge-summary = हमने आपकी गेस्ट गतिविधि आपके अकाउंट में जोड़ दी है
+sats-moved =
+    { $count ->
+        [one] { $count } SATS आपके अकाउंट में भेजा गया
+       *[other] { $count } SATS आपके अकाउंट में भेजे गए
+    }
+follows-moved =
+    { $count ->
+        [one] { $count } फ़ॉलो आपके अकाउंट में भेजा गया
+       *[other] { $count } फ़ॉलो आपके अकाउंट में भेजे गए
+    }
+posts-moved =
+    { $count ->
+        [one] { $count } पोस्ट आपके अकाउंट में ले जाई गई
+       *[other] { $count } पोस्ट आपके अकाउंट में ले जाई गईं
+    }
+posts-not-moved =
+    { $count ->
+        [one] { $count } पोस्ट ले जाई नहीं जा सकी, वह आपकी गेस्ट प्रोफ़ाइल { $profile } पर रहेगी
+       *[other] { $count } पोस्ट ले जाई नहीं जा सकीं, वे आपकी गेस्ट प्रोफ़ाइल { $profile } पर रहेंगी
+    }
+
+## App banner
+app-banner-tagline = ऐप में पूरा अनुभव पाएँ
+app-banner-clipboard-notice = ऐप खोलने पर इस पेज का लिंक आपके क्लिपबोर्ड पर कॉपी हो जाएगा
+open = खोलें
+
+## Wallet
+token-sent = आपने सफलतापूर्वक { $amount } { $token } अपने वॉलेट में भेज दिए हैं।
+back-to-wallet = वॉलेट पर वापस जाएँ
+current-balance = मौजूदा बैलेंस: { $amount } { $token }
+
+## Profile
+unlock-profile = प्रोफ़ाइल अनलॉक करें
+unlock-profile-rewards = इनाम पाने, अपनी प्रोफ़ाइल बनाने
+unlock-profile-community = और { $name } कम्युनिटी से जुड़ने के लिए लॉग इन करें।
+loading = लोड हो रहा है...
+follow = फ़ॉलो करें
+unfollow = अनफ़ॉलो करें
+follow-back = वापस फ़ॉलो करें
+not-following-anyone = अभी किसी को फ़ॉलो नहीं कर रहे
+no-followers = अभी कोई फ़ॉलोअर नहीं
+edit-profile = प्रोफ़ाइल एडिट करें
+
+## Edit profile
+edit-profile-title = { $name } - प्रोफ़ाइल एडिट करें
+user-name = यूज़रनेम
+username-placeholder = यूज़रनेम डालें
+username-rules = यूज़रनेम 3-15 अक्षरों का हो। सिर्फ़ अक्षर और अंक।
+username-invalid = यूज़रनेम 3-15 अक्षरों का होना चाहिए और उसमें सिर्फ़ अक्षर और अंक हो सकते हैं।
+invalid-input = अमान्य इनपुट
+username-taken = यह यूज़रनेम उपलब्ध नहीं है
+bio = बायो
+bio-placeholder = अपने बारे में बताएँ
+website = वेबसाइट/URL
+website-placeholder = आपकी वेबसाइट का URL
+invalid-url = URL का फ़ॉर्मैट अमान्य है
+not-authenticated = आप लॉग इन नहीं हैं
+profile-update-unavailable = इस तरह के अकाउंट की प्रोफ़ाइल अपडेट नहीं की जा सकती
+update-failed = अपडेट नहीं हो सका: { $error }
+network-error = नेटवर्क एरर। फिर से कोशिश करें।
+username-update-failed = यूज़रनेम अपडेट नहीं हो सका: { $error }
+profile-updated = प्रोफ़ाइल अपडेट हो गई!
+username-updated = यूज़रनेम अपडेट हो गया!
+username-and-profile-updated = यूज़रनेम और प्रोफ़ाइल अपडेट हो गए!
+save = सेव करें
+saving = सेव हो रहा है...
+cancel = रद्द करें
+edit-profile-picture = प्रोफ़ाइल फ़ोटो एडिट करें
+click-to-upload-image = इमेज अपलोड करने के लिए क्लिक करें
+change-image = इमेज बदलें
+image-too-large = फ़ाइल बहुत बड़ी है: { $size }MB (ज़्यादा से ज़्यादा 5MB)
+image-wrong-type = कोई इमेज फ़ाइल चुनें (JPEG, PNG, WebP)
+image-unsupported-format = यह फ़ॉर्मैट समर्थित नहीं है। JPEG, PNG या WebP इस्तेमाल करें
+upload-failed = अपलोड नहीं हो सका: { $error }
+processing-failed = प्रोसेस नहीं हो सका: { $error }
+
+## Refer & earn
+refer-earn = रेफ़र करें और कमाएँ
+refer-earn-title = { $name } - रेफ़र करें और कमाएँ
+invite-and-get = इनवाइट करें और { $amount } YRAL पाएँ
+how-it-works = यह कैसे काम करता है?
+refer-step = स्टेप { $step }
+refer-step-share = अपना लिंक किसी दोस्त के साथ शेयर करें
+refer-step-login = आपका दोस्त लिंक से लॉग इन करे
+refer-step-earn = आप दोनों को { $amount } YRAL मिलेंगे
+
+## Login
+login = लॉग इन
+connecting = कनेक्ट हो रहा है...
+login-to-claim = YRAL पाने के लिए लॉग इन करें
+unlock-higher-bets = बड़ी बेट अनलॉक करें
+login-nudge-one-step = हाई-स्टेक मज़े से आप बस 1 कदम दूर हैं।
+login-nudge-unlock-bets = 5 YRAL की बेट अनलॉक करने के लिए लॉग इन करें!
+why-log-in = लॉग इन क्यों करें?
+higher-bets-higher-rewards = बड़ी बेट, बड़े इनाम
+enable-withdrawals = निकासी चालू करें
+login-now = अभी लॉग इन करें
+
+## Feed
+feed-caught-up = आपने सब देख लिया! नए वीडियो के लिए बाद में आएँ
+feed-offline = लगता है आप ऑफ़लाइन हैं, अपना कनेक्शन जाँचें
+feed-unavailable = आपके लिए नए वीडियो ढूँढने में दिक्कत हो रही है
+feed-failed = और वीडियो लोड करने में कुछ गड़बड़ हो गई
+try-again = फिर से कोशिश करें
+
+## AI video
+ai-upload-title = { $name } AI - अपलोड
+create-ai-video = AI वीडियो बनाएँ
+generate-video = वीडियो बनाएँ
+model = मॉडल
+loading-models = मॉडल लोड हो रहे हैं...
+select-model = मॉडल चुनें
+choose-model = वीडियो बनाने के लिए कोई मॉडल चुनें
+coming-soon = जल्द आ रहा है
+duration-seconds = { $count } सेकंड
+duration-minutes = { $count } मिनट
+duration-variable = बदलती रहती है
+image = इमेज
+optional = (वैकल्पिक)
+audio = ऑडियो
+audio-required = (Talking Head के लिए ज़रूरी)
+click-to-upload-audio = ऑडियो अपलोड करने के लिए क्लिक करें
+audio-uploaded = ऑडियो अपलोड हो गया
+prompt = प्रॉम्प्ट
+prompt-placeholder = यहाँ प्रॉम्प्ट लिखें...
+credits-required = ज़रूरी क्रेडिट
+current-balance-loading = मौजूदा बैलेंस: लोड हो रहा है...
+current-balance-unknown-token = मौजूदा बैलेंस: अज्ञात टोकन
+current-balance-error = मौजूदा बैलेंस: लोड नहीं हो सका
+loading-providers = प्रोवाइडर लोड हो रहे हैं...
+free-ai-video = हर दिन 1 AI वीडियो मुफ़्त पाएँ। ज़्यादा के लिए क्रेडिट इस्तेमाल करें।
+generating-and-uploading = बन रहा है और अपलोड हो रहा है...
+generate-and-upload = वीडियो बनाएँ और अपलोड करें
+uploading-video = वीडियो अपलोड हो रहा है
+generating-video = वीडियो बन रहा है
+almost-there = बस हो गया...
+may-take-minutes = इसमें कुछ मिनट लग सकते हैं...
+video-generated = वीडियो बन गया!
+video-ready = आपका AI वीडियो तैयार है। आप इसे फिर से बना सकते हैं या अपलोड कर सकते हैं।
+regenerate = फिर से बनाएँ
+upload = अपलोड करें
+duration = अवधि:
+auto-detected = अपने-आप पता चली
+video-format = फ़ॉर्मैट:
+video-quality = क्वालिटी:
+video-unsupported = आपका ब्राउज़र वीडियो नहीं चला सकता।
+ai-video-generated = AI वीडियो बन गया!
+ai-video-processing = आपका वीडियो प्रोसेस हो रहा है और जल्द ही आपकी प्रोफ़ाइल में "आपके वीडियो" में दिखेगा। मज़े से स्क्रॉल करें!
+done = हो गया
@@ -0,0 +1,223 @@
+//! Translations of user facing strings
+//!
+//! catalogues are a subset of [Fluent](https://projectfluent.org): `key = value`
+//! messages, indented continuation lines and `{ $arg }` placeables
+mod catalogue;
+pub mod format;
+
+use std::{
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+    time::Duration,
+};
+
+use codee::string::FromToStringCodec;
+use consts::LOCALE_COOKIE;
+use leptos::prelude::*;
+use leptos_use::{use_cookie_with_options, SameSite, UseCookieOptions};
+
+const LOCALE_COOKIE_MAX_AGE: Duration = Duration::from_secs(365 * 24 * 60 * 60);
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Locale {
+    #[default]
+    En,
+    Hi,
+    Es,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 3] = [Locale::En, Locale::Hi, Locale::Es];
+
+    /// BCP 47 language tag
+    pub fn code(&self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::Hi => "hi",
+            Locale::Es => "es",
+        }
+    }
+
+    /// Name of the language, in that language
+    pub fn native_name(&self) -> &'static str {
+        match self {
+            Locale::En => "English",
+            Locale::Hi => "हिन्दी",
+            Locale::Es => "Español",
+        }
+    }
+
+    /// Supported locale of a language tag (e.g `hi-IN`), ignoring the region
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let language = tag.trim().split(['-', '_']).next()?;
+        Self::ALL
+            .into_iter()
+            .find(|locale| locale.code().eq_ignore_ascii_case(language))
+    }
+
+    /// Most preferred supported locale of an `Accept-Language` header
+    pub fn negotiate(accept_language: &str) -> Self {
+        let mut preferences: Vec<(&str, f32)> = accept_language
+            .split(',')
+            .filter_map(|range| {
+                let mut parts = range.split(';');
+                let tag = parts.next()?.trim();
+                let quality = parts
+                    .find_map(|param| param.trim().strip_prefix("q="))
+                    .map_or(Some(1.0), |q| q.parse().ok())?;
+                Some((tag, quality))
+            })
+            .collect();
+        // stable, so equally preferred languages keep their order
+        preferences.sort_by(|a, b| b.1.total_cmp(&a.1));
+        preferences
+            .into_iter()
+            .filter(|(_, quality)| *quality > 0.0)
+            .find_map(|(tag, _)| Self::from_tag(tag))
+            .unwrap_or_default()
+    }
+
+    /// Locale the browser asks for, from `Accept-Language` during SSR
+    fn detect() -> Self {
+        #[cfg(feature = "hydrate")]
+        {
+            window()
+                .navigator()
+                .language()
+                .map(|language| Self::negotiate(&language))
+                .unwrap_or_default()
+        }
+
+        #[cfg(not(feature = "hydrate"))]
+        {
+            use axum::http::{header::ACCEPT_LANGUAGE, request::Parts};
+
+            let parts: Option<Parts> = use_context();
+            parts
+                .as_ref()
+                .and_then(|parts| parts.headers.get(ACCEPT_LANGUAGE))
+                .and_then(|header| header.to_str().ok())
+                .map(Self::negotiate)
+                .unwrap_or_default()
+        }
+    }
+}
+
+impl Display for Locale {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+impl FromStr for Locale {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_tag(s).ok_or_else(|| format!("unsupported locale: {s}"))
+    }
+}
+
+/// Translates `key`, falling back to English and then to the key itself
+pub fn translate(locale: Locale, key: &str, args: &[(&str, String)]) -> String {
+    let translated = catalogue::message(locale, key)
+        .map(|pattern| (locale, pattern))
+        .or_else(|| catalogue::message(Locale::En, key).map(|pattern| (Locale::En, pattern)));
+    let Some((locale, pattern)) = translated else {
+        return key.to_string();
+    };
+    catalogue::format_pattern(locale, pattern, args)
+}
+
+/// Locale of the app, provided by [`provide_i18n`]
+#[derive(Clone, Copy)]
+pub struct I18n {
+    locale: RwSignal<Locale>,
+    set_locale_cookie: WriteSignal<Option<Locale>>,
+}
+
+impl I18n {
+    pub fn locale(&self) -> Locale {
+        self.locale.get()
+    }
+
+    pub fn locale_untracked(&self) -> Locale {
+        self.locale.get_untracked()
+    }
+
+    /// Switches the locale, the choice is remembered across visits
+    pub fn set_locale(&self, locale: Locale) {
+        self.locale.set(locale);
+        self.set_locale_cookie.set(Some(locale));
+    }
+
+    pub fn tr(&self, key: &str, args: &[(&str, String)]) -> String {
+        translate(self.locale(), key, args)
+    }
+
+    /// Translation for event handlers and other untracked contexts
+    pub fn tr_untracked(&self, key: &str, args: &[(&str, String)]) -> String {
+        translate(self.locale_untracked(), key, args)
+    }
+}
+
+/// Picks the locale from the user's choice, or the browser's preference
+///
+/// the choice is stored in a cookie, so that the server renders the same
+/// locale the client hydrates with
+pub fn provide_i18n() -> I18n {
+    let (locale_cookie, set_locale_cookie) = use_cookie_with_options::<Locale, FromToStringCodec>(
+        LOCALE_COOKIE,
+        UseCookieOptions::default()
+            .path("/")
+            .max_age(LOCALE_COOKIE_MAX_AGE.as_millis() as i64)
+            .same_site(SameSite::Lax),
+    );
+    let locale = locale_cookie.get_untracked().unwrap_or_else(|| {
+        let locale = Locale::detect();
+        set_locale_cookie.set(Some(locale));
+        locale
+    });
+
+    let i18n = I18n {
+        locale: RwSignal::new(locale),
+        set_locale_cookie,
+    };
+    provide_context(i18n);
+    i18n
+}
+
+pub fn use_i18n() -> I18n {
+    expect_context()
+}
+
+/// Reactive translation, for `view!` templates
+///
+/// `t!("link-copied")`, or with arguments `t!("sats-moved", count = 10)`
+#[macro_export]
+macro_rules! t {
+    ($key:literal $(, $arg:ident = $value:expr)* $(,)?) => {{
+        let i18n = $crate::i18n::use_i18n();
+        move || i18n.tr($key, &[$((stringify!($arg), ($value).to_string())),*])
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accept_language_is_negotiated() {
+        assert_eq!(Locale::negotiate("hi-IN,hi;q=0.9,en-US;q=0.8"), Locale::Hi);
+        assert_eq!(Locale::negotiate("fr-FR,es;q=0.7,en;q=0.5"), Locale::Es);
+        assert_eq!(Locale::negotiate("en;q=0.5,es"), Locale::Es);
+        assert_eq!(Locale::negotiate("fr, de;q=0.9"), Locale::En);
+        assert_eq!(Locale::negotiate("hi;q=0, es;q=0.1"), Locale::Es);
+        assert_eq!(Locale::negotiate(""), Locale::En);
+    }
+
+    #[test]
+    fn missing_translations_fall_back() {
+        assert_eq!(translate(Locale::Hi, "no-such-key", &[]), "no-such-key");
+        assert_eq!(translate(Locale::Es, "open", &[]), "Abrir");
+    }
+}
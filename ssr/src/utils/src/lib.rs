@@ -7,6 +7,7 @@ pub mod client_ip;
 pub mod event_streaming;
 pub mod health;
 pub mod host;
+pub mod i18n;
 pub mod icon;
 pub mod local_storage;
 pub mod mixpanel;
@@ -18,9 +19,11 @@ pub mod profile;
 pub mod qstash;
 pub mod report;
 pub mod route;
+pub mod seen_posts;
 pub mod sentry;
 pub mod time;
 pub mod types;
+pub mod video_prefetch;
 pub mod web;
 /// Wrapper for PartialEq that always returns false
 /// this is currently only used for resources
@@ -0,0 +1,52 @@
+use leptos::server_fn::{
+    codec::JsonEncoding,
+    error::{FromServerFnError, ServerFnErrorErr},
+};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Failure modes of the ML feed
+#[derive(Debug, Clone, PartialEq, Eq, Error, Serialize, Deserialize)]
+pub enum FeedError {
+    #[error("feed service unreachable: {0}")]
+    Network(String),
+    #[error("feed service responded with {status}: {body}")]
+    Upstream { status: u16, body: String },
+    #[error("invalid feed response: {0}")]
+    Decode(String),
+    #[error("no posts available")]
+    Empty,
+    #[error("feed service is paused after repeated failures")]
+    CircuitOpen,
+    #[error("{0}")]
+    ServerFn(String),
+}
+
+impl FeedError {
+    /// Whether retrying the same request may succeed
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Self::Network(_) | Self::Empty => true,
+            Self::Upstream { status, .. } => *status == 429 || *status >= 500,
+            Self::Decode(_) | Self::CircuitOpen | Self::ServerFn(_) => false,
+        }
+    }
+}
+
+impl From<reqwest::Error> for FeedError {
+    fn from(e: reqwest::Error) -> Self {
+        if e.is_decode() {
+            Self::Decode(e.to_string())
+        } else {
+            Self::Network(e.to_string())
+        }
+    }
+}
+
+impl FromServerFnError for FeedError {
+    type Encoder = JsonEncoding;
+
+    fn from_server_fn_error(value: ServerFnErrorErr) -> Self {
+        Self::ServerFn(value.to_string())
+    }
+}
@@ -0,0 +1,40 @@
+use super::{FeedError, FeedRequest, FeedSource, FeedSourceImpl, PostItem};
+
+/// Feed source that tries each of its sources in order
+///
+/// The first source to return a non-empty feed wins. If none of them yields
+/// any posts, the last error (if any) is returned.
+#[derive(Clone, Default)]
+pub struct FallbackFeedSource {
+    sources: Vec<FeedSourceImpl>,
+}
+
+impl FallbackFeedSource {
+    pub fn new(sources: Vec<FeedSourceImpl>) -> Self {
+        Self { sources }
+    }
+}
+
+impl FeedSource for FallbackFeedSource {
+    async fn recommendations(&self, req: FeedRequest) -> Result<Vec<PostItem>, FeedError> {
+        let mut last_err = None;
+        for (idx, source) in self.sources.iter().enumerate() {
+            // boxed to break the recursive future type
+            match Box::pin(source.recommendations(req.clone())).await {
+                Ok(posts) if !posts.is_empty() => return Ok(posts),
+                Ok(_) => {
+                    leptos::logging::warn!("feed source #{idx} returned empty results");
+                }
+                Err(e) => {
+                    leptos::logging::warn!("feed source #{idx} failed: {e}");
+                    last_err = Some(e);
+                }
+            }
+        }
+
+        match last_err {
+            Some(e) => Err(e),
+            None => Ok(vec![]),
+        }
+    }
+}
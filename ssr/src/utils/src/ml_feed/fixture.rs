@@ -0,0 +1,52 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use super::{FeedError, FeedRequest, FeedSource, PostItem};
+
+/// In-memory feed source serving a fixed set of posts
+///
+/// Every call hands out the next `count` posts, wrapping around once the
+/// fixture is exhausted. Useful for running the feed against a local stand-in
+/// of the recsys service.
+#[derive(Clone, Default)]
+pub struct FixtureFeedSource {
+    posts: Arc<[PostItem]>,
+    cursor: Arc<AtomicUsize>,
+}
+
+impl FixtureFeedSource {
+    pub fn new(posts: Vec<PostItem>) -> Self {
+        Self {
+            posts: posts.into(),
+            cursor: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Load the fixture from a JSON file containing an array of [`PostItem`]
+    #[cfg(feature = "ssr")]
+    pub fn from_json_file(path: impl AsRef<std::path::Path>) -> Result<Self, anyhow::Error> {
+        let raw = std::fs::read_to_string(path)?;
+        let posts: Vec<PostItem> = serde_json::from_str(&raw)?;
+        Ok(Self::new(posts))
+    }
+}
+
+impl FeedSource for FixtureFeedSource {
+    async fn recommendations(&self, req: FeedRequest) -> Result<Vec<PostItem>, FeedError> {
+        if self.posts.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let count = (req.count as usize).min(self.posts.len());
+        let start = self.cursor.fetch_add(count, Ordering::Relaxed);
+        let posts = (start..start + count)
+            .map(|idx| &self.posts[idx % self.posts.len()])
+            .filter(|post| !req.exclude.contains(&post.video_id))
+            .cloned()
+            .collect();
+
+        Ok(posts)
+    }
+}
@@ -1,11 +1,22 @@
+mod error;
+mod fallback;
+mod fixture;
+mod recsys;
+mod retry;
+
 use candid::Principal;
+use futures::{stream, StreamExt};
+use leptos::{prelude::*, server_fn::codec::Json};
 use serde::Deserialize;
 use serde::Serialize;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::str::FromStr;
-use yral_canisters_common::utils::posts::PostDetails;
+use yral_canisters_common::{utils::posts::PostDetails, Canisters};
 
-const RECOMMENDATION_SERVICE_URL: &str = "https://recsys-on-premise.fly.dev";
+pub use error::FeedError;
+pub use fallback::FallbackFeedSource;
+pub use fixture::FixtureFeedSource;
+pub use recsys::{RecsysFeedSource, RECOMMENDATION_SERVICE_URL};
 
 /// Piece of post details that should be available as quickly as possible to ensure fast loading of the infinite scroller
 #[derive(Clone)]
@@ -13,7 +24,8 @@ pub struct QuickPostDetails {
     pub video_uid: String,
     pub canister_id: Principal,
     pub publisher_user_id: Principal,
-    pub nsfw_probability: f32,
+    /// `None` if the post hasn't been scored yet
+    pub nsfw_probability: Option<f32>,
     pub post_id: String,
 }
 
@@ -24,7 +36,7 @@ impl From<PostDetails> for QuickPostDetails {
             canister_id: value.canister_id,
             post_id: value.post_id,
             publisher_user_id: value.poster_principal,
-            nsfw_probability: value.nsfw_probability,
+            nsfw_probability: Some(value.nsfw_probability),
         }
     }
 }
@@ -39,6 +51,9 @@ pub struct VideoItemV2 {
     pub publisher_user_id: String,
     pub num_views_loggedin: u64,
     pub num_views_all: u64,
+    /// `None` if the post hasn't been scored yet
+    #[serde(default)]
+    pub nsfw_probability: Option<f32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -51,7 +66,7 @@ pub struct FeedResponseV2 {
 }
 
 /// Recommendation type for the feed
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
 pub enum RecType {
     #[default]
     Mixed,
@@ -69,6 +84,50 @@ impl RecType {
     }
 }
 
+/// Posts with an nsfw probability at or above this are never part of a clean feed
+pub const NSFW_PROBABILITY_THRESHOLD: f32 = 0.4;
+/// Unscored posts of a feed response looked up at a time
+const SCORE_LOOKUP_CONCURRENCY: usize = 10;
+
+/// Content rating allowed in the feed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ContentFilter {
+    #[default]
+    Clean,
+    Nsfw,
+}
+
+impl ContentFilter {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ContentFilter::Clean => "clean",
+            ContentFilter::Nsfw => "nsfw",
+        }
+    }
+
+    /// Unscored posts (`None`) are treated as nsfw, see [`score_unscored`]
+    pub fn allows(&self, nsfw_probability: Option<f32>) -> bool {
+        match self {
+            ContentFilter::Clean => {
+                nsfw_probability.is_some_and(|prob| prob < NSFW_PROBABILITY_THRESHOLD)
+            }
+            ContentFilter::Nsfw => true,
+        }
+    }
+}
+
+/// Parameters for a single recommendations request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeedRequest {
+    pub user_id: Principal,
+    pub count: u32,
+    pub rec_type: RecType,
+    pub content: ContentFilter,
+    /// video ids that must not be part of the response
+    #[serde(default)]
+    pub exclude: Vec<String>,
+}
+
 /// PostItem compatible with existing code
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PostItem {
@@ -77,6 +136,9 @@ pub struct PostItem {
     pub post_id: String,
     pub publisher_user_id: Principal,
     pub views: u64,
+    /// `None` if the post hasn't been scored yet
+    #[serde(default)]
+    pub nsfw_probability: Option<f32>,
 }
 
 impl TryFrom<VideoItemV2> for PostItem {
@@ -91,108 +153,249 @@ impl TryFrom<VideoItemV2> for PostItem {
             publisher_user_id: Principal::from_str(&item.publisher_user_id)
                 .map_err(|e| anyhow::anyhow!("Invalid publisher_user_id: {}", e))?,
             views: item.num_views_all,
+            nsfw_probability: item.nsfw_probability,
+        })
+    }
+}
+
+/// Looks up the nsfw probability of the posts the source didn't score
+///
+/// recsys v2 doesn't send one, posts whose details can't be fetched stay unscored
+async fn score_unscored(posts: &mut [PostItem]) {
+    let canisters: Canisters<false> = use_context().unwrap_or_default();
+    let canisters = &canisters;
+    stream::iter(
+        posts
+            .iter_mut()
+            .filter(|post| post.nsfw_probability.is_none()),
+    )
+    .for_each_concurrent(SCORE_LOOKUP_CONCURRENCY, |post| async move {
+        match canisters
+            .get_post_details_with_nsfw_info(post.canister_id, post.post_id.clone(), None)
+            .await
+        {
+            Ok(details) => post.nsfw_probability = details.map(|details| details.nsfw_probability),
+            Err(e) => leptos::logging::warn!(
+                "failed to score post {}/{}: {e}",
+                post.canister_id,
+                post.post_id
+            ),
+        }
+    })
+    .await;
+}
+
+/// A backend capable of producing recommendations for the ML feed
+#[allow(async_fn_in_trait)]
+pub trait FeedSource {
+    async fn recommendations(&self, req: FeedRequest) -> Result<Vec<PostItem>, FeedError>;
+}
+
+/// The feed source selected at server startup
+///
+/// Provided as context by the server, see [`feed_source`]
+#[derive(Clone)]
+pub enum FeedSourceImpl {
+    Recsys(RecsysFeedSource),
+    Fixture(FixtureFeedSource),
+    Fallback(FallbackFeedSource),
+}
+
+impl Default for FeedSourceImpl {
+    fn default() -> Self {
+        Self::Recsys(RecsysFeedSource::default())
+    }
+}
+
+impl FeedSource for FeedSourceImpl {
+    async fn recommendations(&self, req: FeedRequest) -> Result<Vec<PostItem>, FeedError> {
+        let content = req.content;
+        let exclude: HashSet<String> = req.exclude.iter().cloned().collect();
+        let mut posts = match self {
+            Self::Recsys(source) => source.recommendations(req).await,
+            Self::Fixture(source) => source.recommendations(req).await,
+            Self::Fallback(source) => source.recommendations(req).await,
+        }?;
+        if content == ContentFilter::Clean {
+            score_unscored(&mut posts).await;
+        }
+        // sources are not trusted to honour the content filter or exclusions
+        posts.retain(|post| {
+            content.allows(post.nsfw_probability) && !exclude.contains(&post.video_id)
+        });
+
+        Ok(posts)
+    }
+}
+
+/// Serializable description of a [`FeedSourceImpl`]
+///
+/// e.g `{"type": "fallback", "sources": [{"type": "recsys", "base_url": "http://localhost:8000"}, {"type": "fixture", "path": "./feed.json"}]}`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum FeedSourceConfig {
+    Recsys { base_url: String },
+    Fixture { path: String },
+    Fallback { sources: Vec<FeedSourceConfig> },
+}
+
+impl Default for FeedSourceConfig {
+    fn default() -> Self {
+        Self::Recsys {
+            base_url: RECOMMENDATION_SERVICE_URL.to_string(),
+        }
+    }
+}
+
+#[cfg(feature = "ssr")]
+impl FeedSourceConfig {
+    pub fn build(self) -> Result<FeedSourceImpl, anyhow::Error> {
+        Ok(match self {
+            Self::Recsys { base_url } => FeedSourceImpl::Recsys(RecsysFeedSource::new(base_url)),
+            Self::Fixture { path } => {
+                FeedSourceImpl::Fixture(FixtureFeedSource::from_json_file(path)?)
+            }
+            Self::Fallback { sources } => {
+                let sources = sources
+                    .into_iter()
+                    .map(Self::build)
+                    .collect::<Result<_, _>>()?;
+                FeedSourceImpl::Fallback(FallbackFeedSource::new(sources))
+            }
+        })
+    }
+}
+
+/// The feed source configured for this server
+/// falls back to the default recsys service if none was provided
+#[cfg(feature = "ssr")]
+pub fn feed_source() -> FeedSourceImpl {
+    use leptos::prelude::use_context;
+
+    use_context().unwrap_or_default()
+}
+
+/// Fetch recommendations from the feed source configured on the server
+#[server(endpoint = "ml_feed", input = Json, output = Json)]
+pub async fn get_ml_feed_ssr(req: FeedRequest) -> Result<Vec<PostItem>, FeedError> {
+    feed_source().recommendations(req).await
+}
+
+/// Feed source for use on the client,
+/// proxies every request to the feed source configured on the server
+#[derive(Clone, Copy, Default)]
+pub struct ServerFeedSource;
+
+impl FeedSource for ServerFeedSource {
+    async fn recommendations(&self, req: FeedRequest) -> Result<Vec<PostItem>, FeedError> {
+        get_ml_feed_ssr(req).await
+    }
+}
+
+/// Feed for users without any watch history
+///
+/// Prefers popular posts, and falls back to fresh posts if there are none
+async fn get_ml_feed_coldstart(
+    source: &impl FeedSource,
+    user_id: Principal,
+    num_results: u32,
+    exclude: Vec<String>,
+    content: ContentFilter,
+) -> Result<Vec<PostItem>, FeedError> {
+    let posts = source
+        .recommendations(FeedRequest {
+            user_id,
+            count: num_results,
+            rec_type: RecType::Popularity,
+            content,
+            exclude: exclude.clone(),
         })
+        .await?;
+    if !posts.is_empty() {
+        return Ok(posts);
     }
+
+    source
+        .recommendations(FeedRequest {
+            user_id,
+            count: num_results,
+            rec_type: RecType::Freshness,
+            content,
+            exclude,
+        })
+        .await
 }
 
 pub async fn get_ml_feed_coldstart_clean(
+    source: &impl FeedSource,
     user_id: Principal,
     num_results: u32,
-) -> Result<Vec<PostItem>, anyhow::Error> {
-    get_recommendations(user_id, num_results, RecType::Mixed).await
+    exclude: Vec<String>,
+) -> Result<Vec<PostItem>, FeedError> {
+    get_ml_feed_coldstart(source, user_id, num_results, exclude, ContentFilter::Clean).await
 }
 
 pub async fn get_ml_feed_coldstart_nsfw(
+    source: &impl FeedSource,
     user_id: Principal,
     num_results: u32,
-) -> Result<Vec<PostItem>, anyhow::Error> {
-    get_recommendations(user_id, num_results, RecType::Mixed).await
+    exclude: Vec<String>,
+) -> Result<Vec<PostItem>, FeedError> {
+    get_ml_feed_coldstart(source, user_id, num_results, exclude, ContentFilter::Nsfw).await
 }
 
 pub async fn get_ml_feed_clean(
+    source: &impl FeedSource,
     user_id: Principal,
     num_results: u32,
-) -> Result<Vec<PostItem>, anyhow::Error> {
-    get_recommendations(user_id, num_results, RecType::Mixed).await
+    exclude: Vec<String>,
+    rec_type: RecType,
+) -> Result<Vec<PostItem>, FeedError> {
+    source
+        .recommendations(FeedRequest {
+            user_id,
+            count: num_results,
+            rec_type,
+            content: ContentFilter::Clean,
+            exclude,
+        })
+        .await
 }
 
 pub async fn get_ml_feed_nsfw(
+    source: &impl FeedSource,
     user_id: Principal,
     num_results: u32,
-) -> Result<Vec<PostItem>, anyhow::Error> {
-    get_recommendations(user_id, num_results, RecType::Mixed).await
+    exclude: Vec<String>,
+    rec_type: RecType,
+) -> Result<Vec<PostItem>, FeedError> {
+    source
+        .recommendations(FeedRequest {
+            user_id,
+            count: num_results,
+            rec_type,
+            content: ContentFilter::Nsfw,
+            exclude,
+        })
+        .await
 }
 
-/// Core function to fetch recommendations from the new API
-pub async fn get_recommendations(
-    user_id: Principal,
-    count: u32,
-    rec_type: RecType,
-) -> Result<Vec<PostItem>, anyhow::Error> {
-    const MAX_RETRIES: usize = 5;
-    let client = reqwest::Client::new();
-
-    let url = format!(
-        "{}/v2/recommend-with-metadata/{}",
-        RECOMMENDATION_SERVICE_URL,
-        user_id.to_text()
-    );
-
-    for attempt in 1..=MAX_RETRIES {
-        let response = client
-            .get(&url)
-            .query(&[
-                ("count", count.to_string()),
-                ("rec_type", rec_type.as_str().to_string()),
-            ])
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            leptos::logging::warn!(
-                "FEEDISSUE : ML feed attempt {}/{} failed with status: {}",
-                attempt,
-                MAX_RETRIES,
-                response.status()
-            );
-            if attempt == MAX_RETRIES {
-                return Err(anyhow::anyhow!(format!(
-                    "FEEDISSUE : Error fetching ML feed after {} attempts: {:?}",
-                    MAX_RETRIES,
-                    response.text().await?
-                )));
-            }
-            continue;
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        let response = response.json::<FeedResponseV2>().await?;
-        if !response.videos.is_empty() {
-            leptos::logging::log!(
-                "FEEDISSUE : ML feed succeeded on attempt {}/{}, got {} videos",
-                attempt,
-                MAX_RETRIES,
-                response.videos.len()
-            );
-
-            let posts: Vec<PostItem> = response
-                .videos
-                .into_iter()
-                .filter_map(|v| PostItem::try_from(v).ok())
-                .collect();
-
-            return Ok(posts);
-        }
+    #[test]
+    fn clean_filter_rejects_unscored_posts() {
+        assert!(ContentFilter::Clean.allows(Some(0.1)));
+        assert!(!ContentFilter::Clean.allows(Some(NSFW_PROBABILITY_THRESHOLD)));
+        assert!(!ContentFilter::Clean.allows(None));
+        assert!(ContentFilter::Nsfw.allows(None));
 
-        leptos::logging::warn!(
-            "FEEDISSUE : ML feed attempt {}/{} returned empty results",
-            attempt,
-            MAX_RETRIES
-        );
+        let item: VideoItemV2 = serde_json::from_str(
+            r#"{"video_id": "v", "canister_id": "aaaaa-aa", "post_id": "1",
+                "publisher_user_id": "aaaaa-aa", "num_views_loggedin": 0, "num_views_all": 0}"#,
+        )
+        .unwrap();
+        assert_eq!(item.nsfw_probability, None);
     }
-
-    leptos::logging::error!(
-        "FEEDISSUE : All {} ML feed attempts returned empty results",
-        MAX_RETRIES
-    );
-    Ok(vec![])
 }
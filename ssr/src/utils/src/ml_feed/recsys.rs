@@ -0,0 +1,134 @@
+use super::{
+    retry::{sleep, CircuitBreaker, RetryPolicy},
+    FeedError, FeedRequest, FeedResponseV2, FeedSource, PostItem,
+};
+
+pub const RECOMMENDATION_SERVICE_URL: &str = "https://recsys-on-premise.fly.dev";
+
+/// Feed source backed by the recsys `/v2/recommend-with-metadata` HTTP API
+///
+/// Transient failures are retried with backoff, and the host is skipped
+/// altogether while it keeps failing
+#[derive(Clone)]
+pub struct RecsysFeedSource {
+    client: reqwest::Client,
+    base_url: String,
+    retry: RetryPolicy,
+    breaker: CircuitBreaker,
+}
+
+impl Default for RecsysFeedSource {
+    fn default() -> Self {
+        Self::new(RECOMMENDATION_SERVICE_URL)
+    }
+}
+
+impl RecsysFeedSource {
+    pub fn new(base_url: impl Into<String>) -> Self {
+        let base_url: String = base_url.into();
+        Self {
+            client: reqwest::Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            retry: RetryPolicy::default(),
+            breaker: CircuitBreaker::default(),
+        }
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    async fn fetch_once(
+        &self,
+        url: &str,
+        query: &[(&str, String)],
+    ) -> Result<Vec<PostItem>, FeedError> {
+        let response = self.client.get(url).query(query).send().await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            return Err(FeedError::Upstream {
+                status: status.as_u16(),
+                body: response.text().await.unwrap_or_default(),
+            });
+        }
+
+        let response = response
+            .json::<FeedResponseV2>()
+            .await
+            .map_err(|e| FeedError::Decode(e.to_string()))?;
+        if response.videos.is_empty() {
+            return Err(FeedError::Empty);
+        }
+
+        Ok(response
+            .videos
+            .into_iter()
+            .filter_map(|v| PostItem::try_from(v).ok())
+            .collect())
+    }
+
+    async fn fetch_with_retries(&self, req: &FeedRequest) -> Result<Vec<PostItem>, FeedError> {
+        let url = format!(
+            "{}/v2/recommend-with-metadata/{}",
+            self.base_url,
+            req.user_id.to_text()
+        );
+
+        let mut query = vec![
+            ("count", req.count.to_string()),
+            ("rec_type", req.rec_type.as_str().to_string()),
+            ("content_filter", req.content.as_str().to_string()),
+        ];
+        if !req.exclude.is_empty() {
+            query.push(("exclude", req.exclude.join(",")));
+        }
+
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            match self.fetch_once(&url, &query).await {
+                Ok(posts) => return Ok(posts),
+                Err(e) if e.is_transient() && attempt < self.retry.max_attempts => {
+                    let delay = self.retry.delay(attempt);
+                    leptos::logging::warn!(
+                        "ML feed attempt {attempt}/{} failed: {e}, retrying in {delay:?}",
+                        self.retry.max_attempts
+                    );
+                    sleep(delay).await;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+impl FeedSource for RecsysFeedSource {
+    async fn recommendations(&self, req: FeedRequest) -> Result<Vec<PostItem>, FeedError> {
+        if !self.breaker.allow() {
+            return Err(FeedError::CircuitOpen);
+        }
+
+        match self.fetch_with_retries(&req).await {
+            Ok(posts) => {
+                self.breaker.record_success();
+                Ok(posts)
+            }
+            // the service is up, it just has nothing for this request
+            Err(FeedError::Empty) => {
+                self.breaker.record_success();
+                Ok(vec![])
+            }
+            Err(e) => {
+                if e.is_transient() {
+                    self.breaker.record_failure();
+                } else {
+                    // the host answered, this request was the problem
+                    self.breaker.record_success();
+                }
+                leptos::logging::error!("ML feed request to {} failed: {e}", self.base_url);
+                Err(e)
+            }
+        }
+    }
+}
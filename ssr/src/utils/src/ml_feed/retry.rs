@@ -0,0 +1,176 @@
+use std::{
+    sync::{Arc, LazyLock, Mutex},
+    time::Duration,
+};
+
+use rand::{rngs::SmallRng, Rng, SeedableRng};
+use web_time::Instant;
+
+/// Exponential backoff with jitter
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    pub max_attempts: usize,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(2),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay before retrying after the given (1-indexed) failed attempt
+    ///
+    /// Picked uniformly from the upper half of the exponential delay, so
+    /// clients that failed together don't retry in lockstep
+    pub fn delay(&self, attempt: usize) -> Duration {
+        let exp = 1u32 << attempt.saturating_sub(1).min(16);
+        let delay = self.base_delay.saturating_mul(exp).min(self.max_delay);
+        delay / 2 + delay.mul_f64(jitter() / 2.0)
+    }
+}
+
+/// Shared by every retry, so that concurrent retries draw different delays
+static JITTER_RNG: LazyLock<Mutex<SmallRng>> = LazyLock::new(|| {
+    #[cfg(feature = "ssr")]
+    let rng = SmallRng::from_os_rng();
+    #[cfg(not(feature = "ssr"))]
+    let rng = SmallRng::seed_from_u64(
+        web_time::SystemTime::now()
+            .duration_since(web_time::UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or_default(),
+    );
+    Mutex::new(rng)
+});
+
+/// Number in `[0, 1)`
+fn jitter() -> f64 {
+    JITTER_RNG.lock().unwrap().random()
+}
+
+pub(super) async fn sleep(duration: Duration) {
+    #[cfg(feature = "ssr")]
+    tokio::time::sleep(duration).await;
+    #[cfg(not(feature = "ssr"))]
+    gloo::timers::future::sleep(duration).await;
+}
+
+#[derive(Default)]
+struct BreakerState {
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+    /// start of the request probing the host once the cooldown passed
+    probe_started: Option<Instant>,
+}
+
+/// Stops calling a failing host for `cooldown` after `failure_threshold` consecutive failures
+///
+/// Once the cooldown passes a single probe request is let through (half-open),
+/// its success closes the circuit and its failure re-opens it. A probe that
+/// never reports back is replaced after another `cooldown`
+#[derive(Clone)]
+pub struct CircuitBreaker {
+    state: Arc<Mutex<BreakerState>>,
+    failure_threshold: u32,
+    cooldown: Duration,
+}
+
+impl Default for CircuitBreaker {
+    fn default() -> Self {
+        Self::new(5, Duration::from_secs(30))
+    }
+}
+
+impl CircuitBreaker {
+    pub fn new(failure_threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            state: Arc::default(),
+            failure_threshold,
+            cooldown,
+        }
+    }
+
+    pub fn allow(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+        let Some(open_until) = state.open_until else {
+            return true;
+        };
+        let now = Instant::now();
+        if now < open_until {
+            return false;
+        }
+        let probing = state
+            .probe_started
+            .is_some_and(|started| now < started + self.cooldown);
+        if probing {
+            return false;
+        }
+        state.probe_started = Some(now);
+        true
+    }
+
+    pub fn record_success(&self) {
+        let mut state = self.state.lock().unwrap();
+        *state = BreakerState::default();
+    }
+
+    pub fn record_failure(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.consecutive_failures += 1;
+        if state.consecutive_failures >= self.failure_threshold {
+            state.open_until = Some(Instant::now() + self.cooldown);
+            state.probe_started = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_is_capped() {
+        let policy = RetryPolicy::default();
+        for attempt in 1..=10 {
+            let delay = policy.delay(attempt);
+            assert!(delay <= policy.max_delay);
+            assert!(delay >= policy.base_delay / 2);
+        }
+    }
+
+    #[test]
+    fn breaker_opens_after_threshold() {
+        let breaker = CircuitBreaker::new(2, Duration::from_secs(60));
+        breaker.record_failure();
+        assert!(breaker.allow());
+        breaker.record_failure();
+        assert!(!breaker.allow());
+        breaker.record_success();
+        assert!(breaker.allow());
+    }
+
+    #[test]
+    fn half_open_breaker_lets_one_probe_through() {
+        let breaker = CircuitBreaker::new(1, Duration::from_secs(60));
+        breaker.record_failure();
+        // cooldown is over, only the first caller probes the host
+        breaker.state.lock().unwrap().open_until = Some(Instant::now());
+        assert!(breaker.allow());
+        assert!(!breaker.allow());
+        // a failed probe re-opens the circuit
+        breaker.record_failure();
+        assert!(!breaker.allow());
+        breaker.state.lock().unwrap().open_until = Some(Instant::now());
+        assert!(breaker.allow());
+        breaker.record_success();
+        assert!(breaker.allow());
+        assert!(breaker.allow());
+    }
+}
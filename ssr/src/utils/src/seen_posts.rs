@@ -0,0 +1,151 @@
+use candid::Principal;
+use consts::SEEN_POSTS_STORE;
+use indexmap::IndexSet;
+
+/// Max number of video ids remembered per user
+pub const SEEN_POSTS_CAPACITY: usize = 1000;
+/// Max number of recently seen video ids sent to recsys for exclusion
+pub const SEEN_POSTS_EXCLUDE_LIMIT: usize = 100;
+/// Newly seen videos buffered before the set is written to local storage
+const SEEN_POSTS_PERSIST_BATCH: usize = 10;
+
+/// Capped LRU of video ids that were already shown to a user
+///
+/// Persisted in local storage per user principal, so that reloading
+/// the page doesn't replay the same videos
+///
+/// writes are batched, call [`SeenPosts::persist`] to flush the rest
+/// (e.g when the page is hidden)
+#[derive(Clone, Debug, Default)]
+pub struct SeenPosts {
+    owner: Option<Principal>,
+    ids: IndexSet<String>,
+    /// videos seen since the last write
+    unsaved: usize,
+}
+
+impl SeenPosts {
+    fn storage_key(owner: Principal) -> String {
+        format!("{SEEN_POSTS_STORE}-{owner}")
+    }
+
+    /// Load the seen set of `owner`
+    /// always empty on the server
+    pub fn load(owner: Principal) -> Self {
+        #[cfg(feature = "hydrate")]
+        let ids = leptos::prelude::window()
+            .local_storage()
+            .ok()
+            .flatten()
+            .and_then(|store| store.get_item(&Self::storage_key(owner)).ok().flatten())
+            .and_then(|raw| serde_json::from_str::<Vec<String>>(&raw).ok())
+            .map(IndexSet::from_iter)
+            .unwrap_or_default();
+        #[cfg(not(feature = "hydrate"))]
+        let ids = IndexSet::new();
+
+        Self {
+            owner: Some(owner),
+            ids,
+            unsaved: 0,
+        }
+    }
+
+    /// (Re)load the seen set if it doesn't belong to `owner`
+    pub fn ensure_owner(&mut self, owner: Principal) {
+        if self.owner != Some(owner) {
+            self.persist();
+            *self = Self::load(owner);
+        }
+    }
+
+    pub fn contains(&self, video_id: &str) -> bool {
+        self.ids.contains(video_id)
+    }
+
+    /// Mark a video as seen, evicting the least recently seen ones over capacity
+    /// returns true if the video was not seen before
+    pub fn insert(&mut self, video_id: String) -> bool {
+        let fresh = !self.ids.shift_remove(&video_id);
+        self.ids.insert(video_id);
+        while self.ids.len() > SEEN_POSTS_CAPACITY {
+            self.ids.shift_remove_index(0);
+        }
+        if fresh {
+            self.unsaved += 1;
+        }
+        fresh
+    }
+
+    /// Whether enough videos were seen since the last write to persist again
+    pub fn persist_due(&self) -> bool {
+        self.unsaved >= SEEN_POSTS_PERSIST_BATCH
+    }
+
+    /// Most recently seen video ids, newest first
+    pub fn recent(&self, limit: usize) -> Vec<String> {
+        self.ids.iter().rev().take(limit).cloned().collect()
+    }
+
+    /// Write the seen set back to local storage, if it changed
+    pub fn persist(&mut self) {
+        if self.unsaved == 0 {
+            return;
+        }
+        self.unsaved = 0;
+        #[cfg(feature = "hydrate")]
+        {
+            let Some(owner) = self.owner else {
+                return;
+            };
+            let Ok(Some(store)) = leptos::prelude::window().local_storage() else {
+                return;
+            };
+            let ids: Vec<&String> = self.ids.iter().collect();
+            let Ok(raw) = serde_json::to_string(&ids) else {
+                return;
+            };
+            if let Err(e) = store.set_item(&Self::storage_key(owner), &raw) {
+                leptos::logging::warn!("failed to persist seen posts: {e:?}");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evicts_least_recently_seen() {
+        let mut seen = SeenPosts::default();
+        for i in 0..SEEN_POSTS_CAPACITY {
+            assert!(seen.insert(i.to_string()));
+        }
+        // touching "0" makes "1" the oldest entry
+        assert!(!seen.insert("0".into()));
+        seen.insert("new".into());
+
+        assert!(seen.contains("0"));
+        assert!(!seen.contains("1"));
+        assert!(seen.contains("new"));
+        assert_eq!(seen.recent(2), vec!["new".to_string(), "0".to_string()]);
+    }
+
+    #[test]
+    fn writes_are_batched() {
+        let mut seen = SeenPosts::default();
+        for i in 1..SEEN_POSTS_PERSIST_BATCH {
+            seen.insert(i.to_string());
+            assert!(!seen.persist_due());
+        }
+        // re-seeing a video doesn't count as a change
+        seen.insert("1".into());
+        assert!(!seen.persist_due());
+        seen.insert("new".into());
+        assert!(seen.persist_due());
+
+        seen.persist();
+        assert!(!seen.persist_due());
+    }
+}
@@ -2,24 +2,28 @@ use speedate::{DateTime, ParseError};
 use uts2ts::uts2ts;
 use web_time::Duration;
 
+use crate::i18n::Locale;
+
 /// Get day & month -> DD MMM format
 /// where DD -> 2 digits
 /// MMM -> String representing the month. i.e AUG for august
-pub fn get_day_month(epoch_secs: u64) -> String {
+/// other locales use the full month name
+pub fn get_day_month(epoch_secs: u64, locale: Locale) -> String {
     let ts = uts2ts(epoch_secs as i64);
-    let month = match ts.month {
-        1 => "JAN",
-        2 => "FEB",
-        3 => "MARCH",
-        4 => "APRIL",
-        5 => "MAY",
-        6 => "JUNE",
-        7 => "JULY",
-        8 => "AUG",
-        9 => "SEPT",
-        10 => "OCT",
-        11 => "NOV",
-        12 => "DEC",
+    let month = match (locale, ts.month) {
+        (Locale::En, 1) => "JAN",
+        (Locale::En, 2) => "FEB",
+        (Locale::En, 3) => "MARCH",
+        (Locale::En, 4) => "APRIL",
+        (Locale::En, 5) => "MAY",
+        (Locale::En, 6) => "JUNE",
+        (Locale::En, 7) => "JULY",
+        (Locale::En, 8) => "AUG",
+        (Locale::En, 9) => "SEPT",
+        (Locale::En, 10) => "OCT",
+        (Locale::En, 11) => "NOV",
+        (Locale::En, 12) => "DEC",
+        (_, month @ 1..=12) => locale.month_name(month as u8),
         _ => unreachable!(),
     };
     format!("{:02} {month}", ts.day)
@@ -47,38 +51,32 @@ pub async fn sleep(duration: Duration) {
     }
 }
 
-pub fn parse_ns_to_datetime(timestamp: u64) -> Result<String, ParseError> {
+pub fn parse_ns_to_datetime(timestamp: u64, locale: Locale) -> Result<String, ParseError> {
     DateTime::from_timestamp(
         (timestamp / 1_000_000_000) as i64,           // seconds
         ((timestamp % 1_000_000_000) / 1_000) as u32, // microseconds
     )
     .map(|dt| {
-        format!(
-            "{} {}, {} {:02}:{:02} {}",
-            match dt.date.month {
-                1 => "January",
-                2 => "February",
-                3 => "March",
-                4 => "April",
-                5 => "May",
-                6 => "June",
-                7 => "July",
-                8 => "August",
-                9 => "September",
-                10 => "October",
-                11 => "November",
-                12 => "December",
-                _ => unimplemented!(),
-            },
-            dt.date.day,
-            dt.date.year,
-            if dt.time.hour > 12 {
-                dt.time.hour - 12
-            } else {
-                dt.time.hour
-            },
-            dt.time.minute,
-            if dt.time.hour >= 12 { "PM" } else { "AM" },
-        )
+        let month = locale.month_name(dt.date.month);
+        let hour_12 = if dt.time.hour > 12 {
+            dt.time.hour - 12
+        } else {
+            dt.time.hour
+        };
+        let meridiem = if dt.time.hour >= 12 { "PM" } else { "AM" };
+        match locale {
+            Locale::En => format!(
+                "{month} {}, {} {hour_12:02}:{:02} {meridiem}",
+                dt.date.day, dt.date.year, dt.time.minute,
+            ),
+            Locale::Hi => format!(
+                "{} {month} {} {hour_12:02}:{:02} {meridiem}",
+                dt.date.day, dt.date.year, dt.time.minute,
+            ),
+            Locale::Es => format!(
+                "{} de {month} de {} {:02}:{:02}",
+                dt.date.day, dt.date.year, dt.time.hour, dt.time.minute,
+            ),
+        }
     })
 }
@@ -0,0 +1,42 @@
+const SW_URL = "/video-cache-sw.js";
+
+let registration = null;
+
+// the worker only serves requests to `streamBase`
+async function activeWorker(streamBase) {
+  if (!("serviceWorker" in navigator) || !("caches" in window)) {
+    throw new Error("service workers are not supported");
+  }
+  if (!registration) {
+    const url = `${SW_URL}?stream_base=${encodeURIComponent(streamBase)}`;
+    registration = navigator.serviceWorker
+      .register(url, { scope: "/" })
+      .then(() => navigator.serviceWorker.ready)
+      .catch((err) => {
+        registration = null;
+        throw err;
+      });
+  }
+  const reg = await registration;
+  if (!reg.active) {
+    throw new Error("video cache worker is not active");
+  }
+  return reg.active;
+}
+
+export async function prefetchVideo(streamBase, uid, videoUrl, prefixBytes, budgetBytes) {
+  const worker = await activeWorker(streamBase);
+  return new Promise((resolve, reject) => {
+    const channel = new MessageChannel();
+    channel.port1.onmessage = (ev) => {
+      if (ev.data.ok) {
+        resolve(ev.data.bytes);
+      } else {
+        reject(new Error(ev.data.error));
+      }
+    };
+    worker.postMessage({ type: "prefetch", uid, videoUrl, prefixBytes, budgetBytes }, [
+      channel.port2,
+    ]);
+  });
+}
@@ -0,0 +1,108 @@
+//! Prefetches the start of upcoming feed videos (the first bytes of the mp4
+//! the player loads) into Cache Storage through the `video-cache-sw.js`
+//! service worker, which serves the player's range requests from it
+use indexmap::IndexMap;
+use leptos::prelude::*;
+
+#[cfg(feature = "hydrate")]
+use wasm_bindgen::prelude::*;
+
+use crate::ml_feed::QuickPostDetails;
+
+#[cfg(feature = "hydrate")]
+#[wasm_bindgen(module = "/src/video_prefetch/inline-js/video-prefetch-inline.js")]
+extern "C" {
+    #[wasm_bindgen(catch, js_name = prefetchVideo)]
+    async fn prefetch_video(
+        stream_base: &str,
+        uid: &str,
+        video_url: &str,
+        prefix_bytes: f64,
+        budget_bytes: f64,
+    ) -> Result<JsValue, JsValue>;
+}
+
+/// Number of upcoming videos to warm ahead of the current one
+pub const VIDEO_PREFETCH_AHEAD: usize = 3;
+/// Bytes fetched from the start of each video, enough for the first seconds
+pub const VIDEO_PREFETCH_PREFIX_BYTES: u64 = 1024 * 1024;
+/// Size budget of the prefetch cache, least recently used videos are evicted past this
+pub const VIDEO_PREFETCH_BUDGET_BYTES: u64 = 64 * 1024 * 1024;
+/// Number of videos whose prefetch state is remembered
+const PREFETCH_STATE_CAPACITY: usize = 32;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PrefetchState {
+    #[default]
+    Idle,
+    Pending,
+    Ready,
+    Failed,
+}
+
+#[derive(Clone, Copy, Default)]
+pub struct VideoPrefetchCtx {
+    states: RwSignal<IndexMap<String, PrefetchState>>,
+}
+
+impl VideoPrefetchCtx {
+    /// Prefetch state of the video with `uid`, tracked
+    pub fn state(&self, uid: &str) -> PrefetchState {
+        self.states
+            .with(|s| s.get(uid).copied())
+            .unwrap_or_default()
+    }
+
+    /// Warms the given posts into the video cache, posts that were already
+    /// prefetched (or are being prefetched) are skipped
+    pub fn prefetch(&self, posts: impl IntoIterator<Item = QuickPostDetails>) {
+        for post in posts {
+            let uid = post.video_uid;
+            let mut started = false;
+            self.states.update(|s| {
+                if matches!(
+                    s.get(&uid),
+                    Some(PrefetchState::Pending | PrefetchState::Ready)
+                ) {
+                    return;
+                }
+                s.shift_remove(&uid);
+                s.insert(uid.clone(), PrefetchState::Pending);
+                while s.len() > PREFETCH_STATE_CAPACITY {
+                    s.shift_remove_index(0);
+                }
+                started = true;
+            });
+            if !started {
+                continue;
+            }
+
+            #[cfg(feature = "hydrate")]
+            {
+                let states = self.states;
+                wasm_bindgen_futures::spawn_local(async move {
+                    let res = prefetch_video(
+                        consts::CF_STREAM_BASE,
+                        &uid,
+                        &crate::mp4_url(&uid),
+                        VIDEO_PREFETCH_PREFIX_BYTES as f64,
+                        VIDEO_PREFETCH_BUDGET_BYTES as f64,
+                    )
+                    .await;
+                    let state = match res {
+                        Ok(_) => PrefetchState::Ready,
+                        Err(e) => {
+                            log::warn!("failed to prefetch video {uid}: {e:?}");
+                            PrefetchState::Failed
+                        }
+                    };
+                    states.try_update(|s| {
+                        if let Some(cur) = s.get_mut(&uid) {
+                            *cur = state;
+                        }
+                    });
+                });
+            }
+        }
+    }
+}
@@ -84,3 +84,30 @@ pub async fn paste_from_clipboard() -> Option<String> {
         None
     }
 }
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MobilePlatform {
+    Android,
+    Ios,
+}
+
+impl MobilePlatform {
+    pub fn from_user_agent(user_agent: &str) -> Option<Self> {
+        if user_agent.contains("Android") {
+            Some(Self::Android)
+        } else if ["iPhone", "iPad", "iPod"]
+            .iter()
+            .any(|device| user_agent.contains(device))
+        {
+            Some(Self::Ios)
+        } else {
+            None
+        }
+    }
+}
+
+/// Mobile platform of the browser, None on desktops and during SSR
+pub fn mobile_platform() -> Option<MobilePlatform> {
+    let user_agent = use_window().navigator()?.user_agent().ok()?;
+    MobilePlatform::from_user_agent(&user_agent)
+}